@@ -225,7 +225,7 @@ function clear2D() {
 const coordsEl = document.getElementById('coords');
 
 function onMouseMove(e) {
-    if (!fromCanvas || exampleClassDropdown.value === '3d_examples') {
+    if (!fromCanvas || exampleClassDropdown.value === '3d_examples' || document.getElementById('shape-sphere').checked) {
         coordsEl.textContent = '';
         return;
     }
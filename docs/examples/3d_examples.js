@@ -1,3 +1,5 @@
+import { initial_conditions } from '../wasm/three_body_wasm.js';
+
 let data = null;
 
 async function loadTSV(url) {
@@ -37,8 +39,6 @@ async function getOrbit(i) {
     }
     const row = data[i];
     const m = row[0].match(/^[^\()]+\(([^)]+)\)$/);
-    const m1 = 1;
-    const m2 = 1;
     const m3 = parseFloat(m[1]);
 
     const z0 = parseFloat(row[1]);
@@ -48,11 +48,7 @@ async function getOrbit(i) {
     const t = parseFloat(row[5]);
 
 
-    return [[
-        -1, 0, 0, v_x, v_y, v_z, m1,
-        1, 0, 0, v_x, v_y, -v_z, m2,
-        0, 0, z0, -2 * v_x / m3, -2 * v_y / m3, 0, m3
-    ], t]
+    return [Array.from(initial_conditions('li_liao_3d', [z0, v_x, v_y, v_z, m3])), t];
 }
 
 export default {
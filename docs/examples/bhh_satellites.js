@@ -1,3 +1,5 @@
+import { initial_conditions } from '../wasm/three_body_wasm.js';
+
 // Broucke–Hadjidemetriou–Hénon (BHH) 
 // 
 // Table 3 presets
@@ -22,22 +24,7 @@ const 𝜃4_max = 0.0105056462558377;
 
 // Helper to build an orbit tuple: [state, t, θ_max]
 function buildOrbit({ m1, m2 = 1, m3, x1, v1, v3, t, 𝜃}) {
-    const v2 = -(m1 * v1 + m3 * v3) / m2;
-    const xcm = (m1 * x1 + m2 * 0 + m3 * 1) / (m1 + m2 + m3);
-    const x1c = x1 - xcm;
-    const x2c = -xcm;
-    const x3c = 1 - xcm;
-
-    return [
-        [
-            x1c, 0, 0, 0, v1, 0, m1,
-            x2c, 0, 0, 0, v2, 0, m2,
-            x3c, 0, 0, 0, v3, 0, m3
-        ],
-        t,
-        𝜃,
-    ];
-
+    return [Array.from(initial_conditions('bhh', [m1, m2, m3, x1, v1, v3])), t, 𝜃];
 }
 
 const DATA = [
@@ -1,3 +1,5 @@
+import { initial_conditions } from '../wasm/three_body_wasm.js';
+
 // Name ˙x1(0) ˙y1(0) λ T Θ(rad) 〈P 〉
 // Moore’s figure eight 0.216 343 0.332 029 2.574 29 26.128 0.245 57 1.35 × 100
 // Simo’s figure eight 0.211 139 0.333 568 2.583 87 26.127 0.277 32 1.36 × 100
@@ -410,28 +412,9 @@ function getNames() {
 }
 
 function getOrbit(index) {
-    // c1, c2, c3, c4 and c5.
-    // x1 = −2c1
-    // x2 = c1
-    // x3 = c1
-    // y1 = 0
-    // y2 = c2
-    // y3 = −c2
-    // u1 = 0
-    // u2 = c3
-    // u3 = −c3
-    // v1 = −2c4
-    // v2 = c4
-    // v3 = c4
-    // m1 = m2 = m3 = 1/3
-    // T = c5 * 3
+    // c1..c4 set the bodies (masses 1/3), see three_body::ic::choreography; T = c5 * 3
     const [c1, c2, c3, c4, c5] = data[index];
-    return [
-        [-2 * c1, 0, 0, 0, -2 * c4, 0, 1 / 3,
-         c1, c2, 0, c3, c4, 0, 1 / 3,
-         c1, -c2, 0, -c3, c4, 0, 1 / 3],
-        c5, 0
-    ];
+    return [Array.from(initial_conditions('choreography', [c1, c2, c3, c4])), c5, 0];
 }
 
 function getName() {
@@ -1,3 +1,5 @@
+import { initial_conditions } from '../wasm/three_body_wasm.js';
+
 // Class and number,v1,v2,T],
 const ic = [["I.Ai.c.1", 0.3471168881, 0.5327249454, 6.3259139829],
 ["I.Ai.c.2", 0.3068934205, 0.125506567, 6.2346748391],
@@ -704,11 +706,7 @@ function getOrbit(i) {
     const v2 = data[2];
     const t = data[3];
 
-    return [
-        [-1.0, 0.0, 0.0, v1, v2, 0.0, 1.0,
-          0.0, 0.0, 0.0, -2 * v1, -2 * v2, 0.0, 1,
-          1.0, 0.0, 0.0, v1, v2, 0.0, 1.0]
-        , t];
+    return [Array.from(initial_conditions('suvakov', [v1, v2, 1])), t];
 }
 
 function getNames() {
@@ -1,3 +1,5 @@
+import { initial_conditions } from '../wasm/three_body_wasm.js';
+
 const ic = [["F1(1,1,1)", 0.0207067154, 0.3133550361, 2.1740969264],
 ["F2(1,1,1)", 0.2053886532, 0.1952668419, 1.6896364928],
 ["F3(1,1,1)", 0.056266428, 0.4691503375, 4.5419125588],
@@ -324,12 +326,7 @@ function getOrbit(i) {
     const t = data[3];
     const m = data[0].match(/^[A-Za-z]+\d*\(\s*([^)]*?)\s*\)$/);
     const [m1, m3, m2] = m[1].split(/\s*,\s*/).map(parseFloat);
-    return [
-        [-0.5, 0, 0, 0, 0, 0, m1,
-            x, y, 0, 0, 0, 0, m2,
-            0.5, 0, 0, 0, 0, 0, m3],
-        t
-    ];
+    return [Array.from(initial_conditions('free_fall', [x, y, m1, m2, m3])), t];
 }
 
 function getNames() {
@@ -1,3 +1,5 @@
+import { initial_conditions } from '../wasm/three_body_wasm.js';
+
 // https://db2.fmi.uni-sofia.bg/3bodyfree/
 
 let data = null;
@@ -29,20 +31,11 @@ async function getOrbit(i) {
         await loadTSV('./examples/sol_80_600.csv');
     }
     const row = data[i];
-    const m1 = 1;
-    const m2 = 1;
-    const m3 = 1;
-
     const x = parseFloat(row[0]);
     const y = parseFloat(row[1]);
     const t = parseFloat(row[2]);
 
-
-    return [[
-        -0.5, 0, 0, 0.0, 0.0, 0.0, m1,
-        x, y, 0, 0.0, 0.0, 0.0, m2,
-        0.5, 0, 0.0, 0.0, 0.0, 0, m3
-    ], t]
+    return [Array.from(initial_conditions('free_fall', [x, y, 1, 1, 1])), t];
 }
 
 export default {
@@ -1,3 +1,5 @@
+import { initial_conditions } from '../wasm/three_body_wasm.js';
+
 // https://numericaltank.sjtu.edu.cn/three-body/three-body-unequal-mass-movies.htm
 
 // [Class number,v1,v2,T,mass],
@@ -1358,11 +1360,7 @@ function getOrbit(i) {
     const t = data[3];
     const m = data[4];
 
-    return [
-        [-1.0, 0.0, 0.0, v1, v2, 0.0, 1.0,
-            0.0, 0.0, 0.0, -2 * v1 / m, -2 * v2 / m, 0.0, m,
-            1.0, 0.0, 0.0, v1, v2, 0.0, 1.0]
-        , t];
+    return [Array.from(initial_conditions('suvakov', [v1, v2, m])), t];
 }
 
 function getNames() {
@@ -9,6 +9,11 @@ import free_fall_puzynin_600 from './examples/free_fall_puzynin_600.js';
 import bhh_satellites from './examples/bhh_satellites.js';
 import broucke_boggs from './examples/broucke_boggs.js';
 import matt_sheen from './examples/matt_sheen.js';
+import init from './wasm/three_body_wasm.js';
+
+// The main thread uses the wasm bindings too (the example lists' initial conditions and
+// period estimates of custom examples)
+await init();
 
 const exampleClassDropdown = document.getElementById('example-class');
 const exampleSelect = document.getElementById('example');
@@ -1,10 +1,16 @@
 import { readIC2D, fill_example_dropdown } from "./ic.js";
+import { estimate_periods } from "./wasm/three_body_wasm.js";
 
 const newExBtn = document.getElementById('new-example');
 const dlg = document.getElementById('new-example-dialog');
 const cancelBtn = document.getElementById('cancel-new-ex');
 const createBtn = document.getElementById('create-new-ex');
 const deleteBtn = document.getElementById('delete-new-ex');
+const estimateBtn = document.getElementById('estimate-period');
+const estimateStatus = document.getElementById('estimate-status');
+
+// Horizon of the period search
+const ESTIMATE_T_MAX = 50;
 
 const exampleSelect = document.getElementById('example');
 const exampleClassDropdown = document.getElementById('example-class');
@@ -32,6 +38,7 @@ function fillInputsFromIC(data) {
   }
   document.getElementById('modal-period').value = t;
   document.getElementById('modal-angle').value = theta_max || 0;
+  estimateStatus.textContent = '';
 }
 
 deleteBtn.addEventListener('click', async () => {
@@ -76,19 +83,51 @@ function num(id) {
   return x;
 }
 
-// Collect ICs from the form
-function collectICs() {
-  const name = document.getElementById('ex-name').value.trim() || 'Custom example';
+function isEmpty(id) {
+  return document.getElementById(`modal-${id}`).value.trim() === '';
+}
 
-  const bodies = [[
+function readState() {
+  return [
       num('x1'), num('y1'), num('z1'), num('vx1'), num('vy1'), num('vz1'), num('m1'),
       num('x2'), num('y2'), num('z2'), num('vx2'), num('vy2'), num('vz2'), num('m2'),
       num('x3'), num('y3'), num('z3'), num('vx3'), num('vy3'), num('vz3'), num('m3'),
-  ], num('period'), num('angle')];
+  ];
+}
+
+// Best closest return of the state (up to a rotation about z): [period, angle]
+function estimatePeriod(state) {
+  const [period, angle, distance] = estimate_periods(new Float64Array(state), ESTIMATE_T_MAX, true, 1);
+  if (period === undefined) {
+    throw new Error(`No return found up to t = ${ESTIMATE_T_MAX}`);
+  }
+  document.getElementById('modal-period').value = period;
+  document.getElementById('modal-angle').value = angle;
+  estimateStatus.textContent = `closure ${distance.toExponential(1)}`;
+  return [period, angle];
+}
+
+// Collect ICs from the form; a missing period is estimated
+function collectICs() {
+  const name = document.getElementById('ex-name').value.trim() || 'Custom example';
+
+  const state = readState();
+  const [period, angle] = isEmpty('period')
+    ? estimatePeriod(state)
+    : [num('period'), isEmpty('angle') ? 0 : num('angle')];
+  const bodies = [state, period, angle];
 
   return { name, bodies };
 }
 
+estimateBtn.addEventListener('click', () => {
+  try {
+    estimatePeriod(readState());
+  } catch (err) {
+    estimateStatus.textContent = err?.message || String(err);
+  }
+});
+
 // Create & insert a new option into the example select
 async function addCustomExample({ name, bodies }) {
   const storage = localStorage.getItem("examples");
@@ -103,7 +142,13 @@ async function addCustomExample({ name, bodies }) {
 
 createBtn.addEventListener('click', async (e) => {
   e.preventDefault();
-  const ics = collectICs();
+  let ics;
+  try {
+    ics = collectICs();
+  } catch (err) {
+    estimateStatus.textContent = err?.message || String(err);
+    return;
+  }
   await addCustomExample(ics);
   dlg.close();
 });
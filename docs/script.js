@@ -2,6 +2,7 @@
 import { clear2D, draw2D } from './2d.js';
 import { draw3D } from './3d.js';
 import { fill_example_dropdown, readIC2D } from './ic.js';
+import { drawShapeSphere } from './shape_sphere.js';
 import { reshapeResultToPaths } from './util.js';
 
 
@@ -34,11 +35,14 @@ const bookmarkBtn = document.getElementById('bookmark');
 const cycleThroughBtn = document.getElementById('cycle-through');
 const stopCycleThroughBtn = document.getElementById('stop-cycle-through');
 const removeBookmarkBtn = document.getElementById('remove-bookmark');
+const shapeSphereCheckbox = document.getElementById('shape-sphere');
 
 // Runtime
 let paths = [[], [], []];
 let masses = [1, 1, 1];
 let times = [];
+let sphere = [];       // shape-sphere curve, [n1, n2, n3, t] per step
+let sphereMarks = [];  // L+, L- and the three collision points
 let energy = 0;
 let angularMomentum = [0, 0, 0];
 let period = 0;
@@ -113,7 +117,7 @@ async function computeExample() {
         // Post to worker; transfer the IC buffer (we won't need it again on main)
         simWorker.postMessage({ requestId, ic, t, method }, [ic.buffer]);
 
-        const { result, energy: E, angularMomentum: L, computeMs } = await resultPromise;
+        const { result, sphere: S, sphereMarks: marks, energy: E, angularMomentum: L, computeMs } = await resultPromise;
 
         // Ignore stale responses (if the user changed selection mid-flight)
         if (requestId !== latestRequestId) {
@@ -122,6 +126,8 @@ async function computeExample() {
 
         energy = E;
         angularMomentum = Array.from(L);
+        sphere = S;
+        sphereMarks = Array.from(marks);
 
 
         const x = reshapeResultToPaths(result);
@@ -146,7 +152,9 @@ async function computeExample() {
 }
 
 function draw() {
-    if (exampleClassDropdown.value === '3d_examples') {
+    if (shapeSphereCheckbox.checked) {
+        drawShapeSphere(sphere, sphereMarks, masses, energy, angularMomentum, period);
+    } else if (exampleClassDropdown.value === '3d_examples') {
         draw3D(paths, times, masses, energy, angularMomentum, period);
     } else {
         draw2D(paths, times, masses, energy, angularMomentum, period, 𝜃_max);
@@ -254,6 +262,8 @@ pauseButton.addEventListener('click', () => {
 
 timeSlider.addEventListener('input', draw);
 
+shapeSphereCheckbox.addEventListener('change', draw);
+
 exampleClassDropdown.addEventListener('change', async () => {
     await fill_example_dropdown();
     updateUrl();
@@ -0,0 +1,96 @@
+import { resizeCanvas } from "./common.js";
+import { drawLegend } from "./common.js";
+
+// Montgomery's shape sphere: the triangle of the three bodies up to translation,
+// rotation and scale. n₃ (the signed area) points up, so the equator is the circle of
+// collinear configurations. The curve comes from the wasm `shape_sphere` binding and the
+// marked points from `shape_sphere_points`.
+
+const canvas = document.getElementById('plot');
+const ctx = canvas.getContext('2d');
+const timeSlider = document.getElementById('control-time');
+
+const MAX_POINTS = 10_000;
+const CIRCLE_POINTS = 180;
+const YAW = 0.5;
+const PITCH = 0.3;
+
+function reshapeSphere(curve) {
+    // curve is [n1, n2, n3, t] per step
+    const stepCount = curve.length / 4;
+    const bigStep = Math.max(1, Math.floor(stepCount / MAX_POINTS));
+    const points = [];
+    const times = [];
+    for (let s = 0; s < stepCount; s += bigStep) {
+        points.push([curve[4 * s], curve[4 * s + 1], curve[4 * s + 2]]);
+        times.push(curve[4 * s + 3]);
+    }
+    return { points, times };
+}
+
+// Orthographic view of the unit sphere, turned by YAW about n₃ and tilted by PITCH
+function makeProjector() {
+    const w = canvas.clientWidth, h = canvas.clientHeight;
+    const scale = 0.42 * Math.min(w, h);
+    const [cy, sy] = [Math.cos(YAW), Math.sin(YAW)];
+    const [cp, sp] = [Math.cos(PITCH), Math.sin(PITCH)];
+    return ([n1, n2, n3]) => {
+        const x = cy * n1 - sy * n2;
+        const depth = sy * n1 + cy * n2;
+        const up = cp * n3 - sp * depth;
+        return [w / 2 + scale * x, h / 2 - scale * up];
+    };
+}
+
+function strokeCurve(toCanvas, points) {
+    ctx.beginPath();
+    points.forEach((n, k) => {
+        const [x, y] = toCanvas(n);
+        if (k === 0) ctx.moveTo(x, y); else ctx.lineTo(x, y);
+    });
+    ctx.stroke();
+}
+
+function circle(f) {
+    return Array.from({ length: CIRCLE_POINTS + 1 }, (_, k) => f(2 * Math.PI * k / CIRCLE_POINTS));
+}
+
+function drawShapeSphere(curve, marks, masses, energy, angularMomentum, period) {
+    resizeCanvas();
+    drawLegend(masses, energy, angularMomentum, period);
+    ctx.clearRect(0, 0, canvas.clientWidth, canvas.clientHeight);
+    const toCanvas = makeProjector();
+
+    // Equator (collinear configurations) and two meridians
+    ctx.lineWidth = 1.0;
+    ctx.strokeStyle = 'rgba(203,213,225,0.85)';
+    strokeCurve(toCanvas, circle(a => [Math.cos(a), Math.sin(a), 0]));
+    ctx.strokeStyle = 'rgba(203,213,225,0.35)';
+    strokeCurve(toCanvas, circle(a => [Math.cos(a), 0, Math.sin(a)]));
+    strokeCurve(toCanvas, circle(a => [0, Math.cos(a), Math.sin(a)]));
+
+    const { points, times } = reshapeSphere(curve);
+    if (points.length === 0) {
+        return;
+    }
+    const factor = parseFloat(timeSlider.value) / parseFloat(timeSlider.max);
+    const fullPaths = document.getElementById("full-paths").checked;
+    let k = times.findIndex(t => t > factor * period);
+    k = k < 0 ? points.length - 1 : Math.max(0, k - 1);
+    ctx.lineWidth = 2.0;
+    ctx.strokeStyle = '#3498db';
+    strokeCurve(toCanvas, fullPaths ? points : points.slice(0, k + 1));
+    const [ex, ey] = toCanvas(points[k]);
+    ctx.beginPath(); ctx.arc(ex, ey, 5, 0, Math.PI * 2); ctx.fillStyle = '#3498db'; ctx.fill();
+
+    // L+, L-, then the collisions r1 = r2, r2 = r3 and r3 = r1
+    ctx.font = '14px sans-serif';
+    ['L+', 'L-', '12', '23', '31'].forEach((name, i) => {
+        const [x, y] = toCanvas(marks.slice(3 * i, 3 * i + 3));
+        ctx.fillStyle = i < 2 ? '#2ecc71' : '#e74c3c';
+        ctx.beginPath(); ctx.arc(x, y, 4, 0, Math.PI * 2); ctx.fill();
+        ctx.fillText(name, x + 8, y - 8);
+    });
+}
+
+export { drawShapeSphere };
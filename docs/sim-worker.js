@@ -1,4 +1,10 @@
-import init, { evolve, total_energy, total_angular_momentum } from './wasm/three_body_wasm.js';
+import init, {
+    evolve,
+    shape_sphere,
+    shape_sphere_points,
+    total_energy,
+    total_angular_momentum,
+} from './wasm/three_body_wasm.js';
 
 await init();
 
@@ -17,6 +23,8 @@ self.onmessage = async (evt) => {
         const energy = total_energy(icArr);
         const angularMomentum = total_angular_momentum(icArr); // returns Float64Array(3) or Array(3)
         const result = evolve(icArr, t, method);               // flat array/typed-array from WASM
+        const sphere = shape_sphere(result, icArr);            // [n1, n2, n3, t] per step
+        const sphereMarks = shape_sphere_points(new Float64Array([icArr[6], icArr[13], icArr[20]]));
         const t1 = performance.now();
 
         // Prefer transferring buffers to avoid copies
@@ -25,6 +33,11 @@ self.onmessage = async (evt) => {
         if (result?.buffer instanceof ArrayBuffer) {
             transfers.push(result.buffer);
         }
+        for (const arr of [sphere, sphereMarks]) {
+            if (arr?.buffer instanceof ArrayBuffer) {
+                transfers.push(arr.buffer);
+            }
+        }
         let angMomPayload = angularMomentum;
         if (angularMomentum?.buffer instanceof ArrayBuffer) {
             transfers.push(angularMomentum.buffer);
@@ -35,6 +48,8 @@ self.onmessage = async (evt) => {
                 type: 'ok',
                 requestId,
                 result: resultPayload,
+                sphere,
+                sphereMarks,
                 energy,
                 angularMomentum: angMomPayload,
                 computeMs: t1 - t0,
@@ -0,0 +1,344 @@
+// Shared adaptive driver for the embedded Runge–Kutta pairs.
+//
+// Every adaptive method (DOP853, DOPRI5, Bogacki–Shampine, Cash–Karp, Feagin14)
+// implements `EmbeddedPair`; the accept/reject loop, the initial step selection
+// and the step-size controller live here so they behave the same for all of them.
+
+use crate::types::Body;
+
+/// An embedded pair advancing a packed state vector.
+pub(crate) trait EmbeddedPair {
+    /// Order q used by the controller: the step factor scales like err^(-1/(q+1)).
+    fn order(&self) -> f64;
+
+    /// Attempt a step of size `h` from the current state.
+    /// Returns the weighted RMS error norm; the step is acceptable if it is <= 1.
+    fn try_step(&mut self, h: f64, rtol: f64, atol: f64) -> f64;
+
+    /// Make the last trial step the current state.
+    fn accept(&mut self);
+
+    /// Current state, rounded to f64 (used for the initial step selection).
+    fn state_f64(&self) -> Vec<f64>;
+
+    /// Right-hand side f(y) in f64 (used for the initial step selection).
+    fn rhs_f64(&self, y: &[f64]) -> Vec<f64>;
+
+    /// Append the current position of every body to `out`.
+    fn push_positions(&self, out: &mut Vec<f64>);
+
+    /// Copy the current state back into `bodies`.
+    fn unpack(&self, bodies: &mut [Body]);
+}
+
+/// Step-size control strategy.
+///
+/// With k = q + 1 and err_n the error norm of step n:
+/// - `I`:   h_{n+1} = h_n * err_n^(-1/k)  (elementary controller)
+/// - `PI`:  h_{n+1} = h_n * err_n^(-0.7/k) * err_{n-1}^(0.4/k)  (Gustafsson)
+/// - `PID`: h_{n+1} = h_n * err_n^(-0.49/k) * err_{n-1}^(0.34/k) * err_{n-2}^(-0.10/k)  (Söderlind)
+///
+/// all multiplied by a safety factor and clamped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Controller {
+    I,
+    PI,
+    PID,
+}
+
+impl Controller {
+    fn gains(self) -> [f64; 3] {
+        match self {
+            Controller::I => [1.0, 0.0, 0.0],
+            Controller::PI => [0.7, -0.4, 0.0],
+            Controller::PID => [0.49, -0.34, 0.10],
+        }
+    }
+}
+
+impl std::str::FromStr for Controller {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "i" => Ok(Controller::I),
+            "pi" => Ok(Controller::PI),
+            "pid" => Ok(Controller::PID),
+            _ => Err(format!("Unknown controller: {s}")),
+        }
+    }
+}
+
+/// Tolerances, limits and controller for one adaptive run.
+#[derive(Clone, Copy, Debug)]
+pub struct AdaptiveSettings {
+    pub rtol: f64,
+    pub atol: f64,
+    pub max_steps: usize,
+    /// Give up once |h| falls below this.
+    pub h_min: f64,
+    /// Largest step allowed (f64::INFINITY for no limit beyond |t_end|).
+    pub h_max: f64,
+    pub controller: Controller,
+}
+
+const SAFETY: f64 = 0.9;
+const FAC_MIN: f64 = 0.2;
+const FAC_MAX: f64 = 5.0;
+const FAC_REJECT_MIN: f64 = 0.1;
+// Error norms are floored (as in Hairer's codes), both in the current step's factor and
+// in the controller history
+const ERR_FLOOR: f64 = 1e-4;
+
+/// Step-size controller state: error history of accepted steps and rejection memory.
+pub(crate) struct StepController {
+    gains: [f64; 3],
+    k: f64,
+    err_prev: [f64; 2],
+    rejected: bool,
+}
+
+impl StepController {
+    pub fn new(controller: Controller, order: f64) -> Self {
+        StepController {
+            gains: controller.gains(),
+            k: order + 1.0,
+            err_prev: [1.0, 1.0],
+            rejected: false,
+        }
+    }
+
+    /// Factor for the step following an accepted step with error `errn`.
+    pub fn accept(&mut self, errn: f64) -> f64 {
+        let [b1, b2, b3] = self.gains;
+        let e = errn.max(ERR_FLOOR);
+        let raw = e.powf(-b1 / self.k)
+            * self.err_prev[0].powf(-b2 / self.k)
+            * self.err_prev[1].powf(-b3 / self.k);
+        let fac = (SAFETY * raw).clamp(FAC_MIN, FAC_MAX);
+        self.err_prev = [e, self.err_prev[0]];
+        // Never grow right after a rejection
+        let fac = if self.rejected { fac.min(1.0) } else { fac };
+        self.rejected = false;
+        fac
+    }
+
+    /// Factor for retrying a step rejected with error `errn` (> 1).
+    pub fn reject(&mut self, errn: f64) -> f64 {
+        // Repeated rejections (or a NaN/inf error): the asymptotic model is off, cut hard.
+        let fac = if self.rejected || !errn.is_finite() {
+            FAC_REJECT_MIN
+        } else {
+            (SAFETY * errn.powf(-1.0 / self.k)).clamp(FAC_REJECT_MIN, SAFETY)
+        };
+        self.rejected = true;
+        fac
+    }
+}
+
+/// Initial step size (Hairer–Nørsett–Wanner, Solving ODE I, II.4, `hinit`).
+pub(crate) fn initial_step<P: EmbeddedPair>(
+    pair: &P,
+    dir: f64,
+    h_max: f64,
+    settings: &AdaptiveSettings,
+) -> f64 {
+    let y0 = pair.state_f64();
+    let f0 = pair.rhs_f64(&y0);
+    let sk: Vec<f64> = y0
+        .iter()
+        .map(|y| settings.atol + settings.rtol * y.abs())
+        .collect();
+
+    // Squared RMS norms, as in Hairer's codes
+    let n = y0.len() as f64;
+    let dnf = f0
+        .iter()
+        .zip(&sk)
+        .map(|(f, s)| (f / s) * (f / s))
+        .sum::<f64>()
+        / n;
+    let dny = y0
+        .iter()
+        .zip(&sk)
+        .map(|(y, s)| (y / s) * (y / s))
+        .sum::<f64>()
+        / n;
+    let h = if dnf <= 1e-10 || dny <= 1e-10 {
+        1e-6
+    } else {
+        (dny / dnf).sqrt() * 0.01
+    };
+    let h = h.min(h_max) * dir;
+
+    // Explicit Euler step to estimate the second derivative
+    let y1: Vec<f64> = y0.iter().zip(&f0).map(|(y, f)| y + h * f).collect();
+    let f1 = pair.rhs_f64(&y1);
+    let dn2 = f1
+        .iter()
+        .zip(&f0)
+        .zip(&sk)
+        .map(|((a, b), s)| ((a - b) / s) * ((a - b) / s))
+        .sum::<f64>()
+        / n;
+    let der2 = dn2.sqrt() / h.abs();
+
+    let der12 = der2.max(dnf.sqrt());
+    let h1 = if der12 <= 1e-15 {
+        (h.abs() * 1e-3).max(1e-6)
+    } else {
+        (0.01 / der12).powf(1.0 / (pair.order() + 1.0))
+    };
+    (100.0 * h.abs()).min(h1).min(h_max) * dir
+}
+
+/// Integrate from t=0 to t=t_end, calling `on_accept(pair, t, h)` after every accepted step.
+/// Returns the final time reached.
+pub(crate) fn drive<P: EmbeddedPair>(
+    pair: &mut P,
+    t_end: f64,
+    settings: &AdaptiveSettings,
+    mut on_accept: impl FnMut(&P, f64, f64),
+) -> f64 {
+    let mut t = 0.0;
+    if t_end == 0.0 {
+        return t;
+    }
+    let dir = t_end.signum();
+    let h_max = settings.h_max.min(t_end.abs());
+
+    let mut controller = StepController::new(settings.controller, pair.order());
+    let mut h = initial_step(pair, dir, h_max, settings);
+
+    let mut steps = 0usize;
+    while (t_end - t) * dir > 0.0 && steps < settings.max_steps {
+        // Don’t overshoot t_end
+        if (t + h - t_end) * dir > 0.0 {
+            h = t_end - t;
+        }
+
+        let errn = pair.try_step(h, settings.rtol, settings.atol);
+
+        if errn <= 1.0 {
+            pair.accept();
+            t += h;
+            on_accept(pair, t, h);
+            h *= controller.accept(errn);
+        } else {
+            h *= controller.reject(errn);
+        }
+        if h.abs() > h_max {
+            h = h_max * dir;
+        }
+
+        steps += 1;
+        if h.abs() < settings.h_min {
+            break;
+        }
+    }
+    t
+}
+
+/// Run `drive` recording the positions after every accepted step (t=0 included),
+/// in the flat `[x, y, z] * n, t` layout returned by all `evolve` functions.
+pub(crate) fn evolve_positions<P: EmbeddedPair>(
+    pair: &mut P,
+    bodies: &mut [Body],
+    t_end: f64,
+    settings: &AdaptiveSettings,
+) -> (Vec<f64>, f64) {
+    let mut result = Vec::<f64>::new();
+    pair.push_positions(&mut result);
+    result.push(0.0);
+
+    let t = drive(pair, t_end, settings, |p, t, _h| {
+        p.push_positions(&mut result);
+        result.push(t);
+    });
+
+    pair.unpack(bodies);
+    (result, t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// y' = -y on `n` equal components.
+    struct Decay {
+        n: usize,
+    }
+
+    impl EmbeddedPair for Decay {
+        fn order(&self) -> f64 {
+            4.0
+        }
+        fn try_step(&mut self, _h: f64, _rtol: f64, _atol: f64) -> f64 {
+            0.0
+        }
+        fn accept(&mut self) {}
+        fn state_f64(&self) -> Vec<f64> {
+            vec![1.0; self.n]
+        }
+        fn rhs_f64(&self, y: &[f64]) -> Vec<f64> {
+            y.iter().map(|y| -y).collect()
+        }
+        fn push_positions(&self, _out: &mut Vec<f64>) {}
+        fn unpack(&self, _bodies: &mut [Body]) {}
+    }
+
+    const SETTINGS: AdaptiveSettings = AdaptiveSettings {
+        rtol: 1e-9,
+        atol: 1e-12,
+        max_steps: 1000,
+        h_min: 1e-16,
+        h_max: f64::INFINITY,
+        controller: Controller::PI,
+    };
+
+    #[test]
+    fn initial_step_uses_rms_norms() {
+        let h1 = initial_step(&Decay { n: 1 }, 1.0, 10.0, &SETTINGS);
+        let h18 = initial_step(&Decay { n: 18 }, 1.0, 10.0, &SETTINGS);
+        assert!(h1 > 0.0);
+        assert!((h18 / h1 - 1.0).abs() < 1e-12, "{h1} vs {h18}");
+        // Order 4: h = (0.01 / |y'|)^(1/5) in the scaled norm
+        let expected = (0.01 / (1.0 / (SETTINGS.atol + SETTINGS.rtol))).powf(0.2);
+        assert!((h1 / expected - 1.0).abs() < 1e-9, "{h1} vs {expected}");
+        assert!(initial_step(&Decay { n: 1 }, -1.0, 10.0, &SETTINGS) < 0.0);
+    }
+
+    #[test]
+    fn small_errors_share_one_floor() {
+        for controller in [Controller::I, Controller::PI, Controller::PID] {
+            let factors = |first: f64| {
+                let mut c = StepController::new(controller, 4.0);
+                [c.accept(first), c.accept(0.5), c.accept(0.5)]
+            };
+            let floor = factors(ERR_FLOOR);
+            assert_eq!(factors(0.0), floor, "{controller:?}");
+            assert_eq!(factors(1e-12), floor, "{controller:?}");
+            if controller != Controller::I {
+                // Below saturation, so the floor is what decides the factor
+                assert!(floor[0] < FAC_MAX, "{controller:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn no_growth_after_a_rejection() {
+        let mut c = StepController::new(Controller::PI, 4.0);
+        let shrink = c.reject(10.0);
+        assert!((FAC_REJECT_MIN..SAFETY).contains(&shrink));
+        assert_eq!(c.reject(10.0), FAC_REJECT_MIN);
+        assert!(c.accept(1e-6) <= 1.0);
+        assert!(c.accept(1e-6) > 1.0);
+    }
+
+    #[test]
+    fn controller_names() {
+        assert_eq!("PID".parse::<Controller>(), Ok(Controller::PID));
+        assert_eq!("pi".parse::<Controller>(), Ok(Controller::PI));
+        assert!("p".parse::<Controller>().is_err());
+    }
+}
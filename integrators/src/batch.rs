@@ -0,0 +1,333 @@
+// Batched integration of many independent N-body systems in lockstep.
+//
+// States are stored structure-of-arrays: component c of system s lives at
+// y[c * m + s] (m = number of systems), so the inner loops run over systems with
+// unit stride and auto-vectorize. Every system keeps its own step size, controller,
+// end time and termination, exactly as if it had been integrated alone.
+
+use crate::{
+    adaptive::{AdaptiveSettings, StepController, initial_step},
+    erk::{ErkPair, Tableau},
+    types::Body,
+};
+
+/// Per-system bookkeeping.
+struct Lane {
+    t: f64,
+    t_end: f64,
+    h: f64,
+    h_max: f64,
+    steps: usize,
+    done: bool,
+    controller: StepController,
+    result: Vec<f64>,
+}
+
+/// SoA buffers for `m` systems of `n` bodies each.
+struct Workspace {
+    m: usize,
+    n: usize,
+    masses: Vec<f64>,
+    y: Vec<f64>,
+    y_new: Vec<f64>,
+    ytmp: Vec<f64>,
+    err: Vec<f64>,
+    k: Vec<Vec<f64>>,
+    // per-system step used by the stage loops (0 for finished systems)
+    h: Vec<f64>,
+    // scratch for one pair: 1/r^3 and the separation vector
+    inv_r3: Vec<f64>,
+    d: [Vec<f64>; 3],
+    errn: Vec<f64>,
+}
+
+impl Workspace {
+    fn new(tab: &Tableau, systems: &[Vec<Body>]) -> Self {
+        let m = systems.len();
+        let n = systems[0].len();
+        let len = 6 * n * m;
+        let mut masses = vec![0.0; n * m];
+        let mut y = vec![0.0; len];
+        for (s, bodies) in systems.iter().enumerate() {
+            assert_eq!(
+                bodies.len(),
+                n,
+                "all systems in a batch need the same number of bodies"
+            );
+            for (i, b) in bodies.iter().enumerate() {
+                masses[i * m + s] = b.m;
+                for c in 0..3 {
+                    y[(6 * i + c) * m + s] = b.r[c];
+                    y[(6 * i + 3 + c) * m + s] = b.v[c];
+                }
+            }
+        }
+        Workspace {
+            m,
+            n,
+            masses,
+            y_new: y.clone(),
+            y,
+            ytmp: vec![0.0; len],
+            err: vec![0.0; len],
+            k: vec![vec![0.0; len]; tab.stages()],
+            h: vec![0.0; m],
+            inv_r3: vec![0.0; m],
+            d: std::array::from_fn(|_| vec![0.0; m]),
+            errn: vec![0.0; m],
+        }
+    }
+
+    /// Component `c` of every system.
+    fn comp(v: &[f64], m: usize, c: usize) -> &[f64] {
+        &v[c * m..(c + 1) * m]
+    }
+
+    fn unpack(&self, s: usize, bodies: &mut [Body]) {
+        let m = self.m;
+        for (i, b) in bodies.iter_mut().enumerate() {
+            for c in 0..3 {
+                b.r[c] = self.y[(6 * i + c) * m + s];
+                b.v[c] = self.y[(6 * i + 3 + c) * m + s];
+            }
+        }
+    }
+
+    fn push_positions(&self, s: usize, out: &mut Vec<f64>) {
+        let m = self.m;
+        for i in 0..self.n {
+            for c in 0..3 {
+                out.push(self.y[(6 * i + c) * m + s]);
+            }
+        }
+    }
+}
+
+/// dy/dt = [v, a] for all systems at once; each pair is evaluated once.
+fn deriv_soa(ws: &mut Workspace, y: &[f64], dy: &mut [f64], eps2: f64) {
+    let (m, n) = (ws.m, ws.n);
+    for i in 0..n {
+        for c in 0..3 {
+            let o = 6 * i + c;
+            dy[o * m..(o + 1) * m].copy_from_slice(Workspace::comp(y, m, o + 3));
+            dy[(o + 3) * m..(o + 4) * m].fill(0.0);
+        }
+    }
+    for i in 0..n {
+        for j in (i + 1)..n {
+            for c in 0..3 {
+                let ri = Workspace::comp(y, m, 6 * i + c);
+                let rj = Workspace::comp(y, m, 6 * j + c);
+                for ((d, a), b) in ws.d[c].iter_mut().zip(rj).zip(ri) {
+                    *d = a - b;
+                }
+            }
+            let [dx, dy_, dz] = &ws.d;
+            for (s, inv) in ws.inv_r3.iter_mut().enumerate() {
+                let r2 = dx[s] * dx[s] + dy_[s] * dy_[s] + dz[s] * dz[s] + eps2;
+                *inv = 1.0 / (r2 * r2.sqrt());
+            }
+            let mi = &ws.masses[i * m..(i + 1) * m];
+            let mj = &ws.masses[j * m..(j + 1) * m];
+            for c in 0..3 {
+                let d = &ws.d[c];
+                let ai = &mut dy[(6 * i + 3 + c) * m..(6 * i + 4 + c) * m];
+                for s in 0..m {
+                    ai[s] += mj[s] * ws.inv_r3[s] * d[s];
+                }
+                let aj = &mut dy[(6 * j + 3 + c) * m..(6 * j + 4 + c) * m];
+                for s in 0..m {
+                    aj[s] -= mi[s] * ws.inv_r3[s] * d[s];
+                }
+            }
+        }
+    }
+}
+
+/// out[c * m + s] += coef * h[s] * x[c * m + s]
+fn saxpy_lanes(out: &mut [f64], coef: f64, h: &[f64], x: &[f64]) {
+    let m = h.len();
+    for (o, xc) in out.chunks_exact_mut(m).zip(x.chunks_exact(m)) {
+        for s in 0..m {
+            o[s] += coef * h[s] * xc[s];
+        }
+    }
+}
+
+/// One trial step of every system with its own h (0 for finished ones).
+/// Leaves y_new and the per-system error norms in the workspace.
+fn trial(ws: &mut Workspace, tab: &Tableau, eps2: f64, rtol: f64, atol: f64) {
+    let m = ws.m;
+    let mut k = std::mem::take(&mut ws.k);
+    let mut ytmp = std::mem::take(&mut ws.ytmp);
+
+    for i in 1..tab.stages() {
+        ytmp.copy_from_slice(&ws.y);
+        for (j, &aij) in tab.a[i - 1].iter().enumerate() {
+            if aij != 0.0 {
+                saxpy_lanes(&mut ytmp, aij, &ws.h, &k[j]);
+            }
+        }
+        let y = std::mem::take(&mut ytmp);
+        deriv_soa(ws, &y, &mut k[i], eps2);
+        ytmp = y;
+    }
+
+    ws.y_new.copy_from_slice(&ws.y);
+    ws.err.fill(0.0);
+    for ((&bi, &ei), ki) in tab.b.iter().zip(tab.e.iter()).zip(k.iter()) {
+        if bi != 0.0 {
+            saxpy_lanes(&mut ws.y_new, bi, &ws.h, ki);
+        }
+        if ei != 0.0 {
+            saxpy_lanes(&mut ws.err, ei, &ws.h, ki);
+        }
+    }
+
+    // Weighted RMS error norm per system (Hairer style)
+    ws.errn.fill(0.0);
+    for c in 0..6 * ws.n {
+        let range = c * m..(c + 1) * m;
+        let (e, y0, y1) = (
+            &ws.err[range.clone()],
+            &ws.y[range.clone()],
+            &ws.y_new[range],
+        );
+        for s in 0..m {
+            let sc = atol + rtol * y0[s].abs().max(y1[s].abs());
+            let q = e[s] / sc;
+            ws.errn[s] += q * q;
+        }
+    }
+    let len = (6 * ws.n) as f64;
+    for e in ws.errn.iter_mut() {
+        *e = (*e / len).sqrt();
+    }
+
+    ws.k = k;
+    ws.ytmp = ytmp;
+}
+
+/// Integrate `systems[s]` from t=0 to `t_end[s]` for every s, all in lockstep.
+/// Returns one (flat positions history, final time reached) per system, in the same
+/// format as the single-system `evolve`, and leaves the final states in `systems`.
+pub(crate) fn evolve_batch(
+    tab: &'static Tableau,
+    systems: &mut [Vec<Body>],
+    t_end: &[f64],
+    settings: &AdaptiveSettings,
+) -> Vec<(Vec<f64>, f64)> {
+    assert_eq!(systems.len(), t_end.len(), "one end time per system");
+    if systems.is_empty() {
+        return Vec::new();
+    }
+    let eps2 = 0.0;
+    let mut ws = Workspace::new(tab, systems);
+    let m = ws.m;
+
+    let mut lanes: Vec<Lane> = systems
+        .iter()
+        .zip(t_end)
+        .enumerate()
+        .map(|(s, (bodies, &t_end))| {
+            let dir = if t_end >= 0.0 { 1.0 } else { -1.0 };
+            let h_max = settings.h_max.min(t_end.abs());
+            let pair = ErkPair::new(tab, bodies, eps2);
+            let mut result = Vec::new();
+            ws.push_positions(s, &mut result);
+            result.push(0.0);
+            Lane {
+                t: 0.0,
+                t_end,
+                h: if t_end == 0.0 {
+                    0.0
+                } else {
+                    initial_step(&pair, dir, h_max, settings)
+                },
+                h_max,
+                steps: 0,
+                done: t_end == 0.0,
+                controller: StepController::new(settings.controller, tab.order),
+                result,
+            }
+        })
+        .collect();
+
+    let mut k0 = std::mem::take(&mut ws.k[0]);
+    let y = std::mem::take(&mut ws.y);
+    deriv_soa(&mut ws, &y, &mut k0, eps2);
+    ws.y = y;
+    ws.k[0] = k0;
+
+    while lanes.iter().any(|l| !l.done) {
+        for (lane, h) in lanes.iter_mut().zip(ws.h.iter_mut()) {
+            if lane.done {
+                *h = 0.0;
+                continue;
+            }
+            // Don’t overshoot t_end
+            if (lane.t + lane.h - lane.t_end) * lane.h.signum() > 0.0 {
+                lane.h = lane.t_end - lane.t;
+            }
+            *h = lane.h;
+        }
+
+        trial(&mut ws, tab, eps2, settings.rtol, settings.atol);
+
+        let last = tab.stages() - 1;
+        for (s, lane) in lanes.iter_mut().enumerate() {
+            if lane.done {
+                continue;
+            }
+            let errn = ws.errn[s];
+            let dir = lane.h.signum();
+            if errn <= 1.0 {
+                for c in 0..6 * ws.n {
+                    ws.y[c * m + s] = ws.y_new[c * m + s];
+                }
+                lane.t += lane.h;
+                ws.push_positions(s, &mut lane.result);
+                lane.result.push(lane.t);
+                lane.h *= lane.controller.accept(errn);
+            } else {
+                lane.h *= lane.controller.reject(errn);
+            }
+            if lane.h.abs() > lane.h_max {
+                lane.h = lane.h_max * dir;
+            }
+            lane.steps += 1;
+            lane.done = (lane.t_end - lane.t) * dir <= 0.0
+                || lane.steps >= settings.max_steps
+                || lane.h.abs() < settings.h_min
+                || !lane.h.is_finite();
+        }
+
+        // k[0] <- f(y): copy the last stage of accepted FSAL steps, recompute otherwise
+        if tab.fsal {
+            for s in 0..m {
+                // finished systems stepped with h = 0, so their last stage is f(y) too
+                if ws.errn[s] <= 1.0 {
+                    for c in 0..6 * ws.n {
+                        ws.k[0][c * m + s] = ws.k[last][c * m + s];
+                    }
+                }
+            }
+        } else {
+            let mut k0 = std::mem::take(&mut ws.k[0]);
+            let y = std::mem::take(&mut ws.y);
+            deriv_soa(&mut ws, &y, &mut k0, eps2);
+            ws.y = y;
+            ws.k[0] = k0;
+        }
+    }
+
+    lanes
+        .into_iter()
+        .zip(systems.iter_mut())
+        .enumerate()
+        .map(|(s, (lane, bodies))| {
+            ws.unpack(s, bodies);
+            (lane.result, lane.t)
+        })
+        .collect()
+}
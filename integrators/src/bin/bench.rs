@@ -0,0 +1,165 @@
+// Stepping benchmark on the figure-eight orbit (Chenciner–Montgomery, Simó's ICs).
+//
+//   cargo run --release --bin bench [periods]
+//
+// Times every f64 integrator over `periods` periods and compares RK4 against a copy of
+// the original allocating implementation (one `Vec` per stage and per force evaluation).
+
+use std::time::{Duration, Instant};
+
+use three_body::Body;
+
+const PERIOD: f64 = 6.325_913_985_3;
+
+fn figure_eight() -> Vec<Body> {
+    vec![
+        Body {
+            m: 1.0,
+            r: [-0.970_004_36, 0.243_087_53, 0.0],
+            v: [0.466_203_685, 0.432_365_73, 0.0],
+        },
+        Body {
+            m: 1.0,
+            r: [0.970_004_36, -0.243_087_53, 0.0],
+            v: [0.466_203_685, 0.432_365_73, 0.0],
+        },
+        Body {
+            m: 1.0,
+            r: [0.0, 0.0, 0.0],
+            v: [-0.932_407_37, -0.864_731_46, 0.0],
+        },
+    ]
+}
+
+/// The RK4 stepper as it was before the workspace rework, kept as a reference.
+mod reference {
+    use three_body::Body;
+
+    fn accelerations(positions: &[[f64; 3]], masses: &[f64]) -> Vec<[f64; 3]> {
+        let n = positions.len();
+        let mut a = vec![[0.0; 3]; n];
+        for i in 0..n {
+            for j in 0..n {
+                if i == j {
+                    continue;
+                }
+                let d = [
+                    positions[j][0] - positions[i][0],
+                    positions[j][1] - positions[i][1],
+                    positions[j][2] - positions[i][2],
+                ];
+                let r2 = d[0] * d[0] + d[1] * d[1] + d[2] * d[2];
+                let s = masses[j] / (r2 * r2.sqrt());
+                for c in 0..3 {
+                    a[i][c] += s * d[c];
+                }
+            }
+        }
+        a
+    }
+
+    fn axpy(x: &[[f64; 3]], s: f64, y: &[[f64; 3]]) -> Vec<[f64; 3]> {
+        x.iter()
+            .zip(y)
+            .map(|(x, y)| [x[0] + s * y[0], x[1] + s * y[1], x[2] + s * y[2]])
+            .collect()
+    }
+
+    fn step_rk4(bodies: &mut [Body], dt: f64) {
+        let r0: Vec<[f64; 3]> = bodies.iter().map(|b| b.r).collect();
+        let v0: Vec<[f64; 3]> = bodies.iter().map(|b| b.v).collect();
+        let m: Vec<f64> = bodies.iter().map(|b| b.m).collect();
+        let a0 = accelerations(&r0, &m);
+        let r1 = axpy(&r0, 0.5 * dt, &v0);
+        let v1 = axpy(&v0, 0.5 * dt, &a0);
+        let a1 = accelerations(&r1, &m);
+        let r2 = axpy(&r0, 0.5 * dt, &v1);
+        let v2 = axpy(&v0, 0.5 * dt, &a1);
+        let a2 = accelerations(&r2, &m);
+        let r3 = axpy(&r0, dt, &v2);
+        let v3 = axpy(&v0, dt, &a2);
+        let a3 = accelerations(&r3, &m);
+        for (i, b) in bodies.iter_mut().enumerate() {
+            for c in 0..3 {
+                b.r[c] += dt / 6.0 * (v0[i][c] + 2.0 * v1[i][c] + 2.0 * v2[i][c] + v3[i][c]);
+                b.v[c] += dt / 6.0 * (a0[i][c] + 2.0 * a1[i][c] + 2.0 * a2[i][c] + a3[i][c]);
+            }
+        }
+    }
+
+    /// Same loop and output layout as `three_body::evolve_rk4`.
+    pub fn evolve_rk4(bodies: &mut [Body], t_end: f64) -> (Vec<f64>, f64) {
+        let dt = 1e-5;
+        let mut t = 0.0;
+        let mut result = Vec::new();
+        while t < t_end {
+            let h = if t + dt > t_end { t_end - t } else { dt };
+            result.append(&mut bodies[0].r.to_vec());
+            result.append(&mut bodies[1].r.to_vec());
+            result.append(&mut bodies[2].r.to_vec());
+            result.push(t);
+            step_rk4(bodies, h);
+            t += h;
+        }
+        (result, t)
+    }
+}
+
+type Evolve = fn(&mut [Body], f64) -> (Vec<f64>, f64);
+
+/// Best of `repeats` runs, plus the number of recorded samples.
+fn time(f: Evolve, t_end: f64, repeats: usize) -> (Duration, usize) {
+    let mut best = Duration::MAX;
+    let mut samples = 0;
+    for _ in 0..repeats {
+        let mut bodies = figure_eight();
+        let start = Instant::now();
+        let (series, _) = f(&mut bodies, t_end);
+        best = best.min(start.elapsed());
+        samples = series.len() / 10;
+    }
+    (best, samples)
+}
+
+fn main() {
+    let periods: f64 = std::env::args()
+        .nth(1)
+        .map(|s| s.parse().expect("periods must be a number"))
+        .unwrap_or(1.0);
+    let t_end = periods * PERIOD;
+    println!("figure-eight, {periods} period(s), t_end = {t_end:.4}\n");
+
+    let methods: [(&str, Evolve, usize); 6] = [
+        ("dop853", three_body::evolve_dop853, 20),
+        ("dopri5", three_body::evolve_dopri5, 20),
+        ("cash_karp", three_body::evolve_cash_karp, 20),
+        ("bs32", three_body::evolve_bs32, 20),
+        ("verlet", three_body::evolve_verlet, 3),
+        ("rk4", three_body::evolve_rk4, 3),
+    ];
+
+    println!(
+        "{:<16} {:>10} {:>12} {:>10}",
+        "method", "samples", "time (ms)", "ns/step"
+    );
+    for (name, f, repeats) in methods {
+        let (d, samples) = time(f, t_end, repeats);
+        let ns = d.as_nanos() as f64 / samples.max(1) as f64;
+        println!(
+            "{:<16} {:>10} {:>12.3} {:>10.1}",
+            name,
+            samples,
+            d.as_secs_f64() * 1e3,
+            ns
+        );
+    }
+
+    let (new, _) = time(three_body::evolve_rk4, t_end, 3);
+    let (old, _) = time(reference::evolve_rk4, t_end, 3);
+    println!(
+        "\nrk4 workspace vs allocating reference: {:.3} ms vs {:.3} ms ({:.2}x)",
+        new.as_secs_f64() * 1e3,
+        old.as_secs_f64() * 1e3,
+        old.as_secs_f64() / new.as_secs_f64()
+    );
+}
@@ -0,0 +1,122 @@
+// `orbit-plot animate`: a plot config as an animated GIF.
+//
+// Frames are equally spaced in time over the config's period (`Method::sample`), so the
+// bodies slow down and speed up as they do in the orbit; `--fps` and `--duration` set how
+// many there are and how fast they play. Trails follow the integrator's own steps, which
+// crowd in close encounters, and fade out over `--trail` of the period. The markers'
+// radii are proportional to the masses. The GIF loops, so the frame at the end of the
+// period (the start again, for a periodic orbit) is left out.
+
+use std::path::PathBuf;
+
+use plotters::prelude::*;
+use three_body::{Method, to_frame};
+
+use crate::{Cfg, bounds_with_aspect, build_ic, frame, masses, reshape_paths};
+
+#[derive(clap::Args, Debug)]
+pub struct AnimateArgs {
+    /// Path to the TOML config (same format as for plotting; `output` is not used)
+    #[arg(value_name = "FILE")]
+    config: PathBuf,
+    /// GIF to write
+    #[arg(short, long, value_name = "FILE", default_value = "orbit.gif")]
+    output: PathBuf,
+    /// Frames per second (GIF delays are in 1/100 s, so at most 50 play as asked)
+    #[arg(long, default_value_t = 25.0)]
+    fps: f64,
+    /// Length of the animation in seconds (one period of the orbit)
+    #[arg(long, default_value_t = 6.0)]
+    duration: f64,
+    /// Length of the trails as a fraction of the period (0 = no trails)
+    #[arg(long, default_value_t = 0.2)]
+    trail: f64,
+    /// Marker radius in pixels of the heaviest body
+    #[arg(long, default_value_t = 8.0)]
+    marker: f64,
+}
+
+pub fn run(args: &AnimateArgs) -> anyhow::Result<()> {
+    let cfg: Cfg = toml::from_str(&std::fs::read_to_string(&args.config)?)?;
+    if cfg.projection != "xy" {
+        anyhow::bail!(
+            "animations show the xy projection, got {:?}",
+            cfg.projection
+        );
+    }
+    if !(args.fps > 0.0 && args.duration > 0.0) {
+        anyhow::bail!("--fps and --duration must be positive");
+    }
+    let masses = masses(&cfg.body)?;
+    let frame = frame(&cfg)?;
+    let method: Method = cfg.method.parse().map_err(anyhow::Error::msg)?;
+    let settings = cfg.overrides.settings(method)?;
+    let bodies = build_ic(&cfg.body);
+
+    let mut state = bodies.clone();
+    let (series, _) = method.evolve(&mut state, cfg.period, settings.as_ref());
+    let series = to_frame(&series, &masses, frame).map_err(anyhow::Error::msg)?;
+    let paths = reshape_paths(&series);
+    let step_times: Vec<f64> = series.chunks_exact(10).map(|s| s[9]).collect();
+
+    let frames = (args.fps * args.duration).round().max(1.0) as usize;
+    let mut samples = method.sample(&bodies, cfg.period, settings.as_ref(), frames);
+    samples.truncate(frames);
+    let flat: Vec<f64> = samples
+        .iter()
+        .flat_map(|(t, b)| b.iter().flat_map(|b| b.r).chain([*t]))
+        .collect();
+    let markers = reshape_paths(&to_frame(&flat, &masses, frame).map_err(anyhow::Error::msg)?);
+
+    let (min_x, max_x, min_y, max_y) = bounds_with_aspect(&paths, cfg.width, cfg.height);
+    let delay = (1000.0 / args.fps).round() as u32;
+    let root =
+        BitMapBackend::gif(&args.output, (cfg.width, cfg.height), delay)?.into_drawing_area();
+    let colors = [RED, BLUE, BLACK];
+    let heaviest = masses.iter().copied().fold(0.0, f64::max);
+    let trail = args.trail * cfg.period;
+
+    for (k, (t, _)) in samples.iter().enumerate() {
+        root.fill(&WHITE)?;
+        let mut chart = ChartBuilder::on(&root)
+            .margin(20)
+            .caption(format!("t = {t:.3}"), ("sans-serif", 24))
+            .set_label_area_size(LabelAreaPosition::Left, 40)
+            .set_label_area_size(LabelAreaPosition::Bottom, 40)
+            .build_cartesian_2d(min_x..max_x, min_y..max_y)?;
+        chart
+            .configure_mesh()
+            .x_labels(10)
+            .y_labels(10)
+            .x_label_formatter(&|v| format!("{:.2}", v))
+            .y_label_formatter(&|v| format!("{:.2}", v))
+            .draw()?;
+
+        // Steps within the trail, oldest first, ending at the marker
+        let first = step_times.partition_point(|&s| s < t - trail);
+        let last = step_times.partition_point(|&s| s < *t);
+        for (i, path) in paths.iter().enumerate() {
+            if trail > 0.0 {
+                let mut points: Vec<(f64, (f64, f64))> =
+                    (first..last).map(|j| (step_times[j], path[j])).collect();
+                points.push((*t, markers[i][k]));
+                chart.draw_series(points.windows(2).map(|w| {
+                    let fade = (1.0 - (t - w[0].0) / trail).clamp(0.0, 1.0);
+                    PathElement::new(vec![w[0].1, w[1].1], colors[i].mix(fade).stroke_width(2))
+                }))?;
+            }
+            let radius = (args.marker * masses[i] / heaviest).round().max(2.0) as u32;
+            chart.draw_series(std::iter::once(Circle::new(
+                markers[i][k],
+                radius,
+                colors[i].filled(),
+            )))?;
+        }
+        root.present()?;
+        eprint!("\r{}/{}", k + 1, samples.len());
+    }
+    eprintln!();
+
+    println!("Done: {} ({} frames)", args.output.display(), samples.len());
+    Ok(())
+}
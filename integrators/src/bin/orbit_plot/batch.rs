@@ -0,0 +1,120 @@
+// `orbit-plot batch`: integrate every orbit of a TOML file on a thread pool.
+//
+// The input has the same `method` and tolerance keys as a plot config, plus one
+// `[[orbit]]` table (optional `name`, `period`, `[[orbit.body]]`) per initial condition.
+// One CSV row per orbit is written in input order, whatever the thread count.
+
+use std::{fs, io::Write, path::PathBuf};
+
+use serde::Deserialize;
+use three_body::{BatchOptions, Body, Job, JobResult, Method, run_batch, total_energy};
+
+use crate::{BodyCfg, Overrides, build_ic};
+
+#[derive(clap::Args, Debug)]
+pub struct BatchArgs {
+    /// TOML file with `method`, optional tolerances and `[[orbit]]` tables
+    #[arg(value_name = "FILE")]
+    input: PathBuf,
+    /// Worker threads (0 = all cores)
+    #[arg(short = 'j', long, default_value_t = 0)]
+    threads: usize,
+    /// Write the CSV here instead of stdout
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+}
+
+#[derive(Deserialize, Debug)]
+struct OrbitCfg {
+    name: Option<String>,
+    period: f64,
+    body: Vec<BodyCfg>,
+}
+
+#[derive(Deserialize, Debug)]
+struct BatchCfg {
+    method: String,
+    #[serde(flatten)]
+    overrides: Overrides,
+    orbit: Vec<OrbitCfg>,
+}
+
+pub fn run(args: &BatchArgs) -> anyhow::Result<()> {
+    let cfg: BatchCfg = toml::from_str(&fs::read_to_string(&args.input)?)?;
+    let method: Method = cfg.method.parse().map_err(anyhow::Error::msg)?;
+    let options = BatchOptions {
+        method,
+        settings: cfg.overrides.settings(method)?,
+        threads: args.threads,
+    };
+    let jobs: Vec<Job> = cfg
+        .orbit
+        .iter()
+        .map(|o| Job {
+            bodies: build_ic(&o.body),
+            t_end: o.period,
+        })
+        .collect();
+
+    let results = run_batch(&jobs, &options, |done, total| {
+        eprint!("\r{done}/{total}");
+        if done == total {
+            eprintln!();
+        }
+    });
+
+    let mut csv = String::from("index,name,status,t,samples,return_distance,energy_error,error\n");
+    for (i, ((orbit, job), result)) in cfg.orbit.iter().zip(&jobs).zip(&results).enumerate() {
+        let name = orbit.name.clone().unwrap_or_else(|| format!("orbit-{i}"));
+        let row = match result {
+            Ok(r) => format!(
+                "ok,{},{},{:e},{:e},",
+                r.t,
+                r.series.len() / (3 * r.bodies.len() + 1),
+                return_distance(&job.bodies, &r.bodies),
+                energy_error(&job.bodies, r),
+            ),
+            Err(e) => format!("error,,,,,{}", csv_field(e)),
+        };
+        csv.push_str(&format!("{i},{},{row}\n", csv_field(&name)));
+    }
+
+    match &args.output {
+        Some(path) => {
+            fs::write(path, csv)?;
+            let failed = results.iter().filter(|r| r.is_err()).count();
+            println!(
+                "Done: {} ({} orbits, {failed} failed)",
+                path.display(),
+                results.len()
+            );
+        }
+        None => std::io::stdout().write_all(csv.as_bytes())?,
+    }
+    Ok(())
+}
+
+/// Phase-space distance between the initial and the final state.
+fn return_distance(start: &[Body], end: &[Body]) -> f64 {
+    start
+        .iter()
+        .zip(end)
+        .flat_map(|(a, b)| (0..3).flat_map(move |c| [a.r[c] - b.r[c], a.v[c] - b.v[c]]))
+        .map(|d| d * d)
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// Relative energy error |E(t) - E(0)| / |E(0)|.
+fn energy_error(start: &[Body], r: &JobResult) -> f64 {
+    let e0 = total_energy(start);
+    ((total_energy(&r.bodies) - e0) / e0).abs()
+}
+
+pub fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
@@ -0,0 +1,349 @@
+// `orbit-plot catalog`: import, convert, validate, verify and query orbit catalogs.
+//
+// A catalog is a TOML or JSON file (by extension) with an optional `name` and one
+// `[[orbit]]` table per orbit:
+//
+//     [[orbit]]
+//     name = "I.Ai.c.1"
+//     family = "equal_mass"
+//     source = "Šuvakov & Dmitrašinović (2013)"   # optional citation or URL
+//     period = 6.3259139829
+//     theta = 0.0                # rotation about z that closes the orbit (BHH satellites)
+//     topology = "abAB"          # optional free-group word
+//     stability = "unstable"     # optional: "stable" or "unstable"
+//     exact = { state = ["-1", ...], period = "6.32..." }   # optional, full precision
+//     [[orbit.body]]
+//     mass = 1.0
+//     r = [-1.0, 0.0, 0.0]
+//     v = [0.3471168881, 0.5327249454, 0.0]
+//
+// The `[[orbit]]` tables are those of `orbit-plot batch`, so a TOML catalog with a
+// `method` line added runs as a batch. `exact.state` is packed [r0, v0, r1, v1, ...].
+
+use std::{
+    collections::HashSet,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+use three_body::{BigDecimal, Body};
+
+use crate::{BodyCfg, build_ic, import, verify};
+
+#[derive(clap::Args, Debug)]
+pub struct CatalogArgs {
+    #[command(subcommand)]
+    command: CatalogCommand,
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum CatalogCommand {
+    /// Read one of the viewer's example lists (docs/examples) into a catalog
+    Import {
+        /// equal_mass, unequal_mass, free_fall, choreographies, broucke_boggs,
+        /// bhh_satellites, matt_sheen, li_liao_3d (data.csv), puzynin (sd_80.csv,
+        /// sol_80_600.csv) or custom (the viewer's exported JSON)
+        #[arg(long)]
+        format: String,
+        #[arg(value_name = "FILE")]
+        input: PathBuf,
+        /// Write the catalog here (.toml or .json) instead of TOML on stdout
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Merge catalogs and write them as TOML or JSON
+    Convert {
+        #[arg(value_name = "FILE", required = true)]
+        inputs: Vec<PathBuf>,
+        /// .toml or .json
+        #[arg(short, long, value_name = "FILE")]
+        output: PathBuf,
+    },
+    /// Check catalogs for missing or inconsistent data
+    Validate {
+        #[arg(value_name = "FILE", required = true)]
+        inputs: Vec<PathBuf>,
+    },
+    /// Integrate every orbit over one period and report closure and energy drift
+    Verify(verify::VerifyArgs),
+    /// List (or write) the orbits matching all the filters
+    Query {
+        #[arg(value_name = "FILE", required = true)]
+        inputs: Vec<PathBuf>,
+        /// Family name
+        #[arg(long)]
+        family: Option<String>,
+        /// Range of the smallest over the largest mass as MIN:MAX (1 = equal masses)
+        #[arg(long, value_parser = parse_range, allow_hyphen_values = true)]
+        mass_ratio: Option<[f64; 2]>,
+        /// Range of the period as MIN:MAX
+        #[arg(long, value_parser = parse_range, allow_hyphen_values = true)]
+        period: Option<[f64; 2]>,
+        /// Write the matching orbits as a catalog instead of listing them
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Catalog {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub orbit: Vec<Orbit>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Orbit {
+    pub name: String,
+    #[serde(default)]
+    pub family: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    pub period: f64,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub theta: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topology: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stability: Option<StabilityCfg>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exact: Option<Exact>,
+    pub body: Vec<BodyCfg>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StabilityCfg {
+    Stable,
+    Unstable,
+}
+
+/// Initial state and period as decimal strings, for catalogs with more digits than f64.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Exact {
+    pub state: Vec<String>,
+    pub period: String,
+}
+
+fn is_zero(x: &f64) -> bool {
+    *x == 0.0
+}
+
+impl From<&Body> for BodyCfg {
+    fn from(b: &Body) -> Self {
+        BodyCfg {
+            mass: b.m,
+            r: b.r,
+            v: b.v,
+        }
+    }
+}
+
+/// MIN:MAX
+fn parse_range(s: &str) -> Result<[f64; 2], String> {
+    let (min, max) = s
+        .split_once(':')
+        .ok_or_else(|| format!("expected MIN:MAX, got {s}"))?;
+    let f = |x: &str| x.parse::<f64>().map_err(|e| format!("{x}: {e}"));
+    Ok([f(min)?, f(max)?])
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("json"))
+}
+
+pub fn read(path: &Path) -> anyhow::Result<Catalog> {
+    let text = fs::read_to_string(path)?;
+    let catalog = if is_json(path) {
+        serde_json::from_str(&text)?
+    } else {
+        toml::from_str(&text)?
+    };
+    Ok(catalog)
+}
+
+pub fn read_all(paths: &[PathBuf]) -> anyhow::Result<Catalog> {
+    let mut all = Catalog::default();
+    for path in paths {
+        let catalog = read(path).map_err(|e| anyhow::anyhow!("{}: {e}", path.display()))?;
+        if paths.len() == 1 {
+            all.name = catalog.name;
+        }
+        all.orbit.extend(catalog.orbit);
+    }
+    Ok(all)
+}
+
+fn write(catalog: &Catalog, path: Option<&Path>) -> anyhow::Result<()> {
+    let text = match path {
+        Some(p) if is_json(p) => serde_json::to_string_pretty(catalog)? + "\n",
+        _ => toml::to_string(catalog)?,
+    };
+    match path {
+        Some(p) => {
+            fs::write(p, text)?;
+            println!("Done: {} ({} orbits)", p.display(), catalog.orbit.len());
+        }
+        None => std::io::stdout().write_all(text.as_bytes())?,
+    }
+    Ok(())
+}
+
+/// Smallest over largest mass.
+fn mass_ratio(o: &Orbit) -> f64 {
+    let min = o.body.iter().map(|b| b.mass).fold(f64::INFINITY, f64::min);
+    let max = o.body.iter().map(|b| b.mass).fold(0.0, f64::max);
+    min / max
+}
+
+/// Problems of one orbit (empty if none).
+fn check(o: &Orbit) -> Vec<String> {
+    let mut problems = Vec::new();
+    if o.name.is_empty() {
+        problems.push("empty name".to_string());
+    }
+    if o.family.is_empty() {
+        problems.push("no family".to_string());
+    }
+    if o.body.len() != 3 {
+        problems.push(format!("{} bodies instead of 3", o.body.len()));
+    }
+    if !(o.period.is_finite() && o.period > 0.0) {
+        problems.push(format!("period {} is not positive", o.period));
+    }
+    if !o.theta.is_finite() {
+        problems.push(format!("theta {} is not finite", o.theta));
+    }
+    let total: f64 = o.body.iter().map(|b| b.mass).sum();
+    if o.body.iter().any(|b| b.mass.is_nan() || b.mass < 0.0) || total.is_nan() || total <= 0.0 {
+        problems.push("masses must be non-negative with a positive total".to_string());
+    }
+    if o.body
+        .iter()
+        .flat_map(|b| b.r.iter().chain(&b.v))
+        .any(|x| !x.is_finite())
+    {
+        problems.push("non-finite position or velocity".to_string());
+    }
+    if let Some(word) = &o.topology
+        && !word.chars().all(|c| matches!(c, 'a' | 'b' | 'A' | 'B'))
+    {
+        problems.push(format!("topology {word:?} is not a word in a, b, A, B"));
+    }
+    if let Some(exact) = &o.exact {
+        let bodies = build_ic(&o.body);
+        let rounded: Vec<f64> = bodies
+            .iter()
+            .flat_map(|b| b.r.into_iter().chain(b.v))
+            .collect();
+        if exact.state.len() != rounded.len() {
+            problems.push(format!(
+                "exact state has {} values for {} bodies",
+                exact.state.len(),
+                bodies.len()
+            ));
+        }
+        let matches = |s: &str, x: f64| {
+            BigDecimal::from_str(s).is_ok_and(|d| {
+                let d: f64 = d.to_string().parse().unwrap_or(f64::NAN);
+                (d - x).abs() <= 1e-14 * x.abs().max(1.0)
+            })
+        };
+        for (i, (s, &x)) in exact.state.iter().zip(&rounded).enumerate() {
+            if !matches(s, x) {
+                problems.push(format!("exact state[{i}] = {s} does not round to {x:?}"));
+            }
+        }
+        if !matches(&exact.period, o.period) {
+            problems.push(format!(
+                "exact period {} does not round to {:?}",
+                exact.period, o.period
+            ));
+        }
+    }
+    problems
+}
+
+pub fn run(args: &CatalogArgs) -> anyhow::Result<()> {
+    match &args.command {
+        CatalogCommand::Import {
+            format,
+            input,
+            output,
+        } => {
+            let catalog = Catalog {
+                name: input.file_stem().map(|s| s.to_string_lossy().into_owned()),
+                orbit: import::import(format, input)?,
+            };
+            write(&catalog, output.as_deref())
+        }
+        CatalogCommand::Convert { inputs, output } => write(&read_all(inputs)?, Some(output)),
+        CatalogCommand::Validate { inputs } => {
+            let catalog = read_all(inputs)?;
+            let mut seen = HashSet::new();
+            let mut bad = 0;
+            for o in &catalog.orbit {
+                let mut problems = check(o);
+                if !seen.insert((&o.family, &o.name)) {
+                    problems.push("duplicate name in its family".to_string());
+                }
+                if !problems.is_empty() {
+                    bad += 1;
+                    println!("{} / {}: {}", o.family, o.name, problems.join("; "));
+                }
+            }
+            println!("{} orbits, {bad} with problems", catalog.orbit.len());
+            if bad > 0 {
+                anyhow::bail!("validation failed");
+            }
+            Ok(())
+        }
+        CatalogCommand::Verify(verify_args) => verify::run(verify_args),
+        CatalogCommand::Query {
+            inputs,
+            family,
+            mass_ratio: ratio,
+            period,
+            output,
+        } => {
+            let within = |x: f64, r: &Option<[f64; 2]>| r.is_none_or(|[a, b]| a <= x && x <= b);
+            let catalog = read_all(inputs)?;
+            let orbit: Vec<Orbit> = catalog
+                .orbit
+                .into_iter()
+                .filter(|o| family.as_ref().is_none_or(|f| &o.family == f))
+                .filter(|o| within(mass_ratio(o), ratio))
+                .filter(|o| within(o.period, period))
+                .collect();
+            if output.is_some() {
+                let catalog = Catalog {
+                    name: catalog.name,
+                    orbit,
+                };
+                return write(&catalog, output.as_deref());
+            }
+            println!(
+                "{:<16} {:<24} {:>16} {:>10}  masses",
+                "family", "name", "period", "theta"
+            );
+            for o in &orbit {
+                let masses: Vec<String> = o.body.iter().map(|b| format!("{}", b.mass)).collect();
+                println!(
+                    "{:<16} {:<24} {:>16.10} {:>10.6}  {}",
+                    o.family,
+                    o.name,
+                    o.period,
+                    o.theta,
+                    masses.join(", ")
+                );
+            }
+            println!("{} orbits", orbit.len());
+            Ok(())
+        }
+    }
+}
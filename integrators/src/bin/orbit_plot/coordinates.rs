@@ -0,0 +1,80 @@
+// `orbit-plot coordinates`: a plot config's trajectory in Jacobi or hyperspherical
+// coordinates, with momenta, as CSV.
+//
+// The state is sampled at equally spaced times over `period` (`Method::sample`).
+
+use std::{fs, io::Write, path::PathBuf};
+
+use three_body::{Hyperspherical, Jacobi};
+
+use crate::{Cfg, build_ic};
+
+#[derive(clap::Args, Debug)]
+pub struct CoordinatesArgs {
+    /// Path to the TOML config (same format as for plotting)
+    #[arg(value_name = "FILE")]
+    config: PathBuf,
+    /// jacobi or hyperspherical
+    #[arg(long, default_value = "hyperspherical")]
+    system: String,
+    /// Number of equal time intervals
+    #[arg(long, default_value_t = 1000)]
+    samples: usize,
+    /// Write the CSV here instead of stdout
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+}
+
+pub fn run(args: &CoordinatesArgs) -> anyhow::Result<()> {
+    let cfg: Cfg = toml::from_str(&fs::read_to_string(&args.config)?)?;
+    let method = cfg.method.parse().map_err(anyhow::Error::msg)?;
+    let settings = cfg.overrides.settings(method)?;
+    let states = method.sample(
+        &build_ic(&cfg.body),
+        cfg.period,
+        settings.as_ref(),
+        args.samples,
+    );
+
+    let mut csv = match args.system.as_str() {
+        "jacobi" => String::from(
+            "t,rho_x,rho_y,rho_z,lambda_x,lambda_y,lambda_z,p_rho_x,p_rho_y,p_rho_z,p_lambda_x,p_lambda_y,p_lambda_z\n",
+        ),
+        "hyperspherical" => String::from(
+            "t,radius,alpha,theta1,phi1,theta2,phi2,p_radius,p_alpha,p_theta1,p_phi1,p_theta2,p_phi2\n",
+        ),
+        other => anyhow::bail!("unknown coordinate system {other:?} (jacobi or hyperspherical)"),
+    };
+    for (t, bodies) in &states {
+        let j = Jacobi::from_bodies(bodies).map_err(anyhow::Error::msg)?;
+        let values: Vec<f64> = if args.system == "jacobi" {
+            [j.rho, j.lambda, j.p_rho, j.p_lambda].concat()
+        } else {
+            let h = Hyperspherical::from_jacobi(&j);
+            vec![
+                h.radius,
+                h.alpha,
+                h.theta[0],
+                h.phi[0],
+                h.theta[1],
+                h.phi[1],
+                h.p_radius,
+                h.p_alpha,
+                h.p_theta[0],
+                h.p_phi[0],
+                h.p_theta[1],
+                h.p_phi[1],
+            ]
+        };
+        let values: Vec<String> = values.iter().map(|v| format!("{v:?}")).collect();
+        csv.push_str(&format!("{t:?},{}\n", values.join(",")));
+    }
+    match &args.output {
+        Some(path) => {
+            fs::write(path, csv)?;
+            println!("Done: {} ({} samples)", path.display(), states.len());
+        }
+        None => std::io::stdout().write_all(csv.as_bytes())?,
+    }
+    Ok(())
+}
@@ -0,0 +1,89 @@
+// `orbit-plot diagnostics`: drift of the conserved quantities over a plot config's run.
+//
+// Runs the config's `method` (or every method given with --methods) over `period` and
+// prints the largest relative drift of each invariant; the time series can be written as
+// CSV for plotting.
+
+use std::{fs, path::PathBuf};
+
+use three_body::{Method, diagnose};
+
+use crate::{Cfg, build_ic};
+
+#[derive(clap::Args, Debug)]
+pub struct DiagnosticsArgs {
+    /// Path to the TOML config (same format as for plotting)
+    #[arg(value_name = "FILE")]
+    config: PathBuf,
+    /// Compare these methods instead of the config's, e.g. dop853,rk4,verlet
+    #[arg(long, value_delimiter = ',')]
+    methods: Vec<String>,
+    /// Number of equal intervals the run is checked at
+    #[arg(long, default_value_t = 100)]
+    samples: usize,
+    /// Integrate to this time instead of the config's period
+    #[arg(long)]
+    t_end: Option<f64>,
+    /// Write the time series of every method as CSV here
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+}
+
+pub fn run(args: &DiagnosticsArgs) -> anyhow::Result<()> {
+    let cfg: Cfg = toml::from_str(&fs::read_to_string(&args.config)?)?;
+    let names = if args.methods.is_empty() {
+        vec![cfg.method.clone()]
+    } else {
+        args.methods.clone()
+    };
+    let bodies = build_ic(&cfg.body);
+    let t_end = args.t_end.unwrap_or(cfg.period);
+
+    let mut csv =
+        String::from("method,t,energy,px,py,pz,cx,cy,cz,lx,ly,lz,inertia,virial,sundman\n");
+    println!(
+        "{:<10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}",
+        "method", "t", "energy", "momentum", "centre", "ang. mom.", "c²E"
+    );
+    for name in &names {
+        let method: Method = name.parse().map_err(anyhow::Error::msg)?;
+        let settings = cfg.overrides.settings(method)?;
+        let d = diagnose(&bodies, t_end, method, settings.as_ref(), args.samples)
+            .map_err(anyhow::Error::msg)?;
+        println!(
+            "{:<10} {:>10.4} {:>10.2e} {:>10.2e} {:>10.2e} {:>10.2e} {:>10.2e}",
+            method.name(),
+            d.t_end,
+            d.drift.energy,
+            d.drift.momentum,
+            d.drift.centre,
+            d.drift.angular_momentum,
+            d.drift.sundman
+        );
+        for s in &d.series {
+            let values = [
+                s.t,
+                s.energy,
+                s.momentum[0],
+                s.momentum[1],
+                s.momentum[2],
+                s.centre[0],
+                s.centre[1],
+                s.centre[2],
+                s.angular_momentum[0],
+                s.angular_momentum[1],
+                s.angular_momentum[2],
+                s.inertia,
+                s.virial,
+                s.sundman,
+            ];
+            let values: Vec<String> = values.iter().map(|v| format!("{v:?}")).collect();
+            csv.push_str(&format!("{method},{}\n", values.join(",")));
+        }
+    }
+    if let Some(path) = &args.output {
+        fs::write(path, csv)?;
+        println!("Done: {}", path.display());
+    }
+    Ok(())
+}
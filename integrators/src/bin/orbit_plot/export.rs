@@ -0,0 +1,72 @@
+// `orbit-plot export`: the trajectory of a plot config as CSV, JSON, `.npy` or `.npz`.
+//
+// The format follows the output's extension. By default the run is sampled at equally
+// spaced times (`Method::sample`), which also gives velocities and diagnostics;
+// `--samples 0` writes the integrator's own steps instead, positions only, as the plot
+// draws them.
+
+use std::{fs, path::PathBuf};
+
+use three_body::Table;
+
+use crate::{Cfg, build_ic};
+
+#[derive(clap::Args, Debug)]
+pub struct ExportArgs {
+    /// Path to the TOML config (same format as for plotting)
+    #[arg(value_name = "FILE")]
+    config: PathBuf,
+    /// .csv, .json, .npy or .npz
+    #[arg(short, long, value_name = "FILE")]
+    output: PathBuf,
+    /// Number of equal intervals to sample (0 = every integrator step)
+    #[arg(long, default_value_t = 1000)]
+    samples: usize,
+    /// Add the velocities vx1, vy1, vz1, ...
+    #[arg(long)]
+    velocities: bool,
+    /// Add energy, momentum, centre, angular momentum, inertia, virial ratio and c²E
+    #[arg(long)]
+    diagnostics: bool,
+}
+
+pub fn run(args: &ExportArgs) -> anyhow::Result<()> {
+    let cfg: Cfg = toml::from_str(&fs::read_to_string(&args.config)?)?;
+    let method: three_body::Method = cfg.method.parse().map_err(anyhow::Error::msg)?;
+    let settings = cfg.overrides.settings(method)?;
+    let bodies = build_ic(&cfg.body);
+
+    let table = if args.samples == 0 {
+        if args.velocities || args.diagnostics {
+            anyhow::bail!("--velocities and --diagnostics need --samples > 0");
+        }
+        let mut state = bodies.clone();
+        let (series, _) = method.evolve(&mut state, cfg.period, settings.as_ref());
+        Table::positions(&series, bodies.len())
+    } else {
+        let states = method.sample(&bodies, cfg.period, settings.as_ref(), args.samples);
+        Table::trajectory(&states, args.velocities, args.diagnostics)
+    };
+
+    let extension = args
+        .output
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    let bytes = match extension.as_str() {
+        "csv" => table.to_csv().into_bytes(),
+        "json" => table.to_json().into_bytes(),
+        "npy" => table.to_npy(),
+        "npz" => table.to_npz(),
+        other => anyhow::bail!("unknown format {other:?} (csv, json, npy or npz)"),
+    };
+    fs::write(&args.output, bytes)?;
+    println!(
+        "Done: {} ({} rows, {} columns)",
+        args.output.display(),
+        table.rows.len(),
+        table.columns.len()
+    );
+    Ok(())
+}
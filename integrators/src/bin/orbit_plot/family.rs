@@ -0,0 +1,202 @@
+// `orbit-plot continue`: follow a family of periodic orbits from the orbit of a plot config.
+//
+// The family is written as a CSV table, as custom examples for the web page (the JSON
+// object stored under the "examples" key of localStorage), or as a batch file that
+// `orbit-plot batch` reads, depending on the extension of `--output`.
+
+use std::{fs, io::Write, path::PathBuf};
+
+use three_body::{ContinuationOptions, Event, FamilyPoint, Parameter, continue_family};
+
+use crate::{Cfg, build_ic};
+
+#[derive(clap::Args, Debug)]
+pub struct FamilyArgs {
+    /// Path to the TOML config of a (nearly) periodic orbit
+    #[arg(value_name = "FILE")]
+    config: PathBuf,
+    /// m1, m2, m3, ... (energy held fixed), energy or angular-momentum
+    #[arg(short, long, default_value = "m3")]
+    parameter: String,
+    /// First arclength step; negative to decrease the parameter
+    #[arg(long, default_value_t = 0.01, allow_hyphen_values = true)]
+    step: f64,
+    /// Largest arclength step
+    #[arg(long, default_value_t = 0.1)]
+    max_step: f64,
+    /// Number of orbits, including the starting one
+    #[arg(long, default_value_t = 50)]
+    points: usize,
+    /// Stop below this parameter value
+    #[arg(long, allow_hyphen_values = true)]
+    min: Option<f64>,
+    /// Stop above this parameter value
+    #[arg(long, allow_hyphen_values = true)]
+    max: Option<f64>,
+    /// Also solve for a rotation about z (needed for non-zero angular momentum)
+    #[arg(long)]
+    rotation: bool,
+    /// Output file: .csv (default: CSV on stdout), .json (web examples) or .toml (batch)
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+    /// Name prefix of the orbits in JSON and TOML output
+    #[arg(long, default_value = "family")]
+    name: String,
+}
+
+fn parse_parameter(s: &str) -> anyhow::Result<Parameter> {
+    match s {
+        "energy" => Ok(Parameter::Energy),
+        "angular-momentum" => Ok(Parameter::AngularMomentum),
+        _ => match s.strip_prefix('m').map(str::parse::<usize>) {
+            Some(Ok(k)) if k >= 1 => Ok(Parameter::Mass(k - 1)),
+            _ => anyhow::bail!("Unknown parameter: {s} (m1, m2, ..., energy or angular-momentum)"),
+        },
+    }
+}
+
+pub fn run(args: &FamilyArgs) -> anyhow::Result<()> {
+    let cfg: Cfg = toml::from_str(&fs::read_to_string(&args.config)?)?;
+    let options = ContinuationOptions {
+        parameter: parse_parameter(&args.parameter)?,
+        step: args.step,
+        max_step: args.max_step,
+        max_points: args.points,
+        bounds: (
+            args.min.unwrap_or(f64::NEG_INFINITY),
+            args.max.unwrap_or(f64::INFINITY),
+        ),
+        rotation: args.rotation,
+        ..ContinuationOptions::default()
+    };
+    let bodies = build_ic(&cfg.body);
+    let family = continue_family(&bodies, cfg.period, &options).map_err(anyhow::Error::msg)?;
+
+    let points = &family.points;
+    let extension = args
+        .output
+        .as_ref()
+        .and_then(|p| p.extension())
+        .and_then(|e| e.to_str());
+    let text = match extension {
+        None | Some("csv") => family_csv(points),
+        Some("json") => family_json(points, &args.name),
+        Some("toml") => family_toml(points, &args.name),
+        Some(e) => anyhow::bail!("Unknown output format: .{e} (csv, json or toml)"),
+    };
+
+    match &args.output {
+        Some(path) => {
+            fs::write(path, text)?;
+            println!(
+                "Done: {} ({} orbits, stopped: {})",
+                path.display(),
+                points.len(),
+                family.stop_reason
+            );
+            for (i, p) in points.iter().enumerate() {
+                if let Some(event) = p.event {
+                    println!(
+                        "  {event:?} before orbit {i} at {} = {}",
+                        args.parameter, p.parameter
+                    );
+                }
+            }
+        }
+        None => {
+            std::io::stdout().write_all(text.as_bytes())?;
+            eprintln!("stopped: {}", family.stop_reason);
+        }
+    }
+    Ok(())
+}
+
+fn event_name(event: Option<Event>) -> &'static str {
+    match event {
+        None => "",
+        Some(Event::Fold) => "fold",
+        Some(Event::Bifurcation) => "bifurcation",
+    }
+}
+
+fn family_csv(points: &[FamilyPoint]) -> String {
+    let mut csv = String::from(
+        "index,parameter,period,theta,energy,angular_momentum,residual,max_multiplier,unstable,stable,event",
+    );
+    let n = points.first().map_or(0, |p| p.bodies.len());
+    for i in 1..=n {
+        csv.push_str(&format!(",m{i},x{i},y{i},z{i},vx{i},vy{i},vz{i}"));
+    }
+    csv.push('\n');
+    for (i, p) in points.iter().enumerate() {
+        csv.push_str(&format!(
+            "{i},{:?},{:?},{:?},{:?},{:?},{:e},{:?},{},{},{}",
+            p.parameter,
+            p.period,
+            p.theta,
+            p.energy,
+            p.angular_momentum,
+            p.residual,
+            p.max_multiplier,
+            p.unstable,
+            p.stable,
+            event_name(p.event),
+        ));
+        for b in &p.bodies {
+            csv.push_str(&format!(
+                ",{:?},{:?},{:?},{:?},{:?},{:?},{:?}",
+                b.m, b.r[0], b.r[1], b.r[2], b.v[0], b.v[1], b.v[2]
+            ));
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+/// `{name: [[x, y, z, vx, vy, vz, m, ...], T, theta]}`, the custom-examples format of the
+/// web page.
+fn family_json(points: &[FamilyPoint], name: &str) -> String {
+    let entries: Vec<String> = points
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let ic: Vec<String> = p
+                .bodies
+                .iter()
+                .flat_map(|b| b.r.iter().chain(&b.v).chain([&b.m]))
+                .map(|x| format!("{x:?}"))
+                .collect();
+            format!(
+                "  \"{name}-{i:03}\": [[{}], {:?}, {:?}]",
+                ic.join(", "),
+                p.period,
+                p.theta
+            )
+        })
+        .collect();
+    format!("{{\n{}\n}}\n", entries.join(",\n"))
+}
+
+/// A batch file (`orbit-plot batch`) with one `[[orbit]]` per point.
+fn family_toml(points: &[FamilyPoint], name: &str) -> String {
+    let mut toml = String::from("method = \"dop853\"\nrtol = 1e-13\natol = 1e-15\n");
+    for (i, p) in points.iter().enumerate() {
+        toml.push_str(&format!(
+            "\n[[orbit]]\nname = \"{name}-{i:03}\"\nperiod = {:?}\n",
+            p.period
+        ));
+        if p.theta != 0.0 {
+            toml.push_str(&format!(
+                "# closes after a rotation about z by {:?}\n",
+                p.theta
+            ));
+        }
+        for b in &p.bodies {
+            toml.push_str(&format!(
+                "\n[[orbit.body]]\nmass = {:?}\nr = {:?}\nv = {:?}\n",
+                b.m, b.r, b.v
+            ));
+        }
+    }
+    toml
+}
@@ -0,0 +1,81 @@
+// `orbit-plot free-fall`: search the Agekyan–Anosova domain for brake orbits.
+//
+// Bodies start at rest at (-0.5, 0), (x, y) and (0.5, 0). Every orbit found is written as
+// a line `x,y,T,T|E|^(3/2)` with T the period, in the layout and number format of
+// `docs/examples/sd_80.csv`, so the file can replace it in the viewer.
+
+use std::{fs, io::Write, path::PathBuf};
+
+use three_body::{Axis, FreeFallOptions, search_brake_orbits};
+
+use crate::search::parse_axis;
+
+#[derive(clap::Args, Debug)]
+pub struct FreeFallArgs {
+    /// Range of x as MIN:MAX:STEPS (points outside the domain are skipped)
+    #[arg(long, value_parser = parse_axis, default_value = "0:0.5:51")]
+    x: Axis,
+    /// Range of y as MIN:MAX:STEPS
+    #[arg(long, value_parser = parse_axis, default_value = "0:0.87:88")]
+    y: Axis,
+    /// Masses of the bodies at (-0.5, 0), (x, y) and (0.5, 0)
+    #[arg(long, value_delimiter = ',', num_args = 3, default_value = "1,1,1")]
+    masses: Vec<f64>,
+    /// Ignore brake points before this time
+    #[arg(long, default_value_t = 0.5)]
+    t_min: f64,
+    /// Look for a brake point up to this time
+    #[arg(long, default_value_t = 10.0)]
+    t_max: f64,
+    /// Worker threads (0 = all cores)
+    #[arg(short = 'j', long, default_value_t = 0)]
+    threads: usize,
+    /// Write the CSV here instead of stdout
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+}
+
+pub fn run(args: &FreeFallArgs) -> anyhow::Result<()> {
+    let options = FreeFallOptions {
+        masses: [args.masses[0], args.masses[1], args.masses[2]],
+        t_min: args.t_min,
+        t_max: args.t_max,
+        threads: args.threads,
+        ..FreeFallOptions::default()
+    };
+    let orbits = search_brake_orbits(&args.x, &args.y, &options, |done, total| {
+        eprint!("\r{done}/{total}");
+        if done == total {
+            eprintln!();
+        }
+    });
+
+    let mut csv = String::new();
+    for o in &orbits {
+        let values = [o.x, o.y, o.period(), o.scaled_period()].map(sd_number);
+        csv.push_str(&values.join(","));
+        csv.push('\n');
+    }
+    match &args.output {
+        Some(path) => {
+            fs::write(path, csv)?;
+            println!("Done: {} ({} orbits)", path.display(), orbits.len());
+        }
+        None => std::io::stdout().write_all(csv.as_bytes())?,
+    }
+    Ok(())
+}
+
+/// `x` as `0.ddd…e±k` with a mantissa in [0.1, 1), like the entries of `sd_80.csv`.
+fn sd_number(x: f64) -> String {
+    if x == 0.0 {
+        return "0.0e0".to_string();
+    }
+    let s = format!("{:.16e}", x.abs());
+    let (mantissa, exponent) = s.split_once('e').expect("exponent in {:e} output");
+    let exponent: i32 = exponent.parse().expect("integer exponent");
+    let digits = mantissa.replace('.', "");
+    let digits = digits.trim_end_matches('0');
+    let sign = if x < 0.0 { "-" } else { "" };
+    format!("{sign}0.{digits}e{}", exponent + 1)
+}
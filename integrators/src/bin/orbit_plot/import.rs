@@ -0,0 +1,560 @@
+// Importers from the example lists of the viewer (`docs/examples`) to catalog orbits.
+//
+// The `.js` lists are read with a small parser for the literals they use: arrays,
+// objects with bare keys, strings, and numbers written as products and quotients of
+// numbers, `Math.PI` and earlier top-level constants (`2 * 6.061160`, `1/3`, `𝜃3_max`).
+// Comments are dropped first, which also picks up the rows that a list comments its own
+// brackets around (the Broucke A and R series of `broucke_boggs.js`). The initial
+// conditions are built by `three_body::ic`, as the lists' `getOrbit` functions do.
+
+use std::{collections::HashMap, path::Path};
+
+use anyhow::{Context, anyhow, bail};
+use three_body::{Body, CatalogEntry, Stability, ic, parse_free_fall, parse_li_liao_3d};
+
+use crate::catalog::{Exact, Orbit, StabilityCfg};
+
+/// Formats `orbit-plot catalog import` reads.
+pub const FORMATS: [&str; 10] = [
+    "equal_mass",
+    "unequal_mass",
+    "free_fall",
+    "choreographies",
+    "broucke_boggs",
+    "bhh_satellites",
+    "matt_sheen",
+    "li_liao_3d",
+    "puzynin",
+    "custom",
+];
+
+#[derive(Clone, Debug)]
+enum Js {
+    Num(f64),
+    Str(String),
+    Array(Vec<Js>),
+    Object(Vec<(String, Js)>),
+}
+
+impl Js {
+    fn num(&self) -> anyhow::Result<f64> {
+        match self {
+            Js::Num(x) => Ok(*x),
+            other => bail!("expected a number, got {other:?}"),
+        }
+    }
+
+    fn array(&self) -> anyhow::Result<&[Js]> {
+        match self {
+            Js::Array(a) => Ok(a),
+            other => bail!("expected an array, got {other:?}"),
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<&Js> {
+        match self {
+            Js::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn field(&self, key: &str) -> anyhow::Result<f64> {
+        self.get(key)
+            .ok_or_else(|| anyhow!("missing {key:?}"))?
+            .num()
+    }
+
+    /// Numbers print as JS does for integers (`name: 1` is "1").
+    fn label(&self) -> String {
+        match self {
+            Js::Str(s) => s.clone(),
+            Js::Num(x) => format!("{x}"),
+            other => format!("{other:?}"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Num(f64),
+    Str(String),
+    Ident(String),
+    Punct(char),
+}
+
+/// Source without `//` and `/* */` comments (outside strings).
+fn strip_comments(src: &str) -> String {
+    let mut out = String::with_capacity(src.len());
+    let mut chars = src.chars().peekable();
+    let mut quote: Option<char> = None;
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            out.push(c);
+            if c == '\\' {
+                out.extend(chars.next());
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('/', Some('/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut last = ' ';
+                for c in chars.by_ref() {
+                    if last == '*' && c == '/' {
+                        break;
+                    }
+                    last = c;
+                }
+            }
+            ('"' | '\'' | '`', _) => {
+                quote = Some(c);
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn tokenize(src: &str) -> anyhow::Result<Vec<Token>> {
+    let chars: Vec<char> = strip_comments(src).chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit()
+            || (c == '.' && chars.get(i + 1).is_some_and(char::is_ascii_digit))
+        {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_ascii_digit()
+                    || chars[i] == '.'
+                    || matches!(chars[i], 'e' | 'E')
+                    || (matches!(chars[i], '+' | '-') && matches!(chars[i - 1], 'e' | 'E')))
+            {
+                i += 1;
+            }
+            let s: String = chars[start..i].iter().collect();
+            tokens.push(Token::Num(
+                s.parse().with_context(|| format!("bad number {s:?}"))?,
+            ));
+        } else if matches!(c, '"' | '\'' | '`') {
+            let mut s = String::new();
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                if chars[i] == '\\' {
+                    i += 1;
+                }
+                if let Some(&ch) = chars.get(i) {
+                    s.push(ch);
+                }
+                i += 1;
+            }
+            i += 1;
+            tokens.push(Token::Str(s));
+        } else if c.is_alphanumeric() || c == '_' || c == '$' {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$')
+            {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else {
+            tokens.push(Token::Punct(c));
+            i += 1;
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    consts: &'a HashMap<String, Js>,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let t = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        t
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(&Token::Punct(c)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn value(&mut self) -> anyhow::Result<Js> {
+        match self.peek() {
+            Some(Token::Punct('[')) => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                while !self.eat(']') {
+                    items.push(self.value()?);
+                    if !self.eat(',') && self.peek() != Some(&Token::Punct(']')) {
+                        bail!("expected , or ] in array");
+                    }
+                }
+                Ok(Js::Array(items))
+            }
+            Some(Token::Punct('{')) => {
+                self.pos += 1;
+                let mut fields = Vec::new();
+                while !self.eat('}') {
+                    let key = match self.next() {
+                        Some(Token::Ident(k) | Token::Str(k)) => k,
+                        other => bail!("expected a key, got {other:?}"),
+                    };
+                    if !self.eat(':') {
+                        bail!("expected : after {key:?}");
+                    }
+                    fields.push((key, self.value()?));
+                    if !self.eat(',') && self.peek() != Some(&Token::Punct('}')) {
+                        bail!("expected , or }} in object");
+                    }
+                }
+                Ok(Js::Object(fields))
+            }
+            Some(Token::Str(_)) => match self.next() {
+                Some(Token::Str(s)) => Ok(Js::Str(s)),
+                _ => unreachable!(),
+            },
+            _ => self.product().map(Js::Num),
+        }
+    }
+
+    fn product(&mut self) -> anyhow::Result<f64> {
+        let mut x = self.factor()?;
+        loop {
+            if self.eat('*') {
+                x *= self.factor()?;
+            } else if self.eat('/') {
+                x /= self.factor()?;
+            } else {
+                return Ok(x);
+            }
+        }
+    }
+
+    fn factor(&mut self) -> anyhow::Result<f64> {
+        match self.next() {
+            Some(Token::Num(x)) => Ok(x),
+            Some(Token::Punct('-')) => Ok(-self.factor()?),
+            Some(Token::Punct('+')) => self.factor(),
+            Some(Token::Punct('(')) => {
+                let x = self.product()?;
+                if !self.eat(')') {
+                    bail!("expected )");
+                }
+                Ok(x)
+            }
+            Some(Token::Ident(name)) if name == "Math" => match (self.next(), self.next()) {
+                (Some(Token::Punct('.')), Some(Token::Ident(c))) if c == "PI" => {
+                    Ok(std::f64::consts::PI)
+                }
+                _ => bail!("only Math.PI is supported"),
+            },
+            Some(Token::Ident(name)) => match self.consts.get(&name) {
+                Some(Js::Num(x)) => Ok(*x),
+                _ => bail!("unknown constant {name}"),
+            },
+            other => bail!("unexpected {other:?}"),
+        }
+    }
+}
+
+/// The top-level `const NAME = <literal>;` declarations of a JS file that parse as
+/// literals (functions and the like are skipped).
+fn js_consts(src: &str) -> anyhow::Result<HashMap<String, Js>> {
+    let tokens = tokenize(src)?;
+    let mut consts = HashMap::new();
+    let mut depth = 0usize;
+    let mut i = 0;
+    while i < tokens.len() {
+        match &tokens[i] {
+            Token::Punct('{') => depth += 1,
+            Token::Punct('}') => depth = depth.saturating_sub(1),
+            Token::Ident(kw) if depth == 0 && matches!(kw.as_str(), "const" | "let") => {
+                if let (Some(Token::Ident(name)), Some(Token::Punct('='))) =
+                    (tokens.get(i + 1), tokens.get(i + 2))
+                {
+                    let mut p = Parser {
+                        tokens: &tokens,
+                        pos: i + 3,
+                        consts: &consts,
+                    };
+                    if let Ok(v) = p.value() {
+                        let end = p.pos;
+                        consts.insert(name.clone(), v);
+                        i = end;
+                        continue;
+                    }
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    Ok(consts)
+}
+
+fn list<'a>(consts: &'a HashMap<String, Js>, name: &str) -> anyhow::Result<&'a [Js]> {
+    consts
+        .get(name)
+        .ok_or_else(|| anyhow!("no `const {name} = [...]` in the file"))?
+        .array()
+}
+
+fn orbit(
+    name: String,
+    family: &str,
+    source: Option<&str>,
+    bodies: &[Body],
+    period: f64,
+    theta: f64,
+) -> Orbit {
+    Orbit {
+        name,
+        family: family.to_string(),
+        source: source.map(str::to_string),
+        period,
+        theta,
+        topology: None,
+        stability: None,
+        exact: None,
+        body: bodies.iter().map(Into::into).collect(),
+    }
+}
+
+fn from_entry(e: &CatalogEntry, family: &str, source: &str) -> Orbit {
+    let mut o = orbit(e.name.clone(), family, Some(source), &e.bodies, 0.0, 0.0);
+    o.period = e.period.to_string().parse().unwrap_or(f64::NAN);
+    o.stability = e.stability.map(|s| match s {
+        Stability::Stable => StabilityCfg::Stable,
+        Stability::Unstable => StabilityCfg::Unstable,
+    });
+    o.exact = Some(Exact {
+        state: e.state.iter().map(|x| x.to_string()).collect(),
+        period: e.period.to_string(),
+    });
+    o
+}
+
+/// Orbits of `path` read as `format` (one of `FORMATS`).
+pub fn import(format: &str, path: &Path) -> anyhow::Result<Vec<Orbit>> {
+    let text = std::fs::read_to_string(path)?;
+    let rows =
+        |name: &str| -> anyhow::Result<Vec<Js>> { Ok(list(&js_consts(&text)?, name)?.to_vec()) };
+    let each = |rows: Vec<Js>, f: &dyn Fn(usize, &Js) -> anyhow::Result<Orbit>| {
+        rows.iter()
+            .enumerate()
+            .map(|(i, r)| f(i, r).with_context(|| format!("row {}", i + 1)))
+            .collect::<anyhow::Result<Vec<Orbit>>>()
+    };
+    match format {
+        "equal_mass" | "unequal_mass" => {
+            let source = if format == "equal_mass" {
+                "Šuvakov & Dmitrašinović (2013)"
+            } else {
+                "https://numericaltank.sjtu.edu.cn/three-body/three-body-unequal-mass-movies.htm"
+            };
+            each(rows("ic")?, &|_, r| {
+                let r = r.array()?;
+                let label = r.first().map(Js::label).unwrap_or_default();
+                let (v1, v2, t) = (r[1].num()?, r[2].num()?, r[3].num()?);
+                let (m3, name) = match r.get(4) {
+                    Some(m) => (m.num()?, format!("{label}({})", m.num()?)),
+                    None => (1.0, label),
+                };
+                let bodies = ic::suvakov(v1, v2, m3);
+                Ok(orbit(name, format, Some(source), &bodies, t, 0.0))
+            })
+        }
+        "free_fall" => each(rows("ic")?, &|_, r| {
+            let r = r.array()?;
+            let name = r[0].label();
+            // F(m1, m3, m2): the second mass is that of the body at (0.5, 0)
+            let masses: Vec<f64> = name
+                .split_once('(')
+                .and_then(|(_, m)| m.strip_suffix(')'))
+                .ok_or_else(|| anyhow!("no masses in {name:?}"))?
+                .split(',')
+                .map(|m| m.trim().parse())
+                .collect::<Result<_, _>>()?;
+            let [m1, m3, m2] = masses[..] else {
+                bail!("expected 3 masses in {name:?}");
+            };
+            let bodies = ic::free_fall(r[1].num()?, r[2].num()?, [m1, m2, m3]);
+            Ok(orbit(name, format, None, &bodies, r[3].num()?, 0.0))
+        }),
+        "choreographies" => each(rows("data")?, &|i, r| {
+            let c: Vec<f64> = r.array()?.iter().map(Js::num).collect::<Result<_, _>>()?;
+            let [c1, c2, c3, c4, c5] = c[..] else {
+                bail!("expected 5 numbers");
+            };
+            Ok(orbit(
+                format!("Orbit {}", i + 1),
+                format,
+                Some(
+                    "https://analyticphysics.com/Gravitational%20Choreographies/Sim%C3%B3's%20Three-Body%20Choreographies%20in%20Action.htm",
+                ),
+                &ic::choreography(c1, c2, c3, c4),
+                c5,
+                0.0,
+            ))
+        }),
+        "broucke_boggs" => each(rows("DATA")?, &|_, r| {
+            let mass = |k: &str| r.get(k).map_or(Ok(1.0 / 3.0), Js::num);
+            let bodies = ic::broucke(
+                [mass("m1")?, mass("m2")?, mass("m3")?],
+                r.field("x1")?,
+                r.field("v1")?,
+                r.field("x2")?,
+                r.field("v2")?,
+            );
+            let name = match (r.get("name"), r.get("p_q")) {
+                (Some(n), _) => format!("Solution {}", n.label()),
+                (None, Some(pq)) => format!("Solution Broucke {}", pq.label()),
+                (None, None) => "Solution ?".to_string(),
+            };
+            Ok(orbit(
+                name,
+                format,
+                Some("https://articles.adsabs.harvard.edu/pdf/1975CeMec..11...13B"),
+                &bodies,
+                r.field("t")?,
+                0.0,
+            ))
+        }),
+        "bhh_satellites" => {
+            let consts = js_consts(&text)?;
+            let names = list(&consts, "NAMES")?;
+            each(list(&consts, "DATA")?.to_vec(), &|i, r| {
+                let m2 = r.get("m2").map_or(Ok(1.0), Js::num)?;
+                let bodies = ic::bhh(
+                    [r.field("m1")?, m2, r.field("m3")?],
+                    r.field("x1")?,
+                    r.field("v1")?,
+                    r.field("v3")?,
+                );
+                let name = names
+                    .get(i)
+                    .map_or_else(|| format!("BHH {}", i + 1), Js::label);
+                Ok(orbit(
+                    name,
+                    format,
+                    Some("Broucke, Hadjidemetriou & Hénon"),
+                    &bodies,
+                    r.field("t")?,
+                    r.field("𝜃")?,
+                ))
+            })
+        }
+        "matt_sheen" => each(rows("DATA")?, &|_, r| {
+            let bodies = r
+                .get("ic")
+                .ok_or_else(|| anyhow!("missing \"ic\""))?
+                .array()?
+                .iter()
+                .map(|b| {
+                    let b: Vec<f64> = b.array()?.iter().map(Js::num).collect::<Result<_, _>>()?;
+                    let [x, y, z, vx, vy, vz, m] = b[..] else {
+                        bail!("bodies are [x, y, z, vx, vy, vz, m]");
+                    };
+                    Ok(Body {
+                        m,
+                        r: [x, y, z],
+                        v: [vx, vy, vz],
+                    })
+                })
+                .collect::<anyhow::Result<Vec<Body>>>()?;
+            let name = format!(
+                "Solution {}",
+                r.get("name").map_or_else(String::new, Js::label)
+            );
+            Ok(orbit(
+                name,
+                format,
+                Some(
+                    "https://github.com/mws262/MAE5730_examples/blob/master/3BodySolutions/getSolutionNum.m",
+                ),
+                &bodies,
+                r.field("t")?,
+                0.0,
+            ))
+        }),
+        "li_liao_3d" => Ok(parse_li_liao_3d(&text)
+            .map_err(anyhow::Error::msg)?
+            .iter()
+            .map(|e| from_entry(e, format, "Li & Liao"))
+            .collect()),
+        "puzynin" => {
+            let prefix = path
+                .file_stem()
+                .map_or("free_fall".into(), |s| s.to_string_lossy());
+            Ok(parse_free_fall(&text, &prefix)
+                .map_err(anyhow::Error::msg)?
+                .iter()
+                .map(|e| from_entry(e, &prefix, "https://db2.fmi.uni-sofia.bg/3bodyfree/"))
+                .collect())
+        }
+        "custom" => {
+            // {name: [[x, y, z, vx, vy, vz, m, ...], T, theta]}
+            let examples: serde_json::Map<String, serde_json::Value> = serde_json::from_str(&text)?;
+            examples
+                .iter()
+                .map(|(name, v)| {
+                    let (state, t, theta): (Vec<f64>, f64, Option<f64>) =
+                        serde_json::from_value(v.clone())
+                            .or_else(|_| {
+                                serde_json::from_value::<(Vec<f64>, f64)>(v.clone())
+                                    .map(|(s, t)| (s, t, None))
+                            })
+                            .with_context(|| format!("{name}: expected [state, T, theta]"))?;
+                    if state.len() % 7 != 0 {
+                        bail!("{name}: state must have 7 numbers per body");
+                    }
+                    let bodies: Vec<Body> = state
+                        .chunks_exact(7)
+                        .map(|b| Body {
+                            m: b[6],
+                            r: [b[0], b[1], b[2]],
+                            v: [b[3], b[4], b[5]],
+                        })
+                        .collect();
+                    Ok(orbit(
+                        name.clone(),
+                        format,
+                        None,
+                        &bodies,
+                        t,
+                        theta.unwrap_or(0.0),
+                    ))
+                })
+                .collect()
+        }
+        other => bail!("unknown format {other:?} ({})", FORMATS.join(", ")),
+    }
+}
@@ -0,0 +1,518 @@
+use std::{fs, path::Path, path::PathBuf};
+
+use clap::{Parser, Subcommand};
+use plotters::{coord::Shift, prelude::*};
+use serde::{Deserialize, Serialize};
+use three_body::{self, AdaptiveSettings, Body, Frame, Method, to_frame};
+
+mod animate;
+mod batch;
+mod catalog;
+mod coordinates;
+mod diagnostics;
+mod export;
+mod family;
+mod free_fall;
+mod import;
+mod normalize;
+mod pdf;
+mod period;
+mod refine;
+mod search;
+mod shape_sphere;
+mod stability;
+mod symmetry;
+mod topology;
+mod verify;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "orbit-plot",
+    about = "Evolve and plot a 3-body orbit to PNG, SVG or PDF."
+)]
+struct Args {
+    /// Path to the TOML config
+    #[arg(short, long, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Animate the orbit as a GIF with fading trails
+    Animate(animate::AnimateArgs),
+    /// Integrate many orbits on a thread pool and write a CSV summary
+    Batch(batch::BatchArgs),
+    /// Import, convert, validate, verify and query orbit catalogs (TOML or JSON)
+    Catalog(catalog::CatalogArgs),
+    /// Trajectory in Jacobi or hyperspherical coordinates (with momenta) as CSV
+    Coordinates(coordinates::CoordinatesArgs),
+    /// Drift of energy, momenta, centre of mass and c²E over a run (compare integrators)
+    Diagnostics(diagnostics::DiagnosticsArgs),
+    /// Write the trajectory (optionally velocities and diagnostics) as CSV, JSON, .npy or .npz
+    Export(export::ExportArgs),
+    /// Follow a family of periodic orbits in a mass, the energy or the angular momentum
+    Continue(family::FamilyArgs),
+    /// Search the free-fall problem for brake orbits (sd_80.csv layout)
+    FreeFall(free_fall::FreeFallArgs),
+    /// Move a config to the barycentric frame and rescale it to an energy, period or size
+    Normalize(normalize::NormalizeArgs),
+    /// Estimate the period (and rotation angle) from the closest returns to the start
+    Period(period::PeriodArgs),
+    /// Refine the initial conditions and period by Newton shooting
+    Refine(refine::RefineArgs),
+    /// Scan a grid of initial velocities for near-returns (periodic-orbit candidates)
+    Search(search::SearchArgs),
+    /// Monodromy matrix and Floquet multipliers over one period (dop853 or feagin14)
+    Stability(stability::StabilityArgs),
+    /// Symmetry group of periodic orbits: permutations, time shifts, reflections, time reversal
+    Symmetry(symmetry::SymmetryArgs),
+    /// Syzygy sequence and free-group word of planar three-body orbits
+    Topology(topology::TopologyArgs),
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct BodyCfg {
+    mass: f64,
+    r: [f64; 3],
+    v: [f64; 3],
+}
+
+/// Optional overrides of an adaptive method's default settings.
+#[derive(Deserialize, Debug, Default)]
+struct Overrides {
+    rtol: Option<f64>,
+    atol: Option<f64>,
+    h_max: Option<f64>,
+    controller: Option<String>,
+}
+
+impl Overrides {
+    /// Settings for `method`: its defaults with the overrides applied (None if fixed-step).
+    fn settings(&self, method: Method) -> anyhow::Result<Option<AdaptiveSettings>> {
+        method
+            .default_settings()
+            .map(|base| self.apply(base))
+            .transpose()
+    }
+
+    /// `base` with the overrides applied.
+    fn apply(&self, base: AdaptiveSettings) -> anyhow::Result<AdaptiveSettings> {
+        let mut s = base;
+        if let Some(rtol) = self.rtol {
+            s.rtol = rtol;
+        }
+        if let Some(atol) = self.atol {
+            s.atol = atol;
+        }
+        if let Some(h_max) = self.h_max {
+            s.h_max = h_max;
+        }
+        if let Some(controller) = &self.controller {
+            s.controller = controller.parse().map_err(anyhow::Error::msg)?;
+        }
+        Ok(s)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct Cfg {
+    method: String,
+    period: f64,
+    #[serde(default = "default_output")]
+    output: String,
+    /// "png", "svg" or "pdf" (default: from the extension of `output`)
+    format: Option<String>,
+    #[serde(default = "default_width")]
+    width: u32,
+    #[serde(default = "default_height")]
+    height: u32,
+    /// "xy" (positions) or "shape_sphere"
+    #[serde(default = "default_projection")]
+    projection: String,
+    /// Frame of the "xy" plot: "inertial", "rotating", "pair" or "principal_axes"
+    #[serde(default = "default_frame")]
+    frame: String,
+    /// frame = "rotating": turn by this angle over the period (the viewer's theta_max)...
+    theta: Option<f64>,
+    /// ...or at this angular rate
+    omega: Option<f64>,
+    /// frame = "pair": bodies (1-based) put on the +x axis
+    pair: Option<[usize; 2]>,
+    /// Width of the orbits' lines in pixels (points in PDF), rounded to a whole number
+    #[serde(default = "default_line_width")]
+    line_width: f64,
+    /// Colours of the bodies as "#rrggbb" (default red, blue, black)
+    #[serde(default)]
+    colors: Vec<String>,
+    /// Legend labels of the bodies (default "Body 1", ...)
+    #[serde(default)]
+    labels: Vec<String>,
+    #[serde(default = "default_title")]
+    title: String,
+    /// Leave out the axes, the caption and the legend
+    #[serde(default)]
+    bare: bool,
+    #[serde(default)]
+    body: Vec<BodyCfg>,
+    // Overrides for the adaptive methods
+    #[serde(flatten)]
+    overrides: Overrides,
+}
+
+fn default_output() -> String {
+    "orbit.png".to_string()
+}
+
+fn default_width() -> u32 {
+    1200
+}
+
+fn default_height() -> u32 {
+    900
+}
+
+fn default_line_width() -> f64 {
+    1.0
+}
+
+fn default_title() -> String {
+    "Three-Body Orbit".to_string()
+}
+
+fn default_projection() -> String {
+    "xy".to_string()
+}
+
+fn default_frame() -> String {
+    "inertial".to_string()
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    match (&args.command, &args.config) {
+        (Some(Command::Animate(animate_args)), _) => animate::run(animate_args),
+        (Some(Command::Batch(batch_args)), _) => batch::run(batch_args),
+        (Some(Command::Catalog(catalog_args)), _) => catalog::run(catalog_args),
+        (Some(Command::Coordinates(coordinates_args)), _) => coordinates::run(coordinates_args),
+        (Some(Command::Diagnostics(diagnostics_args)), _) => diagnostics::run(diagnostics_args),
+        (Some(Command::Export(export_args)), _) => export::run(export_args),
+        (Some(Command::Continue(family_args)), _) => family::run(family_args),
+        (Some(Command::FreeFall(free_fall_args)), _) => free_fall::run(free_fall_args),
+        (Some(Command::Normalize(normalize_args)), _) => normalize::run(normalize_args),
+        (Some(Command::Period(period_args)), _) => period::run(period_args),
+        (Some(Command::Refine(refine_args)), _) => refine::run(refine_args),
+        (Some(Command::Search(search_args)), _) => search::run(search_args),
+        (Some(Command::Stability(stability_args)), _) => stability::run(stability_args),
+        (Some(Command::Symmetry(symmetry_args)), _) => symmetry::run(symmetry_args),
+        (Some(Command::Topology(topology_args)), _) => topology::run(topology_args),
+        (None, Some(config)) => plot(config),
+        (None, None) => anyhow::bail!("either --config FILE or a subcommand is required"),
+    }
+}
+
+fn plot(config: &Path) -> anyhow::Result<()> {
+    let toml_str = fs::read_to_string(config)?;
+    let cfg: Cfg = toml::from_str(&toml_str)?;
+
+    let format = image_format(&cfg)?;
+    if !(cfg.line_width > 0.0 && cfg.line_width.is_finite()) {
+        anyhow::bail!("line_width must be positive, got {}", cfg.line_width);
+    }
+    let mut bodies = build_ic(&cfg.body);
+
+    let method: Method = cfg.method.parse().map_err(anyhow::Error::msg)?;
+    let settings = cfg.overrides.settings(method)?;
+    let (series, _t_end) = method.evolve(&mut bodies, cfg.period, settings.as_ref());
+
+    let plot = match cfg.projection.as_str() {
+        "xy" => {
+            let series =
+                to_frame(&series, &masses(&cfg.body)?, frame(&cfg)?).map_err(anyhow::Error::msg)?;
+            let paths = reshape_paths(&series);
+            let bbox = bounds_with_aspect(&paths, cfg.width, cfg.height);
+            Plot::Xy { paths, bbox }
+        }
+        "shape_sphere" => Plot::ShapeSphere {
+            series,
+            masses: masses(&cfg.body)?,
+        },
+        other => anyhow::bail!("unknown projection {other:?} (xy or shape_sphere)"),
+    };
+
+    let size = (cfg.width, cfg.height);
+    match format.as_str() {
+        "svg" => render(
+            SVGBackend::new(&cfg.output, size).into_drawing_area(),
+            &plot,
+            &cfg,
+        )?,
+        "pdf" => render(
+            pdf::PdfBackend::new(&cfg.output, size).into_drawing_area(),
+            &plot,
+            &cfg,
+        )?,
+        _ => render(
+            BitMapBackend::new(&cfg.output, size).into_drawing_area(),
+            &plot,
+            &cfg,
+        )?,
+    }
+
+    println!("Done: {}", cfg.output);
+    Ok(())
+}
+
+/// "png", "svg" or "pdf", from `format` or else the extension of `output`.
+fn image_format(cfg: &Cfg) -> anyhow::Result<String> {
+    let format = match &cfg.format {
+        Some(format) => format.to_ascii_lowercase(),
+        None => Path::new(&cfg.output)
+            .extension()
+            .map_or("png".to_string(), |e| {
+                e.to_string_lossy().to_ascii_lowercase()
+            }),
+    };
+    if !matches!(format.as_str(), "png" | "svg" | "pdf") {
+        anyhow::bail!("unknown image format {format:?} (png, svg or pdf)");
+    }
+    Ok(format)
+}
+
+/// "#rrggbb"
+fn parse_color(s: &str) -> anyhow::Result<RGBColor> {
+    let hex = s
+        .strip_prefix('#')
+        .filter(|h| h.len() == 6 && h.is_ascii())
+        .ok_or_else(|| anyhow::anyhow!("colours are written #rrggbb, got {s:?}"))?;
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16);
+    Ok(RGBColor(channel(0)?, channel(2)?, channel(4)?))
+}
+
+fn masses(body: &[BodyCfg]) -> anyhow::Result<[f64; 3]> {
+    let [a, b, c] = body else {
+        anyhow::bail!("plots need exactly 3 bodies, got {}", body.len());
+    };
+    Ok([a.mass, b.mass, c.mass])
+}
+
+/// The plotting frame chosen by `frame` (and `theta`, `omega` or `pair`).
+fn frame(cfg: &Cfg) -> anyhow::Result<Frame> {
+    Ok(match cfg.frame.as_str() {
+        "inertial" => Frame::Inertial,
+        "rotating" => match (cfg.omega, cfg.theta) {
+            (Some(rate), _) => Frame::Rotating { rate },
+            (None, Some(theta)) => Frame::Rotating {
+                rate: theta / cfg.period,
+            },
+            (None, None) => anyhow::bail!("frame = \"rotating\" needs theta or omega"),
+        },
+        "pair" => {
+            let [i, j] = cfg.pair.unwrap_or([1, 2]);
+            if i == 0 || j == 0 {
+                anyhow::bail!("pair lists bodies from 1, got {i} and {j}");
+            }
+            Frame::Pair(i - 1, j - 1)
+        }
+        "principal_axes" => Frame::PrincipalAxes,
+        other => {
+            anyhow::bail!("unknown frame {other:?} (inertial, rotating, pair or principal_axes)")
+        }
+    })
+}
+
+fn build_ic(body: &[BodyCfg]) -> Vec<Body> {
+    let mut v = Vec::with_capacity(3);
+    for b in body {
+        let body = Body {
+            m: b.mass,
+            r: b.r,
+            v: b.v,
+        };
+        v.push(body);
+    }
+    v
+}
+
+/// Reshape the flat evolution buffer into per-body polylines.
+/// Input length must be steps * 10 (3 bodies × (x,y,z)  and time).
+fn reshape_paths(series: &[f64]) -> [Vec<(f64, f64)>; 3] {
+    let frame_len = 10; // 3 bodies × (x,y,z)  and time = 10
+    assert!(series.len().is_multiple_of(frame_len));
+    let steps = series.len() / frame_len;
+    let mut p1 = Vec::with_capacity(steps);
+    let mut p2 = Vec::with_capacity(steps);
+    let mut p3 = Vec::with_capacity(steps);
+
+    for k in 0..steps {
+        let base = k * frame_len;
+        p1.push((series[base], series[base + 1]));
+        p2.push((series[base + 3], series[base + 4]));
+        p3.push((series[base + 6], series[base + 7]));
+    }
+    [p1, p2, p3]
+}
+
+fn bounds_with_aspect(
+    paths: &[Vec<(f64, f64)>; 3],
+    width: u32,
+    height: u32,
+) -> (f64, f64, f64, f64) {
+    let mut min_x = f64::INFINITY;
+    let mut max_x = f64::NEG_INFINITY;
+    let mut min_y = f64::INFINITY;
+    let mut max_y = f64::NEG_INFINITY;
+
+    for p in paths {
+        for &(x, y) in p {
+            if x < min_x {
+                min_x = x;
+            }
+            if x > max_x {
+                max_x = x;
+            }
+            if y < min_y {
+                min_y = y;
+            }
+            if y > max_y {
+                max_y = y;
+            }
+        }
+    }
+
+    // small padding
+    let pad = 0.05;
+    let mut dx = (max_x - min_x).max(1e-12);
+    let mut dy = (max_y - min_y).max(1e-12);
+
+    // enforce data aspect = pixel aspect
+    let pixel_aspect = (width as f64) / (height as f64);
+    let data_aspect = dx / dy;
+
+    let (cx, cy) = ((min_x + max_x) * 0.5, (min_y + max_y) * 0.5);
+
+    if data_aspect < pixel_aspect {
+        // data too “tall”: widen x to match
+        dx = dy * pixel_aspect;
+    } else {
+        // data too “wide”: enlarge y to match
+        dy = dx / pixel_aspect;
+    }
+
+    let hx = 0.5 * dx * (1.0 + 2.0 * pad);
+    let hy = 0.5 * dy * (1.0 + 2.0 * pad);
+    (cx - hx, cx + hx, cy - hy, cy + hy)
+}
+
+/// What a plot config draws, ready for any backend.
+enum Plot {
+    Xy {
+        paths: [Vec<(f64, f64)>; 3],
+        bbox: (f64, f64, f64, f64),
+    },
+    ShapeSphere {
+        series: Vec<f64>,
+        masses: [f64; 3],
+    },
+}
+
+fn render<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    plot: &Plot,
+    cfg: &Cfg,
+) -> anyhow::Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    match plot {
+        Plot::Xy { paths, bbox } => render_xy(root, paths, *bbox, cfg),
+        Plot::ShapeSphere { series, masses } => {
+            shape_sphere::render(root, series, masses, stroke_width(cfg.line_width))
+        }
+    }
+}
+
+/// `line_width` in whole pixels, as plotters draws them.
+fn stroke_width(line_width: f64) -> u32 {
+    line_width.round().max(1.0) as u32
+}
+
+fn render_xy<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    paths: &[Vec<(f64, f64)>; 3],
+    bbox: (f64, f64, f64, f64),
+    cfg: &Cfg,
+) -> anyhow::Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    root.fill(&WHITE)?;
+
+    let (min_x, max_x, min_y, max_y) = bbox;
+    let mut builder = ChartBuilder::on(&root);
+    builder.margin(20);
+    if !cfg.bare {
+        builder
+            .caption(&cfg.title, ("sans-serif", 24))
+            .set_label_area_size(LabelAreaPosition::Left, 40)
+            .set_label_area_size(LabelAreaPosition::Bottom, 40);
+    }
+    let mut chart = builder.build_cartesian_2d(min_x..max_x, min_y..max_y)?;
+
+    if !cfg.bare {
+        chart
+            .configure_mesh()
+            .x_labels(10)
+            .y_labels(10)
+            .x_label_formatter(&|v| format!("{:.2}", v))
+            .y_label_formatter(&|v| format!("{:.2}", v))
+            .draw()?;
+    }
+
+    let mut colors = [RED.mix(0.9), BLUE.mix(0.9), BLACK.mix(0.9)];
+    for (color, s) in colors.iter_mut().zip(&cfg.colors) {
+        *color = parse_color(s)?.to_rgba();
+    }
+    for (i, poly) in paths.iter().enumerate() {
+        let style = colors[i].stroke_width(stroke_width(cfg.line_width));
+        let label = cfg
+            .labels
+            .get(i)
+            .cloned()
+            .unwrap_or_else(|| format!("Body {}", i + 1));
+        chart
+            .draw_series(LineSeries::new(poly.clone(), style))?
+            .label(label)
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], style));
+    }
+    // after draw_series for the lines
+    for (i, poly) in paths.iter().enumerate() {
+        if let Some(&(x0, y0)) = poly.first() {
+            chart.draw_series(std::iter::once(Circle::new(
+                (x0, y0),
+                /*radius px*/ 4,
+                ShapeStyle::from(&colors[i]).stroke_width(2),
+            )))?;
+        }
+        // end: OPEN circle (no fill)
+        if let Some(&(xn, yn)) = poly.last() {
+            chart.draw_series(std::iter::once(Circle::new(
+                (xn, yn),
+                5,
+                colors[i].filled(),
+            )))?;
+        }
+    }
+    if !cfg.bare {
+        chart
+            .configure_series_labels()
+            .border_style(BLACK.mix(0.3))
+            .background_style(WHITE.mix(0.8))
+            .draw()?;
+    }
+
+    root.present()?;
+    Ok(())
+}
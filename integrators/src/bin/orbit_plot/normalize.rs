@@ -0,0 +1,68 @@
+// `orbit-plot normalize`: a plot config moved to the barycentric frame and rescaled.
+//
+// Prints the new config (method, period and bodies) in the same TOML format, with the
+// scale factor and the period transformation as comments.
+
+use std::{fs, path::PathBuf};
+
+use three_body::{ScaleTarget, rescale_to, to_barycentric, total_energy};
+
+use crate::{Cfg, build_ic};
+
+#[derive(clap::Args, Debug)]
+#[command(group = clap::ArgGroup::new("target").args(["energy", "period", "inertia"]))]
+pub struct NormalizeArgs {
+    /// Path to the TOML config (same format as for plotting)
+    #[arg(value_name = "FILE")]
+    config: PathBuf,
+    /// Rescale to this total energy (e.g. -0.5 as Li & Liao)
+    #[arg(long, allow_hyphen_values = true)]
+    energy: Option<f64>,
+    /// Rescale to this period
+    #[arg(long)]
+    period: Option<f64>,
+    /// Rescale to this moment of inertia about the centre of mass
+    #[arg(long)]
+    inertia: Option<f64>,
+    /// Keep the frame (do not move the centre of mass to the origin at rest)
+    #[arg(long)]
+    keep_frame: bool,
+}
+
+pub fn run(args: &NormalizeArgs) -> anyhow::Result<()> {
+    let cfg: Cfg = toml::from_str(&fs::read_to_string(&args.config)?)?;
+    let mut bodies = build_ic(&cfg.body);
+    if !args.keep_frame {
+        to_barycentric(&mut bodies);
+    }
+    let target = if let Some(e) = args.energy {
+        Some(ScaleTarget::Energy(e))
+    } else if let Some(t) = args.period {
+        Some(ScaleTarget::Period {
+            current: cfg.period,
+            target: t,
+        })
+    } else {
+        args.inertia.map(ScaleTarget::Inertia)
+    };
+    let period = match target {
+        Some(target) => {
+            let scaling = rescale_to(&mut bodies, target).map_err(anyhow::Error::msg)?;
+            println!(
+                "# r -> {:?} r, v -> {:?} v, T -> {:?} T",
+                scaling.length,
+                scaling.velocity(),
+                scaling.time()
+            );
+            scaling.period(cfg.period)
+        }
+        None => cfg.period,
+    };
+
+    println!("# energy = {:?}", total_energy(&bodies));
+    println!("method = \"{}\"\nperiod = {period:?}", cfg.method);
+    for b in &bodies {
+        println!("\n[[body]]\nmass = {:?}\nr = {:?}\nv = {:?}", b.m, b.r, b.v);
+    }
+    Ok(())
+}
@@ -0,0 +1,325 @@
+// A minimal vector PDF backend for plotters.
+//
+// One page the size of the plot, one point per pixel, drawn with PDF path operators:
+// lines, paths and polygons keep their stroke widths, circles are four Bézier arcs and
+// text uses the standard Type 1 fonts (Helvetica, Times, Courier), so nothing is
+// embedded. Alpha goes through one ExtGState per opacity. Text is Latin-1 only; other
+// characters come out as '?'. The file is written by `present`.
+
+use std::{collections::BTreeMap, fmt::Write as _, fs, io, path::PathBuf};
+
+use plotters_backend::{
+    BackendColor, BackendCoord, BackendStyle, BackendTextStyle, DrawingBackend, DrawingErrorKind,
+    FontFamily, FontStyle, FontTransform,
+    text_anchor::{HPos, VPos},
+};
+
+pub struct PdfBackend {
+    path: PathBuf,
+    size: (u32, u32),
+    content: String,
+    /// Font resource name by base font.
+    fonts: BTreeMap<&'static str, String>,
+    /// ExtGState resource name by opacity in thousandths.
+    alphas: BTreeMap<u32, String>,
+}
+
+impl PdfBackend {
+    pub fn new(path: impl Into<PathBuf>, size: (u32, u32)) -> Self {
+        PdfBackend {
+            path: path.into(),
+            size,
+            content: String::new(),
+            fonts: BTreeMap::new(),
+            alphas: BTreeMap::new(),
+        }
+    }
+
+    /// Flip y: PDF pages start at the bottom left.
+    fn point(&self, (x, y): BackendCoord) -> (i32, i32) {
+        (x, self.size.1 as i32 - y)
+    }
+
+    /// Set the fill (`rg`) or stroke (`RG`) colour and the opacity; false if invisible.
+    fn color(&mut self, color: BackendColor, op: &str) -> bool {
+        if color.alpha <= 0.0 {
+            return false;
+        }
+        let (r, g, b) = color.rgb;
+        let c = |v: u8| v as f64 / 255.0;
+        let _ = writeln!(self.content, "{:.3} {:.3} {:.3} {op}", c(r), c(g), c(b));
+        let alpha = (color.alpha.min(1.0) * 1000.0).round() as u32;
+        let n = self.alphas.len();
+        let name = self
+            .alphas
+            .entry(alpha)
+            .or_insert_with(|| format!("A{n}"))
+            .clone();
+        let _ = writeln!(self.content, "/{name} gs");
+        true
+    }
+
+    fn stroke<S: BackendStyle>(&mut self, style: &S) -> bool {
+        let _ = writeln!(self.content, "{} w 1 J 1 j", style.stroke_width());
+        self.color(style.color(), "RG")
+    }
+
+    fn path(&mut self, points: impl IntoIterator<Item = BackendCoord>) {
+        for (i, p) in points.into_iter().enumerate() {
+            let (x, y) = self.point(p);
+            let op = if i == 0 { "m" } else { "l" };
+            let _ = writeln!(self.content, "{x} {y} {op}");
+        }
+    }
+
+    fn font(&mut self, family: FontFamily, style: FontStyle) -> String {
+        let base = match (family, style) {
+            (FontFamily::Serif, FontStyle::Bold) => "Times-Bold",
+            (FontFamily::Serif, FontStyle::Italic | FontStyle::Oblique) => "Times-Italic",
+            (FontFamily::Serif, _) => "Times-Roman",
+            (FontFamily::Monospace, FontStyle::Bold) => "Courier-Bold",
+            (FontFamily::Monospace, FontStyle::Italic | FontStyle::Oblique) => "Courier-Oblique",
+            (FontFamily::Monospace, _) => "Courier",
+            (_, FontStyle::Bold) => "Helvetica-Bold",
+            (_, FontStyle::Italic | FontStyle::Oblique) => "Helvetica-Oblique",
+            _ => "Helvetica",
+        };
+        let n = self.fonts.len();
+        self.fonts
+            .entry(base)
+            .or_insert_with(|| format!("F{n}"))
+            .clone()
+    }
+}
+
+/// `text` as a PDF string in WinAnsiEncoding.
+fn pdf_string(text: &str) -> String {
+    let mut s = String::from("(");
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                s.push('\\');
+                s.push(c);
+            }
+            ' '..='~' => s.push(c),
+            '\u{a0}'..='\u{ff}' => {
+                let _ = write!(s, "\\{:03o}", c as u32);
+            }
+            _ => s.push('?'),
+        }
+    }
+    s.push(')');
+    s
+}
+
+impl DrawingBackend for PdfBackend {
+    type ErrorType = io::Error;
+
+    fn get_size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    fn ensure_prepared(&mut self) -> Result<(), DrawingErrorKind<io::Error>> {
+        Ok(())
+    }
+
+    fn present(&mut self) -> Result<(), DrawingErrorKind<io::Error>> {
+        let (w, h) = self.size;
+        let fonts: String = self
+            .fonts
+            .iter()
+            .map(|(base, name)| {
+                format!(
+                    "/{name} << /Type /Font /Subtype /Type1 /BaseFont /{base} \
+                     /Encoding /WinAnsiEncoding >> "
+                )
+            })
+            .collect();
+        let alphas: String = self
+            .alphas
+            .iter()
+            .map(|(a, name)| {
+                let a = *a as f64 / 1000.0;
+                format!("/{name} << /CA {a} /ca {a} >> ")
+            })
+            .collect();
+        let objects = [
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {w} {h}] /Contents 4 0 R \
+                 /Resources << /Font << {fonts}>> /ExtGState << {alphas}>> >> >>"
+            ),
+            format!(
+                "<< /Length {} >>\nstream\n{}endstream",
+                self.content.len(),
+                self.content
+            ),
+        ];
+        let mut pdf = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::new();
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            let _ = write!(pdf, "{} 0 obj\n{object}\nendobj\n", i + 1);
+        }
+        let xref = pdf.len();
+        let _ = write!(pdf, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(pdf, "{offset:010} 00000 n ");
+        }
+        let _ = write!(
+            pdf,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            objects.len() + 1
+        );
+        fs::write(&self.path, pdf).map_err(DrawingErrorKind::DrawingError)
+    }
+
+    fn draw_pixel(
+        &mut self,
+        point: BackendCoord,
+        color: BackendColor,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        if self.color(color, "rg") {
+            let (x, y) = self.point(point);
+            let _ = writeln!(self.content, "{x} {} 1 1 re f", y - 1);
+        }
+        Ok(())
+    }
+
+    fn draw_line<S: BackendStyle>(
+        &mut self,
+        from: BackendCoord,
+        to: BackendCoord,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        self.draw_path([from, to], style)
+    }
+
+    fn draw_rect<S: BackendStyle>(
+        &mut self,
+        upper_left: BackendCoord,
+        bottom_right: BackendCoord,
+        style: &S,
+        fill: bool,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        let (x0, y0) = upper_left;
+        let (x1, y1) = bottom_right;
+        let corners = [(x0, y0), (x1, y0), (x1, y1), (x0, y1)];
+        if fill {
+            self.fill_polygon(corners, &style.color())
+        } else if self.stroke(style) {
+            self.path(corners);
+            self.content.push_str("s\n");
+            Ok(())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn draw_path<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(
+        &mut self,
+        path: I,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        if self.stroke(style) {
+            self.path(path);
+            self.content.push_str("S\n");
+        }
+        Ok(())
+    }
+
+    fn draw_circle<S: BackendStyle>(
+        &mut self,
+        center: BackendCoord,
+        radius: u32,
+        style: &S,
+        fill: bool,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        let visible = if fill {
+            self.color(style.color(), "rg")
+        } else {
+            self.stroke(style)
+        };
+        if !visible {
+            return Ok(());
+        }
+        // Quarter arcs with control points at k r along the tangents
+        let (cx, cy) = self.point(center);
+        let (cx, cy, r) = (cx as f64, cy as f64, radius as f64);
+        let k = 0.5523 * r;
+        let _ = writeln!(self.content, "{} {cy} m", cx + r);
+        for [(x1, y1), (x2, y2), (x3, y3)] in [
+            [(r, k), (k, r), (0.0, r)],
+            [(-k, r), (-r, k), (-r, 0.0)],
+            [(-r, -k), (-k, -r), (0.0, -r)],
+            [(k, -r), (r, -k), (r, 0.0)],
+        ] {
+            let _ = writeln!(
+                self.content,
+                "{} {} {} {} {} {} c",
+                cx + x1,
+                cy + y1,
+                cx + x2,
+                cy + y2,
+                cx + x3,
+                cy + y3
+            );
+        }
+        self.content.push_str(if fill { "f\n" } else { "s\n" });
+        Ok(())
+    }
+
+    fn fill_polygon<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(
+        &mut self,
+        vert: I,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        if self.color(style.color(), "rg") {
+            self.path(vert);
+            self.content.push_str("h f\n");
+        }
+        Ok(())
+    }
+
+    fn draw_text<TStyle: BackendTextStyle>(
+        &mut self,
+        text: &str,
+        style: &TStyle,
+        pos: BackendCoord,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        if !self.color(style.color(), "rg") {
+            return Ok(());
+        }
+        // Sizes as in plotters' SVG backend; the width comes from plotters' own layout
+        let size = style.size() / 1.24;
+        let (width, _) = self.estimate_text_size(text, style)?;
+        let width = width as f64 / 1.24;
+        let dx = match style.anchor().h_pos {
+            HPos::Left => 0.0,
+            HPos::Center => -width / 2.0,
+            HPos::Right => -width,
+        };
+        let dy = match style.anchor().v_pos {
+            VPos::Top => -0.76 * size,
+            VPos::Center => -0.25 * size,
+            VPos::Bottom => 0.25 * size,
+        };
+        // Clockwise on screen is clockwise on the page too, i.e. a negative angle
+        let (sin, cos) = match style.transform() {
+            FontTransform::Rotate90 => (-1.0, 0.0),
+            FontTransform::Rotate180 => (0.0, -1.0),
+            FontTransform::Rotate270 => (1.0, 0.0),
+            _ => (0.0, 1.0),
+        };
+        let font = self.font(style.family(), style.style());
+        let (x, y) = self.point(pos);
+        let _ = writeln!(
+            self.content,
+            "BT /{font} {size:.2} Tf {cos} {sin} {} {cos} {x} {y} Tm {dx:.2} {dy:.2} Td {} Tj ET",
+            -sin,
+            pdf_string(text)
+        );
+        Ok(())
+    }
+}
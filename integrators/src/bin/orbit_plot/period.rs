@@ -0,0 +1,67 @@
+// `orbit-plot period`: candidate periods of the initial conditions in a plot config.
+//
+// The config's `period` is optional here (it is what we are looking for); `method` and
+// the tolerance overrides are used as for plotting.
+
+use std::{fs, path::PathBuf};
+
+use serde::Deserialize;
+use three_body::{Method, PeriodOptions, estimate_periods};
+
+use crate::{BodyCfg, Overrides, build_ic};
+
+#[derive(clap::Args, Debug)]
+pub struct PeriodArgs {
+    /// Path to the TOML config (same format as for plotting)
+    #[arg(value_name = "FILE")]
+    config: PathBuf,
+    /// Ignore returns before this time
+    #[arg(long, default_value_t = 0.5)]
+    t_min: f64,
+    /// Integrate up to this time
+    #[arg(long, default_value_t = 50.0)]
+    t_max: f64,
+    /// Allow returns up to a rotation about z (relative periodic orbits)
+    #[arg(long)]
+    rotation: bool,
+    /// Number of candidates to report
+    #[arg(short = 'n', long, default_value_t = 10)]
+    candidates: usize,
+}
+
+#[derive(Deserialize, Debug)]
+struct IcCfg {
+    #[serde(default = "default_method")]
+    method: String,
+    #[serde(default)]
+    body: Vec<BodyCfg>,
+    #[serde(flatten)]
+    overrides: Overrides,
+}
+
+fn default_method() -> String {
+    "dop853".to_string()
+}
+
+pub fn run(args: &PeriodArgs) -> anyhow::Result<()> {
+    let cfg: IcCfg = toml::from_str(&fs::read_to_string(&args.config)?)?;
+    let method: Method = cfg.method.parse().map_err(anyhow::Error::msg)?;
+    let options = PeriodOptions {
+        method,
+        settings: cfg.overrides.settings(method)?,
+        t_min: args.t_min,
+        t_max: args.t_max,
+        rotation: args.rotation,
+        count: args.candidates,
+    };
+    let estimates = estimate_periods(&build_ic(&cfg.body), &options).map_err(anyhow::Error::msg)?;
+
+    println!("{:>20} {:>20} {:>12}", "period", "theta", "distance");
+    for e in &estimates {
+        println!(
+            "{:>20.12} {:>20.12} {:>12.3e}",
+            e.period, e.theta, e.distance
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,90 @@
+// `orbit-plot refine`: Newton shooting on the initial conditions of a plot config.
+//
+// Prints the closure residual before and after, and the corrected `period` and bodies in
+// the config's TOML format (full BigDecimal digits are listed too in high precision).
+
+use std::{fs, path::PathBuf};
+
+use three_body::{Method, ShootingOptions, refine_orbit};
+
+use crate::{Cfg, build_ic};
+
+#[derive(clap::Args, Debug)]
+pub struct RefineArgs {
+    /// Path to the TOML config (same format as for plotting)
+    #[arg(value_name = "FILE")]
+    config: PathBuf,
+    /// Integrate the residual with Feagin14 in BigDecimal
+    #[arg(long)]
+    high_precision: bool,
+    /// Also solve for a rotation about z (relative periodic orbits)
+    #[arg(long)]
+    rotation: bool,
+    /// Let the energy change instead of keeping that of the initial guess
+    #[arg(long)]
+    free_energy: bool,
+    /// Maximum number of Newton steps
+    #[arg(long, default_value_t = 20)]
+    max_iter: usize,
+    /// Target closure residual (default 1e-11, or 1e-25 in high precision)
+    #[arg(long)]
+    tol: Option<f64>,
+}
+
+pub fn run(args: &RefineArgs) -> anyhow::Result<()> {
+    let cfg: Cfg = toml::from_str(&fs::read_to_string(&args.config)?)?;
+    let mut options = if args.high_precision {
+        ShootingOptions::high_precision()
+    } else {
+        ShootingOptions {
+            method: cfg.method.parse().map_err(anyhow::Error::msg)?,
+            ..ShootingOptions::default()
+        }
+    };
+    // Config tolerances replace the corrector's defaults for the residual integrator
+    let method = if args.high_precision {
+        Method::Feagin14
+    } else {
+        options.method
+    };
+    if let Some(base) = options.settings.or(method.default_settings()) {
+        options.settings = Some(cfg.overrides.apply(base)?);
+    }
+    options.rotation = args.rotation;
+    options.fix_energy = !args.free_energy;
+    options.max_iterations = args.max_iter;
+    if let Some(tol) = args.tol {
+        options.tol = tol;
+    }
+
+    let bodies = build_ic(&cfg.body);
+    let r = refine_orbit(&bodies, cfg.period, &options).map_err(anyhow::Error::msg)?;
+
+    println!(
+        "residual: {:e} -> {:e} ({} Newton steps, {})",
+        r.residual_before,
+        r.residual_after,
+        r.iterations,
+        if r.converged {
+            "converged"
+        } else {
+            "not converged"
+        }
+    );
+    println!("period = {}", r.period.with_prec(17));
+    if args.rotation {
+        println!("# rotation about z: theta = {}", r.theta.with_prec(17));
+    }
+    for b in &r.bodies {
+        println!("\n[[body]]\nmass = {:?}\nr = {:?}\nv = {:?}", b.m, b.r, b.v);
+    }
+    if args.high_precision {
+        println!("\n# full precision: period, then [r, v] of every body");
+        println!("# {}", r.period);
+        for y in r.state.chunks(6) {
+            let s: Vec<String> = y.iter().map(|x| x.to_string()).collect();
+            println!("# {}", s.join(", "));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,126 @@
+// `orbit-plot search`: scan the (vx, vy) plane of Šuvakov & Dmitrašinović's initial
+// conditions for near-returns.
+//
+// Bodies of unit mass start at (-1, 0), (0, 0) and (1, 0) with velocities (vx, vy),
+// (-2vx, -2vy) and (vx, vy) (`ic::suvakov`). The ranked candidates are written as CSV
+// and, optionally, as plot configs that `orbit-plot refine` takes directly.
+
+use std::{fs, io::Write, path::PathBuf};
+
+use three_body::{Axis, Method, NearReturn, SearchOptions, ic, scan_grid};
+
+#[derive(clap::Args, Debug)]
+pub struct SearchArgs {
+    /// Range of vx as MIN:MAX:STEPS
+    #[arg(long, value_parser = parse_axis, allow_hyphen_values = true)]
+    vx: Axis,
+    /// Range of vy as MIN:MAX:STEPS
+    #[arg(long, value_parser = parse_axis, allow_hyphen_values = true)]
+    vy: Axis,
+    /// dop853, dopri5, bs32 or cash_karp
+    #[arg(long, default_value = "dop853")]
+    method: String,
+    /// Ignore returns before this time
+    #[arg(long, default_value_t = 1.0)]
+    t_min: f64,
+    /// Integrate every point up to this time
+    #[arg(long, default_value_t = 30.0)]
+    t_max: f64,
+    /// Worker threads (0 = all cores)
+    #[arg(short = 'j', long, default_value_t = 0)]
+    threads: usize,
+    /// Number of candidates to report
+    #[arg(short = 'n', long, default_value_t = 20)]
+    candidates: usize,
+    /// Write the CSV here instead of stdout
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+    /// Also write every candidate as a plot config (candidate-NN.toml) in this directory
+    #[arg(long, value_name = "DIR")]
+    configs: Option<PathBuf>,
+}
+
+pub fn parse_axis(s: &str) -> Result<Axis, String> {
+    let parts: Vec<&str> = s.split(':').collect();
+    let [min, max, steps] = parts[..] else {
+        return Err(format!("expected MIN:MAX:STEPS, got {s}"));
+    };
+    let f = |x: &str| x.parse::<f64>().map_err(|e| format!("{x}: {e}"));
+    Ok(Axis {
+        min: f(min)?,
+        max: f(max)?,
+        steps: steps.parse().map_err(|e| format!("{steps}: {e}"))?,
+    })
+}
+
+pub fn run(args: &SearchArgs) -> anyhow::Result<()> {
+    let method: Method = args.method.parse().map_err(anyhow::Error::msg)?;
+    let options = SearchOptions {
+        method,
+        t_min: args.t_min,
+        t_max: args.t_max,
+        threads: args.threads,
+        ..SearchOptions::default()
+    };
+    let scan = scan_grid(
+        |vx, vy| ic::suvakov(vx, vy, 1.0),
+        &args.vx,
+        &args.vy,
+        &options,
+        |done, total| {
+            eprint!("\r{done}/{total}");
+            if done == total {
+                eprintln!();
+            }
+        },
+    )
+    .map_err(anyhow::Error::msg)?;
+    let candidates = scan.candidates(args.candidates);
+
+    let mut csv = String::from("rank,vx,vy,distance,time\n");
+    for (rank, c) in candidates.iter().enumerate() {
+        csv.push_str(&format!(
+            "{rank},{:?},{:?},{:e},{:?}\n",
+            c.params[0], c.params[1], c.distance, c.time
+        ));
+    }
+    if let Some(dir) = &args.configs {
+        fs::create_dir_all(dir)?;
+        for (rank, c) in candidates.iter().enumerate() {
+            fs::write(
+                dir.join(format!("candidate-{rank:02}.toml")),
+                config(c, method),
+            )?;
+        }
+    }
+
+    match &args.output {
+        Some(path) => {
+            fs::write(path, csv)?;
+            let failed = scan.points.iter().filter(|p| p.is_err()).count();
+            println!(
+                "Done: {} ({} candidates from {} grid points, {failed} failed)",
+                path.display(),
+                candidates.len(),
+                scan.points.len()
+            );
+        }
+        None => std::io::stdout().write_all(csv.as_bytes())?,
+    }
+    Ok(())
+}
+
+/// Plot config of a candidate, with its return time as the period.
+fn config(c: &NearReturn, method: Method) -> String {
+    let mut toml = format!(
+        "# vx = {:?}, vy = {:?}: return distance {:e}\nmethod = \"{method}\"\nperiod = {:?}\n",
+        c.params[0], c.params[1], c.distance, c.time
+    );
+    for b in &c.bodies {
+        toml.push_str(&format!(
+            "\n[[body]]\nmass = {:?}\nr = {:?}\nv = {:?}\n",
+            b.m, b.r, b.v
+        ));
+    }
+    toml
+}
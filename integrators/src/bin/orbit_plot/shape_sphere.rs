@@ -0,0 +1,97 @@
+// `projection = "shape_sphere"`: draw the orbit on Montgomery's shape sphere.
+//
+// The sphere is drawn with n₃ (the signed area) pointing up, so the equator is the circle
+// of collinear configurations. Lagrange points are marked L+ / L-, binary collisions by
+// the pair that collides. Drawn on any plotters backend, like the xy plot.
+
+use plotters::{coord::Shift, prelude::*};
+
+use three_body::{collision_points, lagrange_points, shape_sphere};
+
+// Points per guide circle
+const CIRCLE_POINTS: usize = 180;
+
+/// Plot coordinates of a shape-sphere point (n₃ vertical).
+fn to_plot(n: &[f64]) -> (f64, f64, f64) {
+    (n[0], n[2], n[1])
+}
+
+fn circle(f: impl Fn(f64) -> (f64, f64, f64)) -> Vec<(f64, f64, f64)> {
+    (0..=CIRCLE_POINTS)
+        .map(|k| f(std::f64::consts::TAU * k as f64 / CIRCLE_POINTS as f64))
+        .collect()
+}
+
+pub fn render<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    series: &[f64],
+    masses: &[f64; 3],
+    line_width: u32,
+) -> anyhow::Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    let curve: Vec<(f64, f64, f64)> = shape_sphere(series, masses)
+        .chunks_exact(4)
+        .map(to_plot)
+        .collect();
+
+    root.fill(&WHITE)?;
+    let mut chart = ChartBuilder::on(&root)
+        .margin(20)
+        .caption("Shape sphere", ("sans-serif", 24))
+        .build_cartesian_3d(-1.1..1.1, -1.1..1.1, -1.1..1.1)?;
+    chart.with_projection(|mut p| {
+        p.yaw = 0.5;
+        p.pitch = 0.3;
+        p.scale = 0.9;
+        p.into_matrix()
+    });
+
+    // Equator (collinear configurations) and two meridians
+    let guide = BLACK.mix(0.25);
+    chart.draw_series(LineSeries::new(
+        circle(|a| (a.cos(), 0.0, a.sin())),
+        BLACK.mix(0.6),
+    ))?;
+    chart.draw_series(LineSeries::new(circle(|a| (a.cos(), a.sin(), 0.0)), guide))?;
+    chart.draw_series(LineSeries::new(circle(|a| (0.0, a.sin(), a.cos())), guide))?;
+
+    let style = BLUE.mix(0.9).stroke_width(line_width);
+    chart
+        .draw_series(LineSeries::new(curve.clone(), style))?
+        .label("Orbit")
+        .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], style));
+    if let (Some(&first), Some(&last)) = (curve.first(), curve.last()) {
+        chart.draw_series(std::iter::once(Circle::new(
+            first,
+            4,
+            ShapeStyle::from(&BLUE).stroke_width(2),
+        )))?;
+        chart.draw_series(std::iter::once(Circle::new(last, 5, BLUE.filled())))?;
+    }
+
+    let text = ("sans-serif", 16).into_font();
+    for (n, name) in lagrange_points(masses).iter().zip(["L+", "L-"]) {
+        chart.draw_series(std::iter::once(
+            EmptyElement::at(to_plot(n))
+                + Circle::new((0, 0), 5, GREEN.filled())
+                + Text::new(name, (8, -8), text.clone()),
+        ))?;
+    }
+    for (n, name) in collision_points(masses).iter().zip(["12", "23", "31"]) {
+        chart.draw_series(std::iter::once(
+            EmptyElement::at(to_plot(n))
+                + Cross::new((0, 0), 5, RED.stroke_width(2))
+                + Text::new(name, (8, -8), text.clone()),
+        ))?;
+    }
+
+    chart
+        .configure_series_labels()
+        .border_style(BLACK.mix(0.3))
+        .background_style(WHITE.mix(0.8))
+        .draw()?;
+    root.present()?;
+    Ok(())
+}
@@ -0,0 +1,40 @@
+// `orbit-plot stability`: monodromy matrix and Floquet multipliers of a plot config.
+//
+// Uses the config's `method` (dop853 or feagin14), `period`, bodies and tolerances.
+
+use std::{fs, path::PathBuf};
+
+use three_body::{Method, STABILITY_TOL, monodromy};
+
+use crate::{Cfg, build_ic};
+
+#[derive(clap::Args, Debug)]
+pub struct StabilityArgs {
+    /// Path to the TOML config (same format as for plotting)
+    #[arg(value_name = "FILE")]
+    config: PathBuf,
+}
+
+pub fn run(args: &StabilityArgs) -> anyhow::Result<()> {
+    let cfg: Cfg = toml::from_str(&fs::read_to_string(&args.config)?)?;
+    let method: Method = cfg.method.parse().map_err(anyhow::Error::msg)?;
+    let settings = cfg.overrides.settings(method)?;
+    let bodies = build_ic(&cfg.body);
+    let m =
+        monodromy(&bodies, cfg.period, method, settings.as_ref()).map_err(anyhow::Error::msg)?;
+
+    println!("Floquet multipliers ({method}, T = {}):", cfg.period);
+    for &(re, im) in &m.multipliers {
+        println!("  {re:>+.12} {im:>+.12}i   |λ| = {:.12}", re.hypot(im));
+    }
+    println!(
+        "max |λ| = {:.12} -> {} (tolerance {STABILITY_TOL:e})",
+        m.max_modulus,
+        if m.stable {
+            "S (linearly stable)"
+        } else {
+            "U (unstable)"
+        }
+    );
+    Ok(())
+}
@@ -0,0 +1,62 @@
+// `orbit-plot symmetry`: symmetry group of the periodic orbits in plot configs.
+//
+// Every config is integrated over its `period` with its `method` and tolerances; each
+// symmetry found is listed as r_σ(i)(ε t + τ) = M r_i(t).
+
+use std::{fs, path::PathBuf};
+
+use three_body::{Method, SymmetryOptions, find_symmetries};
+
+use crate::{Cfg, build_ic};
+
+#[derive(clap::Args, Debug)]
+pub struct SymmetryArgs {
+    /// Paths to TOML configs (same format as for plotting)
+    #[arg(value_name = "FILE", required = true)]
+    configs: Vec<PathBuf>,
+    /// Largest relative residual of an accepted symmetry
+    #[arg(long, default_value_t = 1e-6)]
+    tol: f64,
+    /// Points of the time grid over one period
+    #[arg(long, default_value_t = 360)]
+    samples: usize,
+}
+
+pub fn run(args: &SymmetryArgs) -> anyhow::Result<()> {
+    for path in &args.configs {
+        let cfg: Cfg = toml::from_str(&fs::read_to_string(path)?)?;
+        let method: Method = cfg.method.parse().map_err(anyhow::Error::msg)?;
+        let options = SymmetryOptions {
+            method,
+            settings: cfg.overrides.settings(method)?,
+            samples: args.samples,
+            tol: args.tol,
+        };
+        let name = path.display();
+        let symmetries = match find_symmetries(&build_ic(&cfg.body), cfg.period, &options) {
+            Ok(s) => s,
+            Err(e) => {
+                eprintln!("{name}: {e}");
+                continue;
+            }
+        };
+        println!("{name}: {}", symmetries.label());
+        for op in &symmetries.ops {
+            let sigma: Vec<String> = op.permutation.iter().map(|j| (j + 1).to_string()).collect();
+            println!(
+                "  σ = ({})  τ = {:.9} T  {}  M = {}({:+.9})  residual {:.2e}",
+                sigma.join(" "),
+                op.shift,
+                if op.time_reversal {
+                    "t -> -t"
+                } else {
+                    "       "
+                },
+                if op.reflection { "RF" } else { "R " },
+                op.angle,
+                op.residual
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,53 @@
+// `orbit-plot topology`: syzygy sequence and free-group word of plot configs.
+//
+// Every config is integrated over its period with its `method` and tolerances. Configs
+// with the same class label are the same orbit up to topology; they are listed at the end.
+
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use three_body::{Method, classify};
+
+use crate::{Cfg, build_ic};
+
+#[derive(clap::Args, Debug)]
+pub struct TopologyArgs {
+    /// Paths to TOML configs (same format as for plotting)
+    #[arg(value_name = "FILE", required = true)]
+    configs: Vec<PathBuf>,
+}
+
+pub fn run(args: &TopologyArgs) -> anyhow::Result<()> {
+    let mut classes: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for path in &args.configs {
+        let cfg: Cfg = toml::from_str(&fs::read_to_string(path)?)?;
+        let method: Method = cfg.method.parse().map_err(anyhow::Error::msg)?;
+        let settings = cfg.overrides.settings(method)?;
+        let bodies = build_ic(&cfg.body);
+        let name = path.display().to_string();
+        match classify(&bodies, cfg.period, method, settings.as_ref()) {
+            Ok(t) => {
+                println!(
+                    "{name}: {} syzygies {}  word {}  class {}",
+                    t.syzygies.len(),
+                    t.sequence(),
+                    t.word,
+                    t.canonical
+                );
+                classes.entry(t.canonical).or_default().push(name);
+            }
+            Err(e) => eprintln!("{name}: {e}"),
+        }
+    }
+
+    let duplicates: Vec<_> = classes
+        .iter()
+        .filter(|(_, names)| names.len() > 1)
+        .collect();
+    if !duplicates.is_empty() {
+        println!("\nSame class:");
+        for (class, names) in duplicates {
+            println!("  {class}: {}", names.join(", "));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,200 @@
+// `orbit-plot catalog verify`: integrate every orbit of catalogs over one period and
+// check that it closes.
+//
+// The closure error is the phase-space distance between the final state and the initial
+// one rotated by the orbit's `theta` about z, |y(T) - R(θ) y(0)|, relative to |y(0)|.
+// An orbit passes if that is within the tolerance, the integrator reached T and the
+// state stayed finite. The energy drift |E(T) - E(0)| / |E(0)| is reported alongside,
+// to tell a bad entry (energy kept, orbit open) from an integrator problem.
+
+use std::{fs, io::Write, path::PathBuf};
+
+use three_body::{AdaptiveSettings, Body, Job, Method, par_map, run_job, total_energy};
+
+use crate::{
+    Overrides,
+    batch::csv_field,
+    build_ic,
+    catalog::{self, Orbit},
+};
+
+#[derive(clap::Args, Debug)]
+pub struct VerifyArgs {
+    #[arg(value_name = "FILE", required = true)]
+    inputs: Vec<PathBuf>,
+    /// Integrator
+    #[arg(long, default_value = "dop853")]
+    method: String,
+    /// Relative tolerance of adaptive methods (default: the method's)
+    #[arg(long)]
+    rtol: Option<f64>,
+    /// Absolute tolerance of adaptive methods (default: the method's)
+    #[arg(long)]
+    atol: Option<f64>,
+    /// Largest relative closure error that passes (tables with few digits need more)
+    #[arg(long, default_value_t = 1e-4)]
+    tol: f64,
+    /// Worker threads (0 = all cores)
+    #[arg(short = 'j', long, default_value_t = 0)]
+    threads: usize,
+    /// Write the table here (Markdown if it ends in .md, CSV otherwise) instead of stdout
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+    /// Markdown instead of CSV on stdout
+    #[arg(long)]
+    markdown: bool,
+}
+
+/// Outcome of one orbit.
+struct Check {
+    t: f64,
+    closure: f64,
+    relative: f64,
+    energy_drift: f64,
+}
+
+fn norm(y: impl Iterator<Item = f64>) -> f64 {
+    y.map(|x| x * x).sum::<f64>().sqrt()
+}
+
+/// |y(T) - R(θ) y(0)| and |y(0)|.
+fn closure(start: &[Body], end: &[Body], theta: f64) -> (f64, f64) {
+    let (s, c) = theta.sin_cos();
+    let rotate = |a: [f64; 3]| [c * a[0] - s * a[1], s * a[0] + c * a[1], a[2]];
+    let diff = start.iter().zip(end).flat_map(|(a, b)| {
+        let (r, v) = (rotate(a.r), rotate(a.v));
+        (0..3).flat_map(move |k| [b.r[k] - r[k], b.v[k] - v[k]])
+    });
+    let size = start.iter().flat_map(|a| a.r.into_iter().chain(a.v));
+    (norm(diff), norm(size))
+}
+
+fn verify(o: &Orbit, method: Method, settings: Option<&AdaptiveSettings>) -> Result<Check, String> {
+    let job = Job {
+        bodies: build_ic(&o.body),
+        t_end: o.period,
+    };
+    let r = run_job(&job, method, settings)?;
+    let (closure, size) = closure(&job.bodies, &r.bodies, o.theta);
+    let e0 = total_energy(&job.bodies);
+    Ok(Check {
+        t: r.t,
+        closure,
+        relative: closure / size,
+        energy_drift: ((total_energy(&r.bodies) - e0) / e0).abs(),
+    })
+}
+
+pub fn run(args: &VerifyArgs) -> anyhow::Result<()> {
+    let catalog = catalog::read_all(&args.inputs)?;
+    let method: Method = args.method.parse().map_err(anyhow::Error::msg)?;
+    let overrides = Overrides {
+        rtol: args.rtol,
+        atol: args.atol,
+        ..Overrides::default()
+    };
+    let settings = overrides.settings(method)?;
+    let checks = par_map(
+        &catalog.orbit,
+        args.threads,
+        |o| verify(o, method, settings.as_ref()),
+        |done, total| {
+            eprint!("\r{done}/{total}");
+            if done == total {
+                eprintln!();
+            }
+        },
+    );
+
+    let markdown = args.markdown
+        || args
+            .output
+            .as_ref()
+            .is_some_and(|p| p.extension().is_some_and(|e| e == "md"));
+    let header = [
+        "family",
+        "name",
+        "period",
+        "theta",
+        "t",
+        "closure",
+        "relative_closure",
+        "energy_drift",
+        "status",
+        "error",
+    ];
+    let mut rows = Vec::new();
+    let mut failed = 0;
+    for (o, check) in catalog.orbit.iter().zip(&checks) {
+        let (values, error) = match check {
+            Ok(c) => {
+                let error = if c.t < o.period {
+                    format!("stopped at t = {}", c.t)
+                } else if c.relative.is_nan() || c.relative > args.tol {
+                    format!("does not close within {:e}", args.tol)
+                } else {
+                    String::new()
+                };
+                (
+                    vec![
+                        format!("{}", c.t),
+                        format!("{:e}", c.closure),
+                        format!("{:e}", c.relative),
+                        format!("{:e}", c.energy_drift),
+                    ],
+                    error,
+                )
+            }
+            Err(e) => (vec![String::new(); 4], e.clone()),
+        };
+        let status = if error.is_empty() { "pass" } else { "fail" };
+        if !error.is_empty() {
+            failed += 1;
+        }
+        let mut row = vec![
+            o.family.clone(),
+            o.name.clone(),
+            format!("{}", o.period),
+            format!("{}", o.theta),
+        ];
+        row.extend(values);
+        row.push(status.to_string());
+        row.push(error);
+        rows.push(row);
+    }
+
+    let table = if markdown {
+        let line = |cells: &[String]| format!("| {} |\n", cells.join(" | ").replace('\n', " "));
+        let mut md = line(&header.map(String::from));
+        md.push_str(&line(&header.map(|_| "---".to_string())));
+        for row in &rows {
+            md.push_str(&line(
+                &row.iter()
+                    .map(|c| c.replace('|', "\\|"))
+                    .collect::<Vec<_>>(),
+            ));
+        }
+        md
+    } else {
+        let mut csv = header.join(",") + "\n";
+        for row in &rows {
+            let cells: Vec<String> = row.iter().map(|c| csv_field(c)).collect();
+            csv.push_str(&(cells.join(",") + "\n"));
+        }
+        csv
+    };
+    match &args.output {
+        Some(path) => fs::write(path, table)?,
+        None => std::io::stdout().write_all(table.as_bytes())?,
+    }
+    eprintln!(
+        "{} orbits, {failed} failed (method {}, tolerance {:e})",
+        rows.len(),
+        method.name(),
+        args.tol
+    );
+    if failed > 0 {
+        anyhow::bail!("{failed} orbits failed verification");
+    }
+    Ok(())
+}
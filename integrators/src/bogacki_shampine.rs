@@ -0,0 +1,72 @@
+// Bogacki–Shampine 3(2) for N-body gravity (G=1).
+//
+// Four stages with FSAL (three force evaluations per accepted step); the cheapest
+// adaptive method here, meant for quick previews at loose tolerances.
+
+use crate::{
+    adaptive::{AdaptiveSettings, Controller, evolve_positions},
+    batch,
+    erk::{ErkPair, Tableau},
+    types::Body,
+};
+
+const A: [&[f64]; 3] = [
+    &[1.0 / 2.0],
+    &[0.0, 3.0 / 4.0],
+    &[2.0 / 9.0, 1.0 / 3.0, 4.0 / 9.0],
+];
+
+// 3rd-order weights (the last stage is evaluated at y_new: FSAL)
+const B: [f64; 4] = [2.0 / 9.0, 1.0 / 3.0, 4.0 / 9.0, 0.0];
+
+// E = b(3) - b_hat(2), with b_hat = [7/24, 1/4, 1/3, 1/8]
+const E: [f64; 4] = [
+    2.0 / 9.0 - 7.0 / 24.0,
+    1.0 / 3.0 - 1.0 / 4.0,
+    4.0 / 9.0 - 1.0 / 3.0,
+    -1.0 / 8.0,
+];
+
+pub(crate) static TABLEAU: Tableau = Tableau {
+    a: &A,
+    b: &B,
+    e: &E,
+    order: 2.0,
+    fsal: true,
+};
+
+pub const SETTINGS: AdaptiveSettings = AdaptiveSettings {
+    rtol: 1e-6,
+    atol: 1e-9,
+    max_steps: 5_000_000,
+    h_min: 1e-16,
+    h_max: f64::INFINITY,
+    controller: Controller::PI,
+};
+
+/// Evolve from t=0 to t=t_end with adaptive Bogacki–Shampine 3(2).
+/// Returns (flat positions history, final time reached).
+pub fn evolve(bodies: &mut [Body], t_end: f64) -> (Vec<f64>, f64) {
+    evolve_with(bodies, t_end, &SETTINGS)
+}
+
+/// `evolve` with explicit tolerances and controller.
+pub fn evolve_with(
+    bodies: &mut [Body],
+    t_end: f64,
+    settings: &AdaptiveSettings,
+) -> (Vec<f64>, f64) {
+    let eps2 = 0.0;
+    let mut pair = ErkPair::new(&TABLEAU, bodies, eps2);
+    evolve_positions(&mut pair, bodies, t_end, settings)
+}
+
+/// Integrate many systems (same number of bodies) in lockstep with Bogacki–Shampine, system s
+/// up to `t_end[s]`. Returns one `evolve`-style result per system, in input order.
+pub fn evolve_batch(
+    systems: &mut [Vec<Body>],
+    t_end: &[f64],
+    settings: &AdaptiveSettings,
+) -> Vec<(Vec<f64>, f64)> {
+    batch::evolve_batch(&TABLEAU, systems, t_end, settings)
+}
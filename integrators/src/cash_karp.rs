@@ -0,0 +1,88 @@
+// Cash–Karp 5(4) for N-body gravity (G=1).
+//
+// Six stages, no FSAL. We propagate the 5th-order solution (local extrapolation).
+
+use crate::{
+    adaptive::{AdaptiveSettings, Controller, evolve_positions},
+    batch,
+    erk::{ErkPair, Tableau},
+    types::Body,
+};
+
+const A: [&[f64]; 5] = [
+    &[1.0 / 5.0],
+    &[3.0 / 40.0, 9.0 / 40.0],
+    &[3.0 / 10.0, -9.0 / 10.0, 6.0 / 5.0],
+    &[-11.0 / 54.0, 5.0 / 2.0, -70.0 / 27.0, 35.0 / 27.0],
+    &[
+        1631.0 / 55296.0,
+        175.0 / 512.0,
+        575.0 / 13824.0,
+        44275.0 / 110592.0,
+        253.0 / 4096.0,
+    ],
+];
+
+// 5th-order weights
+const B: [f64; 6] = [
+    37.0 / 378.0,
+    0.0,
+    250.0 / 621.0,
+    125.0 / 594.0,
+    0.0,
+    512.0 / 1771.0,
+];
+
+// E = b(5) - b_hat(4), with b_hat = [2825/27648, 0, 18575/48384, 13525/55296, 277/14336, 1/4]
+const E: [f64; 6] = [
+    37.0 / 378.0 - 2825.0 / 27648.0,
+    0.0,
+    250.0 / 621.0 - 18575.0 / 48384.0,
+    125.0 / 594.0 - 13525.0 / 55296.0,
+    -277.0 / 14336.0,
+    512.0 / 1771.0 - 1.0 / 4.0,
+];
+
+pub(crate) static TABLEAU: Tableau = Tableau {
+    a: &A,
+    b: &B,
+    e: &E,
+    order: 4.0,
+    fsal: false,
+};
+
+pub const SETTINGS: AdaptiveSettings = AdaptiveSettings {
+    rtol: 1e-9,
+    atol: 1e-12,
+    max_steps: 5_000_000,
+    h_min: 1e-16,
+    h_max: f64::INFINITY,
+    controller: Controller::PI,
+};
+
+/// Evolve from t=0 to t=t_end with adaptive Cash–Karp 5(4).
+/// Returns (flat positions history, final time reached).
+pub fn evolve(bodies: &mut [Body], t_end: f64) -> (Vec<f64>, f64) {
+    evolve_with(bodies, t_end, &SETTINGS)
+}
+
+/// `evolve` with explicit tolerances and controller.
+pub fn evolve_with(
+    bodies: &mut [Body],
+    t_end: f64,
+    settings: &AdaptiveSettings,
+) -> (Vec<f64>, f64) {
+    let eps2 = 0.0;
+    let mut pair = ErkPair::new(&TABLEAU, bodies, eps2);
+    evolve_positions(&mut pair, bodies, t_end, settings)
+}
+
+/// Integrate many systems (same number of bodies) in lockstep with Cash–Karp, system s
+/// up to `t_end[s]`. Returns one `evolve`-style result per system, in input order.
+pub fn evolve_batch(
+    systems: &mut [Vec<Body>],
+    t_end: &[f64],
+    settings: &AdaptiveSettings,
+) -> Vec<(Vec<f64>, f64)> {
+    batch::evolve_batch(&TABLEAU, systems, t_end, settings)
+}
@@ -0,0 +1,162 @@
+// Parsers for the orbit catalogs shipped with the viewer (`docs/examples`).
+//
+// - `data.csv`: Li & Liao's 3D orbits, tab separated with a header line. Every row is
+//   `O_{index}(m3)  z0  vx  vy  vz  T  S|U`, with the initial conditions of `ic::li_liao_3d`.
+// - `sd_80.csv`, `sol_80_600.csv`: free-fall orbits of equal masses (Puzynin et al.),
+//   comma separated with 80 significant digits and no header. Every row is
+//   `x, y, T, T |E|^(3/2)`, with the initial conditions of `ic::free_fall`.
+//
+// Numbers are kept as BigDecimal, so the initial states and periods can be handed to
+// `refine_orbit_bd` or Feagin14 without losing digits; `bodies` has them rounded to f64.
+// Free-fall rows are named by file and line, `sd_80/1` being the first line (the viewer's
+// lists skip it as a header and start numbering at the second).
+
+use std::str::FromStr;
+
+use bigdecimal::{BigDecimal as BD, ToPrimitive, Zero};
+
+use crate::types::Body;
+
+/// Stability flag of a catalog entry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stability {
+    Stable,
+    Unstable,
+}
+
+/// One orbit of a catalog.
+#[derive(Clone, Debug)]
+pub struct CatalogEntry {
+    pub name: String,
+    pub masses: [f64; 3],
+    /// Initial state at full precision, packed as [r0, v0, r1, v1, r2, v2].
+    pub state: Vec<BD>,
+    pub period: BD,
+    /// `state` rounded to f64.
+    pub bodies: Vec<Body>,
+    /// Only listed by `data.csv`.
+    pub stability: Option<Stability>,
+    /// Scale-invariant period T |E|^(3/2) (free-fall tables only).
+    pub scaled_period: Option<BD>,
+}
+
+fn decimal(s: &str, line: usize) -> Result<BD, String> {
+    let s = s.trim();
+    BD::from_str(s).map_err(|e| format!("line {line}: bad number {s:?}: {e}"))
+}
+
+fn to_bodies(state: &[BD], masses: [f64; 3]) -> Vec<Body> {
+    let y: Vec<f64> = state.iter().map(|x| x.to_f64().unwrap()).collect();
+    y.chunks_exact(6)
+        .zip(masses)
+        .map(|(b, m)| Body {
+            m,
+            r: [b[0], b[1], b[2]],
+            v: [b[3], b[4], b[5]],
+        })
+        .collect()
+}
+
+/// Parse Li & Liao's `data.csv`.
+pub fn parse_li_liao_3d(text: &str) -> Result<Vec<CatalogEntry>, String> {
+    let mut entries = Vec::new();
+    for (i, row) in text.lines().enumerate().skip(1) {
+        let line = i + 1;
+        if row.trim().is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = row.split('\t').map(str::trim).collect();
+        let [name, z0, vx, vy, vz, t, stability] = fields[..] else {
+            return Err(format!(
+                "line {line}: expected 7 tab-separated fields, got {}",
+                fields.len()
+            ));
+        };
+        let m3 = name
+            .rsplit_once('(')
+            .and_then(|(_, m)| m.strip_suffix(')'))
+            .ok_or_else(|| format!("line {line}: no mass in label {name:?}"))?;
+        let m3_exact = decimal(m3, line)?;
+        if m3_exact <= BD::zero() {
+            return Err(format!("line {line}: mass must be positive, got {m3}"));
+        }
+        let stability = match stability {
+            "S" => Stability::Stable,
+            "U" => Stability::Unstable,
+            s => return Err(format!("line {line}: stability must be S or U, got {s:?}")),
+        };
+        let [z0, vx, vy, vz] = [z0, vx, vy, vz].map(|x| decimal(x, line));
+        let (z0, vx, vy, vz) = (z0?, vx?, vy?, vz?);
+        let zero = BD::zero;
+        let one = BD::from(1);
+        let w = BD::from(-2) / &m3_exact;
+        let state = vec![
+            -one.clone(),
+            zero(),
+            zero(),
+            vx.clone(),
+            vy.clone(),
+            vz.clone(),
+            one,
+            zero(),
+            zero(),
+            vx.clone(),
+            vy.clone(),
+            -vz,
+            zero(),
+            zero(),
+            z0,
+            &w * vx,
+            &w * vy,
+            zero(),
+        ];
+        let masses = [1.0, 1.0, m3_exact.to_f64().unwrap()];
+        entries.push(CatalogEntry {
+            name: name.to_string(),
+            masses,
+            bodies: to_bodies(&state, masses),
+            state,
+            period: decimal(t, line)?,
+            stability: Some(stability),
+            scaled_period: None,
+        });
+    }
+    Ok(entries)
+}
+
+/// Parse a free-fall table (`sd_80.csv`, `sol_80_600.csv`); entries are named
+/// `{prefix}/{line}`.
+pub fn parse_free_fall(text: &str, prefix: &str) -> Result<Vec<CatalogEntry>, String> {
+    let mut entries = Vec::new();
+    for (i, row) in text.lines().enumerate() {
+        let line = i + 1;
+        if row.trim().is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = row.split(',').collect();
+        let [x, y, t, scaled] = fields[..] else {
+            return Err(format!(
+                "line {line}: expected 4 comma-separated fields, got {}",
+                fields.len()
+            ));
+        };
+        let zero = BD::zero;
+        let half = BD::from_str("0.5").unwrap();
+        let mut state = vec![zero(); 18];
+        state[0] = -half.clone();
+        state[6] = decimal(x, line)?;
+        state[7] = decimal(y, line)?;
+        state[12] = half;
+        let masses = [1.0; 3];
+        entries.push(CatalogEntry {
+            name: format!("{prefix}/{line}"),
+            masses,
+            bodies: to_bodies(&state, masses),
+            state,
+            period: decimal(t, line)?,
+            stability: None,
+            scaled_period: Some(decimal(scaled, line)?),
+        });
+    }
+    Ok(entries)
+}
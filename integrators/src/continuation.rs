@@ -0,0 +1,451 @@
+// Pseudo-arclength continuation of periodic orbit families.
+//
+// Unknowns x = [y0 (6N), T, (θ), p] where p is the continuation parameter: one mass, the
+// energy or the z angular momentum. At each point the shooting equations of `shooting`
+// (closure of the return map plus the phase conditions) are augmented with
+//
+//     τ · (x - x_pred) = 0,    x_pred = x_i + ds τ
+//
+// where τ is the unit tangent of the family at x_i (the secant through the last two
+// points after the first step). The extra row lets the family turn back in p at folds.
+// With a mass as the parameter the energy is held at its starting value and ∂F/∂m comes
+// from central differences; with the energy or L_z as the parameter a row E(y0) = p or
+// L_z(y0) = p is added instead. The centre of mass stays at the origin with zero momentum.
+//
+// Folds show up as a sign change of dp along the family, bifurcations as a change in the
+// number of Floquet multipliers off the unit circle.
+
+use crate::{
+    adaptive::AdaptiveSettings,
+    dop853, erk,
+    method::Method,
+    shooting::{
+        JACOBIAN_SETTINGS, PhaseTargets, bodies_from, damped_step, energy, energy_gradient, norm,
+        phase_rows, return_map_rows, rotate_z, to_centre_of_mass,
+    },
+    types::Body,
+    variational::{self, Monodromy, STABILITY_TOL},
+};
+
+// Tiny damping, only to survive a momentarily rank-deficient system
+const LAMBDA: f64 = 1e-10;
+
+/// Quantity stepped along the family.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Parameter {
+    /// Mass of body k (energy held fixed).
+    Mass(usize),
+    /// Total energy.
+    Energy,
+    /// z component of the total angular momentum (energy held fixed). Orbits with
+    /// L_z != 0 generally close only up to a rotation, so set `rotation` too.
+    AngularMomentum,
+}
+
+/// Options of `continue_family`.
+#[derive(Clone, Copy, Debug)]
+pub struct ContinuationOptions {
+    pub parameter: Parameter,
+    /// Initial arclength step; its sign chooses the direction in the parameter.
+    pub step: f64,
+    pub min_step: f64,
+    pub max_step: f64,
+    /// Points of the family, including the starting orbit.
+    pub max_points: usize,
+    /// Stop once the parameter leaves [min, max].
+    pub bounds: (f64, f64),
+    /// Also solve for a rotation angle θ about z (relative periodic orbits).
+    pub rotation: bool,
+    /// Corrector tolerance on the residual of the augmented system.
+    pub tol: f64,
+    /// Newton iterations per point before the step is halved.
+    pub max_newton: usize,
+    /// Settings of the DOP853 integration of the closure residual.
+    pub settings: AdaptiveSettings,
+}
+
+impl Default for ContinuationOptions {
+    fn default() -> Self {
+        ContinuationOptions {
+            parameter: Parameter::Mass(2),
+            step: 0.01,
+            min_step: 1e-6,
+            max_step: 0.1,
+            max_points: 50,
+            bounds: (f64::NEG_INFINITY, f64::INFINITY),
+            rotation: false,
+            tol: 1e-10,
+            max_newton: 8,
+            settings: AdaptiveSettings {
+                rtol: 1e-13,
+                atol: 1e-15,
+                ..dop853::SETTINGS
+            },
+        }
+    }
+}
+
+/// Something the family went through between the previous point and this one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// The parameter turned back.
+    Fold,
+    /// The number of unstable multipliers changed.
+    Bifurcation,
+}
+
+/// One converged orbit of the family.
+#[derive(Clone, Debug)]
+pub struct FamilyPoint {
+    /// Value of the continuation parameter.
+    pub parameter: f64,
+    /// Initial conditions in the centre-of-mass frame.
+    pub bodies: Vec<Body>,
+    pub period: f64,
+    /// Rotation about z that closes the orbit (0 unless `rotation` was requested).
+    pub theta: f64,
+    pub energy: f64,
+    /// z component of the total angular momentum.
+    pub angular_momentum: f64,
+    /// Closure residual |F|.
+    pub residual: f64,
+    /// Largest |Floquet multiplier|.
+    pub max_multiplier: f64,
+    /// Multipliers with |λ| > 1 + `STABILITY_TOL`.
+    pub unstable: usize,
+    pub stable: bool,
+    pub event: Option<Event>,
+}
+
+/// A continued family and why the continuation stopped.
+#[derive(Clone, Debug)]
+pub struct Family {
+    pub points: Vec<FamilyPoint>,
+    pub stop_reason: String,
+}
+
+struct Continuer<'a> {
+    masses: Vec<f64>,
+    options: &'a ContinuationOptions,
+    /// Energy held fixed (mass and angular-momentum continuation).
+    energy: Option<f64>,
+    dim: usize,
+    cols: usize,
+}
+
+impl Continuer<'_> {
+    fn masses(&self, x: &[f64]) -> Vec<f64> {
+        let mut masses = self.masses.clone();
+        if let Parameter::Mass(k) = self.options.parameter {
+            masses[k] = x[self.cols - 1];
+        }
+        masses
+    }
+
+    fn theta(&self, x: &[f64]) -> Option<f64> {
+        self.options.rotation.then(|| x[self.dim + 1])
+    }
+
+    /// Closure residual R(-θ) y(T) - y0 with the given masses.
+    fn closure(&self, x: &[f64], masses: &[f64]) -> Result<Vec<f64>, String> {
+        let y0 = &x[..self.dim];
+        let t = x[self.dim];
+        let mut bodies = bodies_from(y0, masses);
+        let (_, t_reached) = dop853::evolve_with(&mut bodies, t, &self.options.settings);
+        if t_reached != t {
+            return Err(format!("Integration stopped at t = {t_reached} before {t}"));
+        }
+        let mut y_t = erk::pack_state(&bodies);
+        rotate_z(&mut y_t, -self.theta(x).unwrap_or(0.0));
+        let f: Vec<f64> = y_t.iter().zip(y0).map(|(a, b)| a - b).collect();
+        if f.iter().any(|v| !v.is_finite()) {
+            return Err("Closure residual is not finite".to_string());
+        }
+        Ok(f)
+    }
+
+    /// Right-hand sides (minus the residuals) of everything but the extra row, and |F|.
+    fn rhs(&self, x: &[f64]) -> Result<(Vec<f64>, f64), String> {
+        let masses = self.masses(x);
+        let f = self.closure(x, &masses)?;
+        let mut b: Vec<f64> = f.iter().map(|v| -v).collect();
+        b.extend(self.constraints(x, &masses).1);
+        Ok((b, norm(&f)))
+    }
+
+    /// Phase conditions and the parameter row, as (rows, rhs).
+    fn constraints(&self, x: &[f64], masses: &[f64]) -> (Vec<f64>, Vec<f64>) {
+        let y0 = &x[..self.dim];
+        let p = x[self.cols - 1];
+        let targets = PhaseTargets {
+            energy: self.energy,
+            centre: [0.0; 3],
+            mass_param: match self.options.parameter {
+                Parameter::Mass(k) => Some(k),
+                _ => None,
+            },
+        };
+        let (mut a, mut b) = phase_rows(y0, masses, &targets, self.cols);
+        let mut row = vec![0.0; self.cols];
+        match self.options.parameter {
+            Parameter::Mass(_) => return (a, b),
+            Parameter::Energy => {
+                row[..self.dim].copy_from_slice(&energy_gradient(y0, masses));
+                b.push(p - energy(y0, masses));
+            }
+            Parameter::AngularMomentum => {
+                for (i, m) in masses.iter().enumerate() {
+                    let (r, v) = (&y0[6 * i..6 * i + 3], &y0[6 * i + 3..6 * i + 6]);
+                    row[6 * i..6 * i + 6].copy_from_slice(&[
+                        m * v[1],
+                        -m * v[0],
+                        0.0,
+                        -m * r[1],
+                        m * r[0],
+                        0.0,
+                    ]);
+                }
+                b.push(p - angular_momentum(y0, masses));
+            }
+        }
+        row[self.cols - 1] = -1.0;
+        a.extend(row);
+        (a, b)
+    }
+
+    /// Jacobian of the closure and the constraints (without the extra row).
+    fn jacobian(&self, x: &[f64]) -> Result<Vec<f64>, String> {
+        let masses = self.masses(x);
+        let y0 = &x[..self.dim];
+        let (mut a, _) = return_map_rows(y0, &masses, x[self.dim], self.theta(x), self.cols)?;
+        if let Parameter::Mass(k) = self.options.parameter {
+            // ∂F/∂m_k by central differences
+            let m = masses[k];
+            let h = 1e-6 * m.max(1.0);
+            let mut plus = masses.clone();
+            plus[k] = m + h;
+            let mut minus = masses.clone();
+            minus[k] = m - h;
+            let (fp, fm) = (self.closure(x, &plus)?, self.closure(x, &minus)?);
+            for (i, (a_p, a_m)) in fp.iter().zip(&fm).enumerate() {
+                a[i * self.cols + self.cols - 1] = (a_p - a_m) / (2.0 * h);
+            }
+        }
+        a.extend(self.constraints(x, &masses).0);
+        Ok(a)
+    }
+
+    /// Newton iteration on the system plus the row `c · x = target`.
+    /// Returns the converged point, the iterations taken and |F|.
+    fn correct(
+        &self,
+        mut x: Vec<f64>,
+        c: &[f64],
+        target: f64,
+    ) -> Result<(Vec<f64>, usize, f64), String> {
+        for it in 0..=self.options.max_newton {
+            let (mut b, closure) = self.rhs(&x)?;
+            b.push(target - dot(c, &x));
+            if norm(&b) <= self.options.tol {
+                return Ok((x, it, closure));
+            }
+            if it == self.options.max_newton {
+                break;
+            }
+            let mut a = self.jacobian(&x)?;
+            a.extend_from_slice(c);
+            let delta = damped_step(&a, b.len(), self.cols, &b, LAMBDA)?;
+            for (xi, d) in x.iter_mut().zip(&delta) {
+                *xi += d;
+            }
+        }
+        Err(format!(
+            "Corrector did not converge in {} iterations",
+            self.options.max_newton
+        ))
+    }
+
+    /// Unit tangent at a converged point, oriented so that dp has the sign of `dir`.
+    fn tangent(&self, x: &[f64], dir: f64) -> Result<Vec<f64>, String> {
+        let mut a = self.jacobian(x)?;
+        let mut b = vec![0.0; a.len() / self.cols];
+        let mut row = vec![0.0; self.cols];
+        row[self.cols - 1] = 1.0;
+        a.extend(row);
+        b.push(dir.signum());
+        let t = damped_step(&a, b.len(), self.cols, &b, LAMBDA)?;
+        let n = norm(&t);
+        Ok(t.iter().map(|v| v / n).collect())
+    }
+
+    fn point(&self, x: &[f64], residual: f64) -> Result<FamilyPoint, String> {
+        let masses = self.masses(x);
+        let y0 = &x[..self.dim];
+        let bodies = bodies_from(y0, &masses);
+        let period = x[self.dim];
+        let theta = self.theta(x).unwrap_or(0.0);
+        let monodromy = relative_monodromy(&bodies, period, theta)?;
+        let unstable = monodromy
+            .multipliers
+            .iter()
+            .filter(|&&(re, im)| re.hypot(im) > 1.0 + STABILITY_TOL)
+            .count();
+        Ok(FamilyPoint {
+            parameter: x[self.cols - 1],
+            energy: energy(y0, &masses),
+            angular_momentum: angular_momentum(y0, &masses),
+            bodies,
+            period,
+            theta,
+            residual,
+            max_multiplier: monodromy.max_modulus,
+            unstable,
+            stable: monodromy.stable,
+            event: None,
+        })
+    }
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn angular_momentum(y: &[f64], masses: &[f64]) -> f64 {
+    masses
+        .iter()
+        .enumerate()
+        .map(|(i, m)| m * (y[6 * i] * y[6 * i + 4] - y[6 * i + 1] * y[6 * i + 3]))
+        .sum()
+}
+
+/// Monodromy of the return map R(-θ) y(T), whose multipliers classify relative
+/// periodic orbits too.
+fn relative_monodromy(bodies: &[Body], period: f64, theta: f64) -> Result<Monodromy, String> {
+    let m = variational::monodromy(bodies, period, Method::Dop853, Some(&JACOBIAN_SETTINGS))?;
+    if theta == 0.0 {
+        return Ok(m);
+    }
+    let dim = m.dim;
+    let mut matrix = m.matrix;
+    for q in 0..dim {
+        let mut column: Vec<f64> = (0..dim).map(|p| matrix[p * dim + q]).collect();
+        rotate_z(&mut column, -theta);
+        for (p, v) in column.into_iter().enumerate() {
+            matrix[p * dim + q] = v;
+        }
+    }
+    Monodromy::from_matrix(matrix, dim)
+}
+
+/// Continue the periodic orbit (`bodies`, `period`) in `options.parameter`.
+///
+/// The starting orbit is moved to its centre-of-mass frame and corrected first; it is
+/// an error if that does not converge. After that the family is followed until
+/// `max_points`, the parameter bounds, or a step below `min_step`, and the reason is
+/// reported in `Family::stop_reason`.
+pub fn continue_family(
+    bodies: &[Body],
+    period: f64,
+    options: &ContinuationOptions,
+) -> Result<Family, String> {
+    let n = bodies.len();
+    if let Parameter::Mass(k) = options.parameter
+        && k >= n
+    {
+        return Err(format!("No body {} (there are {n})", k + 1));
+    }
+    if options.step == 0.0 || !options.step.is_finite() {
+        return Err(format!(
+            "step must be finite and non-zero, got {}",
+            options.step
+        ));
+    }
+
+    // Centre-of-mass frame
+    let masses: Vec<f64> = bodies.iter().map(|b| b.m).collect();
+    let mut y0 = erk::pack_state(bodies);
+    to_centre_of_mass(&mut y0, &masses);
+
+    let dim = 6 * n;
+    let cols = dim + 2 + usize::from(options.rotation);
+    let p0 = match options.parameter {
+        Parameter::Mass(k) => masses[k],
+        Parameter::Energy => energy(&y0, &masses),
+        Parameter::AngularMomentum => angular_momentum(&y0, &masses),
+    };
+    let cont = Continuer {
+        energy: (options.parameter != Parameter::Energy).then(|| energy(&y0, &masses)),
+        masses,
+        options,
+        dim,
+        cols,
+    };
+
+    let mut x = y0;
+    x.push(period);
+    if options.rotation {
+        x.push(0.0);
+    }
+    x.push(p0);
+    let mut e_p = vec![0.0; cols];
+    e_p[cols - 1] = 1.0;
+    let (mut x, _, residual) = cont
+        .correct(x, &e_p, p0)
+        .map_err(|e| format!("Starting orbit: {e}"))?;
+    let mut points = vec![cont.point(&x, residual)?];
+    let mut tangent = cont.tangent(&x, options.step)?;
+
+    let mut ds = options.step.abs();
+    let stop_reason = loop {
+        if points.len() >= options.max_points {
+            break format!("reached {} points", options.max_points);
+        }
+        let predicted: Vec<f64> = x.iter().zip(&tangent).map(|(a, t)| a + ds * t).collect();
+        let target = dot(&tangent, &predicted);
+        let (next, iterations, residual) = match cont.correct(predicted, &tangent, target) {
+            Ok(r) => r,
+            Err(e) => {
+                ds /= 2.0;
+                if ds < options.min_step {
+                    break format!("step fell below {} ({e})", options.min_step);
+                }
+                continue;
+            }
+        };
+
+        let p = next[cols - 1];
+        if p < options.bounds.0 || p > options.bounds.1 {
+            break format!(
+                "parameter left [{}, {}]",
+                options.bounds.0, options.bounds.1
+            );
+        }
+        if matches!(options.parameter, Parameter::Mass(_)) && p <= 0.0 {
+            break "mass reached zero".to_string();
+        }
+
+        let mut point = match cont.point(&next, residual) {
+            Ok(point) => point,
+            Err(e) => break format!("no multipliers at parameter {p} ({e})"),
+        };
+        let previous = points.last().unwrap();
+        let secant: Vec<f64> = next.iter().zip(&x).map(|(a, b)| a - b).collect();
+        let length = norm(&secant);
+        let secant: Vec<f64> = secant.iter().map(|v| v / length).collect();
+        if secant[cols - 1] * tangent[cols - 1] < 0.0 {
+            point.event = Some(Event::Fold);
+        } else if point.unstable != previous.unstable {
+            point.event = Some(Event::Bifurcation);
+        }
+        points.push(point);
+        x = next;
+        tangent = secant;
+        if iterations <= 3 {
+            ds = (1.5 * ds).min(options.max_step);
+        }
+    };
+    Ok(Family {
+        points,
+        stop_reason,
+    })
+}
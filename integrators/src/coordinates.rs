@@ -0,0 +1,213 @@
+// Jacobi and hyperspherical coordinates of three bodies, with their conjugate momenta.
+//
+// Jacobi vectors and reduced masses:
+//
+//     ρ = r₂ - r₁,     μ₁ = m₁m₂ / (m₁ + m₂),     p_ρ = μ₁ ρ̇
+//     λ = r₃ - c₁₂,    μ₂ = m₃(m₁ + m₂) / M,      p_λ = μ₂ λ̇
+//
+// with c₁₂ the centre of mass of bodies 1 and 2. Together with the centre of mass R and
+// the total momentum P the kinetic energy separates, K = P²/2M + p_ρ²/2μ₁ + p_λ²/2μ₂.
+//
+// Hyperspherical (Delves) coordinates use the mass-weighted vectors x = √μ₁ ρ, y = √μ₂ λ
+// (those of `shape_sphere`):
+//
+//     x = R cos α n(θ₁, φ₁),    y = R sin α n(θ₂, φ₂),    n(θ, φ) = (sin θ cos φ, sin θ sin φ, cos θ)
+//
+// R = √I is the hyperradius (I the moment of inertia about the centre of mass) and
+// α ∈ [0, π/2] the hyperangle. The momenta are the conjugates p_q = ∂K/∂q̇, e.g. p_R = Ṙ,
+// p_α = R² α̇. The angles are singular at α = 0 or π/2 (a collision of 1 and 2, or body 3
+// at their centre of mass) and at θ = 0 or π; planar orbits have θ = π/2.
+
+use crate::{
+    types::Body,
+    utils::{add, centre_of_mass, dot, smul, sub, total_linear_momentum},
+};
+
+/// Jacobi coordinates of three bodies, with the centre of mass and total momentum.
+#[derive(Clone, Copy, Debug)]
+pub struct Jacobi {
+    pub masses: [f64; 3],
+    pub centre: [f64; 3],
+    pub momentum: [f64; 3],
+    pub rho: [f64; 3],
+    pub lambda: [f64; 3],
+    pub p_rho: [f64; 3],
+    pub p_lambda: [f64; 3],
+}
+
+impl Jacobi {
+    pub fn from_bodies(bodies: &[Body]) -> Result<Self, String> {
+        let [b1, b2, b3] = bodies else {
+            return Err(format!(
+                "Jacobi coordinates need exactly 3 bodies, got {}",
+                bodies.len()
+            ));
+        };
+        let masses = [b1.m, b2.m, b3.m];
+        let m12 = b1.m + b2.m;
+        let [mu1, mu2] = reduced_masses(&masses);
+        let pair = |a: [f64; 3], b: [f64; 3]| smul(1.0 / m12, add(smul(b1.m, a), smul(b2.m, b)));
+        Ok(Jacobi {
+            masses,
+            centre: centre_of_mass(bodies).0,
+            momentum: total_linear_momentum(bodies),
+            rho: sub(b2.r, b1.r),
+            lambda: sub(b3.r, pair(b1.r, b2.r)),
+            p_rho: smul(mu1, sub(b2.v, b1.v)),
+            p_lambda: smul(mu2, sub(b3.v, pair(b1.v, b2.v))),
+        })
+    }
+
+    pub fn to_bodies(&self) -> Vec<Body> {
+        let [m1, m2, m3] = self.masses;
+        let (m12, mass) = (m1 + m2, m1 + m2 + m3);
+        let [mu1, mu2] = reduced_masses(&self.masses);
+        let place = |centre: [f64; 3], rho: [f64; 3], lambda: [f64; 3]| {
+            let base = sub(centre, smul(m3 / mass, lambda));
+            [
+                sub(base, smul(m2 / m12, rho)),
+                add(base, smul(m1 / m12, rho)),
+                add(centre, smul(m12 / mass, lambda)),
+            ]
+        };
+        let r = place(self.centre, self.rho, self.lambda);
+        let v = place(
+            smul(1.0 / mass, self.momentum),
+            smul(1.0 / mu1, self.p_rho),
+            smul(1.0 / mu2, self.p_lambda),
+        );
+        (0..3)
+            .map(|i| Body {
+                m: self.masses[i],
+                r: r[i],
+                v: v[i],
+            })
+            .collect()
+    }
+}
+
+/// μ₁ = m₁m₂ / (m₁ + m₂) and μ₂ = m₃(m₁ + m₂) / M.
+pub fn reduced_masses(masses: &[f64; 3]) -> [f64; 2] {
+    let [m1, m2, m3] = *masses;
+    let m12 = m1 + m2;
+    [m1 * m2 / m12, m3 * m12 / (m12 + m3)]
+}
+
+/// Hyperspherical coordinates and conjugate momenta; the centre of mass and total
+/// momentum are carried along so the transform can be inverted.
+#[derive(Clone, Copy, Debug)]
+pub struct Hyperspherical {
+    pub masses: [f64; 3],
+    pub centre: [f64; 3],
+    pub momentum: [f64; 3],
+    /// Hyperradius R.
+    pub radius: f64,
+    /// Hyperangle α.
+    pub alpha: f64,
+    /// Polar angles of x and y.
+    pub theta: [f64; 2],
+    /// Azimuths of x and y.
+    pub phi: [f64; 2],
+    pub p_radius: f64,
+    pub p_alpha: f64,
+    pub p_theta: [f64; 2],
+    pub p_phi: [f64; 2],
+}
+
+/// Unit vectors n, e_θ and e_φ at (θ, φ).
+fn spherical_basis(theta: f64, phi: f64) -> [[f64; 3]; 3] {
+    let (st, ct) = theta.sin_cos();
+    let (sp, cp) = phi.sin_cos();
+    [
+        [st * cp, st * sp, ct],
+        [ct * cp, ct * sp, -st],
+        [-sp, cp, 0.0],
+    ]
+}
+
+/// Polar angle and azimuth of `v` (0 for the zero vector).
+fn angles(v: [f64; 3]) -> (f64, f64) {
+    let r = dot(v, v).sqrt();
+    if r == 0.0 {
+        return (0.0, 0.0);
+    }
+    ((v[2] / r).clamp(-1.0, 1.0).acos(), v[1].atan2(v[0]))
+}
+
+impl Hyperspherical {
+    pub fn from_jacobi(j: &Jacobi) -> Self {
+        let [mu1, mu2] = reduced_masses(&j.masses);
+        let (s1, s2) = (mu1.sqrt(), mu2.sqrt());
+        let (x, y) = (smul(s1, j.rho), smul(s2, j.lambda));
+        let (dx, dy) = (smul(1.0 / s1, j.p_rho), smul(1.0 / s2, j.p_lambda));
+        let (nx, ny) = (dot(x, x).sqrt(), dot(y, y).sqrt());
+        let radius = nx.hypot(ny);
+        let alpha = ny.atan2(nx);
+        let (theta1, phi1) = angles(x);
+        let (theta2, phi2) = angles(y);
+        let [n1, et1, ep1] = spherical_basis(theta1, phi1);
+        let [n2, et2, ep2] = spherical_basis(theta2, phi2);
+        let (sa, ca) = alpha.sin_cos();
+        Hyperspherical {
+            masses: j.masses,
+            centre: j.centre,
+            momentum: j.momentum,
+            radius,
+            alpha,
+            theta: [theta1, theta2],
+            phi: [phi1, phi2],
+            p_radius: ca * dot(dx, n1) + sa * dot(dy, n2),
+            p_alpha: radius * (-sa * dot(dx, n1) + ca * dot(dy, n2)),
+            p_theta: [radius * ca * dot(dx, et1), radius * sa * dot(dy, et2)],
+            p_phi: [
+                radius * ca * theta1.sin() * dot(dx, ep1),
+                radius * sa * theta2.sin() * dot(dy, ep2),
+            ],
+        }
+    }
+
+    pub fn to_jacobi(&self) -> Jacobi {
+        let [mu1, mu2] = reduced_masses(&self.masses);
+        let (s1, s2) = (mu1.sqrt(), mu2.sqrt());
+        let r = self.radius;
+        let (sa, ca) = self.alpha.sin_cos();
+        let [n1, et1, ep1] = spherical_basis(self.theta[0], self.phi[0]);
+        let [n2, et2, ep2] = spherical_basis(self.theta[1], self.phi[1]);
+        // Components of ẋ and ẏ in their spherical bases
+        let radial = [
+            ca * self.p_radius - sa * self.p_alpha / r,
+            sa * self.p_radius + ca * self.p_alpha / r,
+        ];
+        let dx = add(
+            smul(radial[0], n1),
+            add(
+                smul(self.p_theta[0] / (r * ca), et1),
+                smul(self.p_phi[0] / (r * ca * self.theta[0].sin()), ep1),
+            ),
+        );
+        let dy = add(
+            smul(radial[1], n2),
+            add(
+                smul(self.p_theta[1] / (r * sa), et2),
+                smul(self.p_phi[1] / (r * sa * self.theta[1].sin()), ep2),
+            ),
+        );
+        Jacobi {
+            masses: self.masses,
+            centre: self.centre,
+            momentum: self.momentum,
+            rho: smul(r * ca / s1, n1),
+            lambda: smul(r * sa / s2, n2),
+            p_rho: smul(s1, dx),
+            p_lambda: smul(s2, dy),
+        }
+    }
+
+    pub fn from_bodies(bodies: &[Body]) -> Result<Self, String> {
+        Jacobi::from_bodies(bodies).map(|j| Self::from_jacobi(&j))
+    }
+
+    pub fn to_bodies(&self) -> Vec<Body> {
+        self.to_jacobi().to_bodies()
+    }
+}
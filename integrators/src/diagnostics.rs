@@ -0,0 +1,149 @@
+// Conserved-quantity diagnostics along a trajectory.
+//
+// The run is split into `samples` equal intervals (`Method::sample`), each integrated
+// with `Method::evolve` from the state the previous one ended in, so every method
+// (fixed-step ones included) can be checked the same way. Adaptive methods restart their
+// step-size control at every sample, which costs a few steps but does not change the
+// accuracy asked for.
+//
+// Drifts are max_t |q(t) - q(0)| over a scale of q at t = 0: |E| for the energy, Σ m|v|
+// for the momentum, the RMS distance to the centre of mass for R - V t, Σ m|r × v| (about
+// the centre of mass) for the angular momentum, and that squared times K + |U| for c²E.
+// The scales do not vanish when the conserved value does (the figure-eight has L = 0).
+
+use crate::{
+    adaptive::AdaptiveSettings,
+    method::Method,
+    types::Body,
+    utils::{
+        centre_of_mass, cross, kinetic_energy, moment_of_inertia, potential_energy, smul, sub,
+        sundman_invariant, total_angular_momentum, total_linear_momentum, virial_ratio,
+    },
+};
+
+/// Invariants (and two non-conserved companions) at one time.
+#[derive(Clone, Copy, Debug)]
+pub struct Invariants {
+    pub t: f64,
+    pub energy: f64,
+    pub momentum: [f64; 3],
+    /// R - V t: where the centre of mass was at t = 0 if it moved uniformly.
+    pub centre: [f64; 3],
+    pub angular_momentum: [f64; 3],
+    /// Moment of inertia about the centre of mass (not conserved).
+    pub inertia: f64,
+    /// 2K / |U| (not conserved; averages to 1 over a period).
+    pub virial: f64,
+    /// Sundman's c² E.
+    pub sundman: f64,
+}
+
+impl Invariants {
+    pub fn of(bodies: &[Body], t: f64) -> Self {
+        let (r, v) = centre_of_mass(bodies);
+        Invariants {
+            t,
+            energy: kinetic_energy(bodies) + potential_energy(bodies),
+            momentum: total_linear_momentum(bodies),
+            centre: sub(r, smul(t, v)),
+            angular_momentum: total_angular_momentum(bodies),
+            inertia: moment_of_inertia(bodies),
+            virial: virial_ratio(bodies),
+            sundman: sundman_invariant(bodies),
+        }
+    }
+}
+
+/// Largest relative drift of every conserved quantity (see the module comment).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Drift {
+    pub energy: f64,
+    pub momentum: f64,
+    pub centre: f64,
+    pub angular_momentum: f64,
+    pub sundman: f64,
+}
+
+/// Result of `diagnose`.
+#[derive(Clone, Debug)]
+pub struct Diagnostics {
+    /// At t = 0 and after every interval.
+    pub series: Vec<Invariants>,
+    pub drift: Drift,
+    /// Time reached (before `t_end` if the integrator gave up).
+    pub t_end: f64,
+}
+
+fn norm(a: [f64; 3]) -> f64 {
+    (a[0] * a[0] + a[1] * a[1] + a[2] * a[2]).sqrt()
+}
+
+/// Integrate `bodies` to `t_end` in `samples` intervals with `method` and record the
+/// invariants after each. `settings` replaces the defaults of adaptive methods.
+pub fn diagnose(
+    bodies: &[Body],
+    t_end: f64,
+    method: Method,
+    settings: Option<&AdaptiveSettings>,
+    samples: usize,
+) -> Result<Diagnostics, String> {
+    if samples == 0 {
+        return Err("samples must be at least 1".to_string());
+    }
+    if bodies.is_empty() {
+        return Err("no bodies".to_string());
+    }
+
+    // Scales at t = 0
+    let (r0, v0) = centre_of_mass(bodies);
+    let mass: f64 = bodies.iter().map(|b| b.m).sum();
+    let momentum_scale: f64 = bodies.iter().map(|b| b.m * norm(b.v)).sum();
+    let centre_scale = (moment_of_inertia(bodies) / mass).sqrt();
+    let angular_scale: f64 = bodies
+        .iter()
+        .map(|b| b.m * norm(cross(sub(b.r, r0), sub(b.v, v0))))
+        .sum();
+    let energy_scale = kinetic_energy(bodies) + potential_energy(bodies).abs();
+    let first = Invariants::of(bodies, 0.0);
+    let scale = |s: f64| if s > 0.0 { s } else { 1.0 };
+    let scales = Drift {
+        energy: scale(if first.energy != 0.0 {
+            first.energy.abs()
+        } else {
+            energy_scale
+        }),
+        momentum: scale(momentum_scale),
+        centre: scale(centre_scale),
+        angular_momentum: scale(angular_scale),
+        sundman: scale(angular_scale * angular_scale * energy_scale),
+    };
+
+    let mut series = Vec::new();
+    let mut drift = Drift::default();
+    for (t, state) in method.sample(bodies, t_end, settings, samples) {
+        let now = Invariants::of(&state, t);
+        let d = |a: [f64; 3], b: [f64; 3]| norm(sub(a, b));
+        drift.energy = drift
+            .energy
+            .max((now.energy - first.energy).abs() / scales.energy);
+        drift.momentum = drift
+            .momentum
+            .max(d(now.momentum, first.momentum) / scales.momentum);
+        drift.centre = drift
+            .centre
+            .max(d(now.centre, first.centre) / scales.centre);
+        drift.angular_momentum = drift
+            .angular_momentum
+            .max(d(now.angular_momentum, first.angular_momentum) / scales.angular_momentum);
+        drift.sundman = drift
+            .sundman
+            .max((now.sundman - first.sundman).abs() / scales.sundman);
+        series.push(now);
+    }
+    let t_end = series.last().map_or(0.0, |s| s.t);
+    Ok(Diagnostics {
+        series,
+        drift,
+        t_end,
+    })
+}
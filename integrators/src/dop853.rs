@@ -4,7 +4,15 @@
 // Coefficients are the classic DOP853 ones (Hairer–Nørsett–Wanner).
 // Numbers below match SciPy/ode_solvers tables (citations in comments).
 
-use crate::{types::Body, utils::accelerations};
+// The tables keep the published digits even where they exceed f64 precision.
+#![allow(clippy::excessive_precision)]
+
+use crate::{
+    adaptive::{AdaptiveSettings, Controller, EmbeddedPair, drive, evolve_positions},
+    batch,
+    erk::{ErkPair, Tableau},
+    types::Body,
+};
 
 // ---------- DOP853 Butcher data ----------
 // Sources for these constants (same values):
@@ -150,199 +158,66 @@ const E5: [f64; 12] = [
     -0.2235530786388629525884427845E-01,
 ];
 
-// ---------- helpers on the 6N state vector ----------
-fn pack_state(bodies: &[Body]) -> Vec<f64> {
-    // y = [r0x, r0y, r0z, v0x, v0y, v0z, r1x, ...]
-    let n = bodies.len();
-    let mut y = vec![0.0; 6 * n];
-    for (i, b) in bodies.iter().enumerate() {
-        let o = 6 * i;
-        y[o] = b.r[0];
-        y[o + 1] = b.r[1];
-        y[o + 2] = b.r[2];
-        y[o + 3] = b.v[0];
-        y[o + 4] = b.v[1];
-        y[o + 5] = b.v[2];
-    }
-    y
-}
-
-fn unpack_state(y: &[f64], bodies: &mut [Body]) {
-    for (i, b) in bodies.iter_mut().enumerate() {
-        let o = 6 * i;
-        b.r = [y[o], y[o + 1], y[o + 2]];
-        b.v = [y[o + 3], y[o + 4], y[o + 5]];
-    }
-}
-
-fn deriv(y: &[f64], masses: &[f64], eps2: f64) -> Vec<f64> {
-    let n = masses.len();
-    // extract positions and velocities
-    let mut r = vec![[0.0f64; 3]; n];
-    let mut v = vec![[0.0f64; 3]; n];
-    for i in 0..n {
-        let o = 6 * i;
-        r[i] = [y[o], y[o + 1], y[o + 2]];
-        v[i] = [y[o + 3], y[o + 4], y[o + 5]];
-    }
-    let a = accelerations(&r, masses, eps2);
-    // dy/dt = [v, a]
-    let mut dy = vec![0.0; 6 * n];
-    for i in 0..n {
-        let o = 6 * i;
-        dy[o] = v[i][0];
-        dy[o + 1] = v[i][1];
-        dy[o + 2] = v[i][2];
-        dy[o + 3] = a[i][0];
-        dy[o + 4] = a[i][1];
-        dy[o + 5] = a[i][2];
-    }
-    dy
-}
-
-fn saxpy_into(out: &mut [f64], a: f64, x: &[f64]) {
-    for (o, xi) in out.iter_mut().zip(x.iter()) {
-        *o += a * xi;
-    }
-}
-
-// Weighted RMS error norm (Hairer style)
-fn error_norm(err: &[f64], y: &[f64], y_new: &[f64], rtol: f64, atol: f64) -> f64 {
-    let mut accum = 0.0;
-    let n = err.len();
-    for i in 0..n {
-        let sc = atol + rtol * y[i].abs().max(y_new[i].abs());
-        let e = err[i] / sc;
-        accum += e * e;
-    }
-    (accum / (n as f64)).sqrt()
-}
-
-// One DOP853 trial step: returns (y8, err_norm, k1) ; k1 is returned for optional FSAL (not used here).
-fn dop853_trial(
-    y: &[f64],
-    h: f64,
-    masses: &[f64],
-    eps2: f64,
-    rtol: f64,
-    atol: f64,
-) -> (Vec<f64>, f64, Vec<f64>) {
-    let n = y.len();
-    let mut k: [Vec<f64>; 12] = std::array::from_fn(|_| vec![0.0; n]);
-
-    // k1
-    k[0] = deriv(y, masses, eps2);
-
-    // stages 2..12
-    for s in 2..=12 {
-        let ai = A[s - 2];
-        let mut ytmp = y.to_vec();
-        for (j, &aij) in ai.iter().enumerate() {
-            if aij != 0.0 {
-                saxpy_into(&mut ytmp, h * aij, &k[j]);
-            }
-        }
-        k[s - 1] = deriv(&ytmp, masses, eps2);
-    }
-
-    // 8th-order solution
-    let mut y8 = y.to_vec();
-    for i in 0..12 {
-        if B8[i] != 0.0 {
-            saxpy_into(&mut y8, h * B8[i], &k[i]);
-        }
-    }
-
-    // embedded 5th-order error estimate (vector), then norm
-    let mut errv = vec![0.0; n];
-    for i in 0..12 {
-        if E5[i] != 0.0 {
-            saxpy_into(&mut errv, h * E5[i], &k[i]);
-        }
-    }
-    let errn = error_norm(&errv, y, &y8, rtol, atol);
-
-    (y8, errn, k[0].clone())
-}
-
-fn push_sample(result: &mut Vec<f64>, y: &[f64], t: f64, n: usize) {
-    for i in 0..n {
-        let o = 6 * i;
-        result.push(y[o]);
-        result.push(y[o + 1]);
-        result.push(y[o + 2]);
-    }
-    result.push(t);
-}
+pub(crate) static TABLEAU: Tableau = Tableau {
+    a: &A,
+    b: &B8,
+    e: &E5,
+    order: 8.0,
+    fsal: false,
+};
+
+pub const SETTINGS: AdaptiveSettings = AdaptiveSettings {
+    rtol: 1e-9,
+    atol: 1e-12,
+    max_steps: 5_000_000,
+    h_min: 1e-16,
+    h_max: f64::INFINITY,
+    controller: Controller::PI,
+};
 
 /// Evolve from t=0 to t=t_end with adaptive DOP853.
 /// Returns (flat positions history, final time reached).
 ///
 /// Notes:
-/// - Appends the 3D positions of each body *before* every accepted step (t=0 included via the first loop push).
-/// - Uses a simple step controller; tweak `RTOL/ATOL/SAFETY/MIN/MAX` as you like.
+/// - Appends the 3D positions of each body after every accepted step (t=0 included).
+/// - Step control is shared with the other embedded pairs (see `adaptive`).
 /// - For close encounters you may want softening (eps2 > 0) to tame singularities.
 pub fn evolve(bodies: &mut [Body], t_end: f64) -> (Vec<f64>, f64) {
-    let n = bodies.len();
-    let masses: Vec<f64> = bodies.iter().map(|b| b.m).collect();
-
-    const RTOL: f64 = 1e-9;
-    const ATOL: f64 = 1e-12;
-    const SAFETY: f64 = 0.9;
-    const FAC_MIN: f64 = 0.2;
-    const FAC_MAX: f64 = 5.0;
-
-    const P: f64 = 8.0; // order
-    const INV_EXP: f64 = 1.0 / (P + 1.0);
+    evolve_with(bodies, t_end, &SETTINGS)
+}
 
+/// `evolve` with explicit tolerances and controller.
+pub fn evolve_with(
+    bodies: &mut [Body],
+    t_end: f64,
+    settings: &AdaptiveSettings,
+) -> (Vec<f64>, f64) {
     let eps2 = 0.0;
+    let mut pair = ErkPair::new(&TABLEAU, bodies, eps2);
+    evolve_positions(&mut pair, bodies, t_end, settings)
+}
 
-    let mut y = pack_state(bodies);
-    let mut t = 0.0;
-    let dir = (t_end - t).signum();
-
-    let mut result = Vec::<f64>::new();
-    push_sample(&mut result, &y, t, n);
-
-    // Initial step (adaptive)
-    let mut h = (t_end - t).abs().max(1e-12) * 1e-3 * dir;
-
-    let max_steps = 5_000_000usize;
-    let mut steps = 0usize;
-
-    while (t_end - t) * dir > 0.0 && steps < max_steps {
-        // Don’t overshoot t_end
-        if (t + h - t_end) * dir > 0.0 {
-            h = t_end - t;
-        }
-
-        let (y_trial, errn, _k1) = dop853_trial(&y, h, &masses, eps2, RTOL, ATOL);
-
-        if errn <= 1.0 {
-            // accept
-            y = y_trial;
-            t += h;
-            push_sample(&mut result, &y, t, n);
-
-            // next h
-            let fac = if errn == 0.0 {
-                FAC_MAX
-            } else {
-                (SAFETY * errn.powf(-INV_EXP)).clamp(FAC_MIN, FAC_MAX)
-            };
-            h *= fac;
-        } else {
-            // reject -> shrink
-            let fac = (SAFETY * errn.powf(-INV_EXP)).clamp(0.1, 0.5);
-            h *= fac;
-        }
-
-        steps += 1;
-        if h.abs() < 1e-16 {
-            break;
-        }
-    }
+/// Integrate the trajectory together with its variational equations (see `variational`).
+/// Returns (Φ(t) = ∂y(t)/∂y(0) as a 6N×6N row-major matrix, final time reached) and
+/// leaves the final state in `bodies`.
+pub fn evolve_variational(
+    bodies: &mut [Body],
+    t_end: f64,
+    settings: &AdaptiveSettings,
+) -> (Vec<f64>, f64) {
+    let eps2 = 0.0;
+    let mut pair = ErkPair::new_variational(&TABLEAU, bodies, eps2);
+    let t = drive(&mut pair, t_end, settings, |_, _, _| {});
+    pair.unpack(bodies);
+    (pair.state()[6 * bodies.len()..].to_vec(), t)
+}
 
-    unpack_state(&y, bodies);
-    (result, t)
+/// Integrate many systems (same number of bodies) in lockstep with DOP853, system s
+/// up to `t_end[s]`. Returns one `evolve`-style result per system, in input order.
+pub fn evolve_batch(
+    systems: &mut [Vec<Body>],
+    t_end: &[f64],
+    settings: &AdaptiveSettings,
+) -> Vec<(Vec<f64>, f64)> {
+    batch::evolve_batch(&TABLEAU, systems, t_end, settings)
 }
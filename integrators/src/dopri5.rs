@@ -0,0 +1,172 @@
+// DOPRI5 (Dormand–Prince 5(4)) for N-body gravity (G=1).
+//
+// Seven stages with FSAL, so six force evaluations per accepted step.
+// The continuous extension is Hairer's `contd5` (Solving ODE I, II.6): a 4th-order
+// interpolant that costs no extra force evaluations.
+
+use crate::{
+    adaptive::{AdaptiveSettings, Controller, EmbeddedPair, drive, evolve_positions},
+    batch,
+    erk::{ErkPair, Tableau, push_positions},
+    types::Body,
+};
+
+const A: [&[f64]; 6] = [
+    &[1.0 / 5.0],
+    &[3.0 / 40.0, 9.0 / 40.0],
+    &[44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0],
+    &[
+        19372.0 / 6561.0,
+        -25360.0 / 2187.0,
+        64448.0 / 6561.0,
+        -212.0 / 729.0,
+    ],
+    &[
+        9017.0 / 3168.0,
+        -355.0 / 33.0,
+        46732.0 / 5247.0,
+        49.0 / 176.0,
+        -5103.0 / 18656.0,
+    ],
+    &[
+        35.0 / 384.0,
+        0.0,
+        500.0 / 1113.0,
+        125.0 / 192.0,
+        -2187.0 / 6784.0,
+        11.0 / 84.0,
+    ],
+];
+
+// 5th-order weights (the last stage is evaluated at y_new: FSAL)
+const B: [f64; 7] = [
+    35.0 / 384.0,
+    0.0,
+    500.0 / 1113.0,
+    125.0 / 192.0,
+    -2187.0 / 6784.0,
+    11.0 / 84.0,
+    0.0,
+];
+
+// E = b(5) - b_hat(4)
+const E: [f64; 7] = [
+    71.0 / 57600.0,
+    0.0,
+    -71.0 / 16695.0,
+    71.0 / 1920.0,
+    -17253.0 / 339200.0,
+    22.0 / 525.0,
+    -1.0 / 40.0,
+];
+
+// Dense output coefficients
+const D: [f64; 7] = [
+    -12715105075.0 / 11282082432.0,
+    0.0,
+    87487479700.0 / 32700410799.0,
+    -10690763975.0 / 1880347072.0,
+    701980252875.0 / 199316789632.0,
+    -1453857185.0 / 822651844.0,
+    69997945.0 / 29380423.0,
+];
+
+pub(crate) static TABLEAU: Tableau = Tableau {
+    a: &A,
+    b: &B,
+    e: &E,
+    order: 4.0,
+    fsal: true,
+};
+
+pub const SETTINGS: AdaptiveSettings = AdaptiveSettings {
+    rtol: 1e-9,
+    atol: 1e-12,
+    max_steps: 5_000_000,
+    h_min: 1e-16,
+    h_max: f64::INFINITY,
+    controller: Controller::PI,
+};
+
+/// Evaluate the continuous extension of the last accepted step at
+/// t_prev + theta * h (0 <= theta <= 1) into `y` and append the positions to `out`.
+fn push_dense(pair: &ErkPair, theta: f64, y: &mut [f64], out: &mut Vec<f64>) {
+    let y0 = pair.previous_state();
+    let y1 = pair.state();
+    let k = pair.stages();
+    let h = pair.last_step();
+    let theta1 = 1.0 - theta;
+
+    for (m, ym) in y.iter_mut().enumerate() {
+        let ydiff = y1[m] - y0[m];
+        let bspl = h * k[0][m] - ydiff;
+        let r4 = ydiff - h * k[6][m] - bspl;
+        let r5 = h * D.iter().zip(k.iter()).map(|(d, km)| d * km[m]).sum::<f64>();
+        *ym = y0[m] + theta * (ydiff + theta1 * (bspl + theta * (r4 + theta1 * r5)));
+    }
+    push_positions(out, y);
+}
+
+/// Evolve from t=0 to t=t_end with adaptive DOPRI5.
+/// Returns (flat positions history, final time reached), one sample per accepted step.
+pub fn evolve(bodies: &mut [Body], t_end: f64) -> (Vec<f64>, f64) {
+    evolve_with(bodies, t_end, &SETTINGS)
+}
+
+/// `evolve` with explicit tolerances and controller.
+pub fn evolve_with(
+    bodies: &mut [Body],
+    t_end: f64,
+    settings: &AdaptiveSettings,
+) -> (Vec<f64>, f64) {
+    let eps2 = 0.0;
+    let mut pair = ErkPair::new(&TABLEAU, bodies, eps2);
+    evolve_positions(&mut pair, bodies, t_end, settings)
+}
+
+/// Integrate many systems (same number of bodies) in lockstep with DOPRI5, system s
+/// up to `t_end[s]`. Returns one `evolve`-style result per system, in input order.
+pub fn evolve_batch(
+    systems: &mut [Vec<Body>],
+    t_end: &[f64],
+    settings: &AdaptiveSettings,
+) -> Vec<(Vec<f64>, f64)> {
+    batch::evolve_batch(&TABLEAU, systems, t_end, settings)
+}
+
+/// Like `evolve`, but samples the dense output at `samples + 1` equally spaced times
+/// 0, t_end/samples, ..., t_end instead of at the accepted steps.
+pub fn evolve_dense(bodies: &mut [Body], t_end: f64, samples: usize) -> (Vec<f64>, f64) {
+    let eps2 = 0.0;
+    let samples = samples.max(1);
+    let dt = t_end / samples as f64;
+    let mut pair = ErkPair::new(&TABLEAU, bodies, eps2);
+
+    let mut y = vec![0.0; pair.state().len()];
+    let mut result = Vec::<f64>::new();
+    push_positions(&mut result, pair.state());
+    result.push(0.0);
+    let mut next = 1usize;
+
+    let t = drive(&mut pair, t_end, &SETTINGS, |p, t, h| {
+        let t_prev = t - h;
+        while next <= samples {
+            let ts = if next == samples {
+                t_end
+            } else {
+                next as f64 * dt
+            };
+            let theta = (ts - t_prev) / h;
+            // allow for round-off in the final t += h
+            if theta > 1.0 + 1e-12 {
+                break;
+            }
+            push_dense(p, theta.min(1.0), &mut y, &mut result);
+            result.push(ts);
+            next += 1;
+        }
+    });
+
+    pair.unpack(bodies);
+    (result, t)
+}
@@ -0,0 +1,386 @@
+// Generic explicit Runge–Kutta embedded pair on the 6N state vector (f64).
+//
+// A method is just a `Tableau`; DOP853, DOPRI5, Bogacki–Shampine and Cash–Karp
+// all step through `ErkPair`.
+
+use crate::{adaptive::EmbeddedPair, types::Body, utils::pair_accelerations, variational};
+
+/// Butcher data of an embedded pair for an autonomous system.
+pub(crate) struct Tableau {
+    /// Rows i = 2..s; each row has a_ij, j=1..i-1. Extra rows are ignored.
+    pub a: &'static [&'static [f64]],
+    /// Weights of the propagated solution.
+    pub b: &'static [f64],
+    /// b - b_hat; h * sum(e_i * k_i) is the local error estimate.
+    pub e: &'static [f64],
+    /// Controller order (see `EmbeddedPair::order`).
+    pub order: f64,
+    /// First same as last: the last stage is f(y_new) and is reused as k1.
+    pub fsal: bool,
+}
+
+impl Tableau {
+    pub fn stages(&self) -> usize {
+        self.b.len()
+    }
+}
+
+// ---------- helpers on the 6N state vector ----------
+pub(crate) fn pack_state(bodies: &[Body]) -> Vec<f64> {
+    // y = [r0x, r0y, r0z, v0x, v0y, v0z, r1x, ...]
+    let n = bodies.len();
+    let mut y = vec![0.0; 6 * n];
+    for (i, b) in bodies.iter().enumerate() {
+        let o = 6 * i;
+        y[o] = b.r[0];
+        y[o + 1] = b.r[1];
+        y[o + 2] = b.r[2];
+        y[o + 3] = b.v[0];
+        y[o + 4] = b.v[1];
+        y[o + 5] = b.v[2];
+    }
+    y
+}
+
+pub(crate) fn unpack_state(y: &[f64], bodies: &mut [Body]) {
+    for (i, b) in bodies.iter_mut().enumerate() {
+        let o = 6 * i;
+        b.r = [y[o], y[o + 1], y[o + 2]];
+        b.v = [y[o + 3], y[o + 4], y[o + 5]];
+    }
+}
+
+/// dy/dt = [v, a] for the packed state, written into `dy` without allocating.
+pub(crate) fn deriv_into(y: &[f64], masses: &[f64], eps2: f64, dy: &mut [f64]) {
+    let n = masses.len();
+    for i in 0..n {
+        let o = 6 * i;
+        dy[o..o + 3].copy_from_slice(&y[o + 3..o + 6]);
+        dy[o + 3..o + 6].fill(0.0);
+    }
+    for i in 0..n {
+        let oi = 6 * i;
+        let ri = [y[oi], y[oi + 1], y[oi + 2]];
+        for j in (i + 1)..n {
+            let oj = 6 * j;
+            let rj = [y[oj], y[oj + 1], y[oj + 2]];
+            let (ai, aj) = pair_accelerations(ri, rj, masses[i], masses[j], eps2);
+            for c in 0..3 {
+                dy[oi + 3 + c] += ai[c];
+                dy[oj + 3 + c] += aj[c];
+            }
+        }
+    }
+}
+
+/// Right-hand side of the plain (`jac` empty) or variational (`jac` is 3N×3N scratch) system.
+fn rhs_into(y: &[f64], masses: &[f64], eps2: f64, jac: &mut [f64], dy: &mut [f64]) {
+    if jac.is_empty() {
+        deriv_into(y, masses, eps2, dy);
+    } else {
+        variational::deriv_into(y, masses, eps2, jac, dy);
+    }
+}
+
+pub(crate) fn deriv(y: &[f64], masses: &[f64], eps2: f64) -> Vec<f64> {
+    let mut dy = vec![0.0; y.len()];
+    deriv_into(y, masses, eps2, &mut dy);
+    dy
+}
+
+pub(crate) fn saxpy_into(out: &mut [f64], a: f64, x: &[f64]) {
+    for (o, xi) in out.iter_mut().zip(x.iter()) {
+        *o += a * xi;
+    }
+}
+
+// Weighted RMS error norm (Hairer style)
+pub(crate) fn error_norm(err: &[f64], y: &[f64], y_new: &[f64], rtol: f64, atol: f64) -> f64 {
+    let mut accum = 0.0;
+    let n = err.len();
+    for i in 0..n {
+        let sc = atol + rtol * y[i].abs().max(y_new[i].abs());
+        let e = err[i] / sc;
+        accum += e * e;
+    }
+    (accum / (n as f64)).sqrt()
+}
+
+pub(crate) fn push_positions(result: &mut Vec<f64>, y: &[f64]) {
+    for o in (0..y.len()).step_by(6) {
+        result.push(y[o]);
+        result.push(y[o + 1]);
+        result.push(y[o + 2]);
+    }
+}
+
+/// State of an explicit embedded pair between steps.
+/// All buffers are allocated once, so stepping does not allocate.
+pub(crate) struct ErkPair {
+    tab: &'static Tableau,
+    masses: Vec<f64>,
+    eps2: f64,
+    // ∂a/∂r scratch when the state carries the variational equations, empty otherwise
+    jac: Vec<f64>,
+    y: Vec<f64>,
+    y_prev: Vec<f64>,
+    y_new: Vec<f64>,
+    ytmp: Vec<f64>,
+    err: Vec<f64>,
+    k: Vec<Vec<f64>>,
+    h_last: f64,
+    // k[0] holds f(y) for the current state
+    k0_valid: bool,
+    // the last stage of the accepted step is f(y) and must be moved into k[0]
+    fsal_pending: bool,
+}
+
+impl ErkPair {
+    pub fn new(tab: &'static Tableau, bodies: &[Body], eps2: f64) -> Self {
+        Self::with_state(tab, bodies, eps2, pack_state(bodies), Vec::new())
+    }
+
+    /// Pair integrating [y, Φ]: the trajectory together with its state-transition matrix.
+    pub fn new_variational(tab: &'static Tableau, bodies: &[Body], eps2: f64) -> Self {
+        let n = bodies.len();
+        let y = variational::pack_augmented(bodies);
+        Self::with_state(tab, bodies, eps2, y, vec![0.0; 9 * n * n])
+    }
+
+    fn with_state(
+        tab: &'static Tableau,
+        bodies: &[Body],
+        eps2: f64,
+        y: Vec<f64>,
+        jac: Vec<f64>,
+    ) -> Self {
+        let n = y.len();
+        ErkPair {
+            tab,
+            masses: bodies.iter().map(|b| b.m).collect(),
+            eps2,
+            jac,
+            y_prev: y.clone(),
+            y_new: y.clone(),
+            ytmp: vec![0.0; n],
+            err: vec![0.0; n],
+            y,
+            k: vec![vec![0.0; n]; tab.stages()],
+            h_last: 0.0,
+            k0_valid: false,
+            fsal_pending: false,
+        }
+    }
+
+    /// Current state.
+    pub fn state(&self) -> &[f64] {
+        &self.y
+    }
+
+    /// State before the last accepted step.
+    pub fn previous_state(&self) -> &[f64] {
+        &self.y_prev
+    }
+
+    /// Size of the last accepted step.
+    pub fn last_step(&self) -> f64 {
+        self.h_last
+    }
+
+    /// Stages of the last accepted step.
+    pub fn stages(&self) -> &[Vec<f64>] {
+        &self.k
+    }
+}
+
+impl EmbeddedPair for ErkPair {
+    fn order(&self) -> f64 {
+        self.tab.order
+    }
+
+    fn try_step(&mut self, h: f64, rtol: f64, atol: f64) -> f64 {
+        let s = self.tab.stages();
+        if self.fsal_pending {
+            self.k.swap(0, s - 1);
+            self.fsal_pending = false;
+            self.k0_valid = true;
+        }
+        if !self.k0_valid {
+            rhs_into(
+                &self.y,
+                &self.masses,
+                self.eps2,
+                &mut self.jac,
+                &mut self.k[0],
+            );
+            self.k0_valid = true;
+        }
+
+        // stages 2..s
+        for i in 1..s {
+            self.ytmp.copy_from_slice(&self.y);
+            for (j, &aij) in self.tab.a[i - 1].iter().enumerate() {
+                if aij != 0.0 {
+                    saxpy_into(&mut self.ytmp, h * aij, &self.k[j]);
+                }
+            }
+            rhs_into(
+                &self.ytmp,
+                &self.masses,
+                self.eps2,
+                &mut self.jac,
+                &mut self.k[i],
+            );
+        }
+
+        // propagated solution
+        self.y_new.copy_from_slice(&self.y);
+        for (bi, ki) in self.tab.b.iter().zip(self.k.iter()) {
+            if *bi != 0.0 {
+                saxpy_into(&mut self.y_new, h * bi, ki);
+            }
+        }
+
+        // embedded error estimate (vector), then norm
+        self.err.fill(0.0);
+        for (ei, ki) in self.tab.e.iter().zip(self.k.iter()) {
+            if *ei != 0.0 {
+                saxpy_into(&mut self.err, h * ei, ki);
+            }
+        }
+        self.h_last = h;
+        error_norm(&self.err, &self.y, &self.y_new, rtol, atol)
+    }
+
+    fn accept(&mut self) {
+        // y_prev <- y <- y_new
+        std::mem::swap(&mut self.y_prev, &mut self.y);
+        std::mem::swap(&mut self.y, &mut self.y_new);
+        if self.tab.fsal {
+            self.fsal_pending = true;
+        } else {
+            self.k0_valid = false;
+        }
+    }
+
+    fn state_f64(&self) -> Vec<f64> {
+        self.y.clone()
+    }
+
+    fn rhs_f64(&self, y: &[f64]) -> Vec<f64> {
+        if self.jac.is_empty() {
+            deriv(y, &self.masses, self.eps2)
+        } else {
+            variational::deriv(y, &self.masses, self.eps2)
+        }
+    }
+
+    fn push_positions(&self, out: &mut Vec<f64>) {
+        push_positions(out, &self.y[..6 * self.masses.len()]);
+    }
+
+    fn unpack(&self, bodies: &mut [Body]) {
+        unpack_state(&self.y, bodies);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Tableau;
+    use crate::{bogacki_shampine, cash_karp, dop853, dopri5};
+
+    /// Rooted trees up to `order` as (order, children), children indexing earlier trees.
+    fn trees(order: usize) -> Vec<(usize, Vec<usize>)> {
+        let mut trees = vec![(1, vec![])];
+        for n in 2..=order {
+            // Multisets of smaller trees (indices non-increasing) whose orders add up to n - 1
+            let smaller = trees.len();
+            let mut partial = vec![(Vec::<usize>::new(), n - 1)];
+            while let Some((children, left)) = partial.pop() {
+                if left == 0 {
+                    trees.push((n, children));
+                    continue;
+                }
+                let max = children.last().copied().unwrap_or(smaller - 1);
+                for j in (0..=max).filter(|&j| trees[j].0 <= left) {
+                    let mut children = children.clone();
+                    children.push(j);
+                    partial.push((children, left - trees[j].0));
+                }
+            }
+        }
+        trees
+    }
+
+    /// Order of the weights `b` on the stages of `tab`: the largest p <= `max` such that
+    /// b·Φ(t) = 1/γ(t) for every tree of order up to p.
+    fn order(tab: &Tableau, b: &[f64], max: usize) -> usize {
+        let s = tab.stages();
+        let a = |i: usize, j: usize| match i {
+            0 => 0.0,
+            _ => tab.a[i - 1].get(j).copied().unwrap_or(0.0),
+        };
+        let mut phi: Vec<Vec<f64>> = Vec::new();
+        let mut gamma: Vec<f64> = Vec::new();
+        let mut order = max;
+        for (n, children) in trees(max) {
+            let mut p = vec![1.0; s];
+            let mut g = n as f64;
+            for &c in &children {
+                for (i, p) in p.iter_mut().enumerate() {
+                    *p *= (0..s).map(|j| a(i, j) * phi[c][j]).sum::<f64>();
+                }
+                g *= gamma[c];
+            }
+            let weight: f64 = b.iter().zip(&p).map(|(b, p)| b * p).sum();
+            if (weight - 1.0 / g).abs() > 1e-12 {
+                order = order.min(n - 1);
+            }
+            phi.push(p);
+            gamma.push(g);
+        }
+        order
+    }
+
+    #[test]
+    fn tree_counts() {
+        let mut counts = [0; 8];
+        for (n, _) in trees(8) {
+            counts[n - 1] += 1;
+        }
+        assert_eq!(counts, [1, 1, 2, 4, 9, 20, 48, 115]);
+    }
+
+    #[test]
+    fn orders_of_the_pairs() {
+        // (tableau, order of b, order of the embedded b - e)
+        let pairs: [(&str, &Tableau, usize, usize); 4] = [
+            ("dop853", &dop853::TABLEAU, 8, 5),
+            ("dopri5", &dopri5::TABLEAU, 5, 4),
+            ("bs32", &bogacki_shampine::TABLEAU, 3, 2),
+            ("cash_karp", &cash_karp::TABLEAU, 5, 4),
+        ];
+        for (name, tab, p, p_hat) in pairs {
+            assert_eq!(tab.e.len(), tab.stages(), "{name}");
+            assert!(tab.a.len() + 1 >= tab.stages(), "{name}");
+            for (i, row) in tab.a.iter().take(tab.stages() - 1).enumerate() {
+                assert!(row.len() <= i + 1, "{name}: row {} is too long", i + 2);
+            }
+            let b_hat: Vec<f64> = tab.b.iter().zip(tab.e).map(|(b, e)| b - e).collect();
+            assert_eq!(order(tab, tab.b, p + 1), p, "{name}");
+            assert_eq!(order(tab, &b_hat, p_hat + 1), p_hat, "{name} (embedded)");
+        }
+    }
+
+    #[test]
+    fn fsal_pairs_end_with_the_propagated_solution() {
+        for tab in [&dopri5::TABLEAU, &bogacki_shampine::TABLEAU] {
+            assert!(tab.fsal);
+            let s = tab.stages();
+            assert_eq!(tab.b[s - 1], 0.0);
+            for (a, b) in tab.a[s - 2].iter().zip(tab.b) {
+                assert!((a - b).abs() < 1e-15);
+            }
+        }
+    }
+}
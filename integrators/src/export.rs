@@ -0,0 +1,223 @@
+// Trajectories as tables, written as CSV, JSON or NumPy `.npy` / `.npz`.
+//
+// Columns are named per body from 1: `t`, `x1`, `y1`, `z1`, then (optionally) `vx1`,
+// `vy1`, `vz1`, and so on for every body, then the diagnostics of `Invariants` if asked
+// for. The NumPy writers need no Python side: `.npy` holds a structured array with one
+// float64 field per column (`a["x1"]`), and `.npz` an uncompressed zip with one 1-D
+// array per column (`np.load(f)["x1"]`).
+
+use crate::{diagnostics::Invariants, types::Body};
+
+/// Named float64 columns of equal length.
+#[derive(Clone, Debug, Default)]
+pub struct Table {
+    pub columns: Vec<String>,
+    /// One row per time, in `columns` order.
+    pub rows: Vec<Vec<f64>>,
+}
+
+const DIAGNOSTICS: [&str; 13] = [
+    "energy", "px", "py", "pz", "cx", "cy", "cz", "lx", "ly", "lz", "inertia", "virial", "sundman",
+];
+
+impl Table {
+    /// Table of states at given times (e.g. from `Method::sample`).
+    pub fn trajectory(states: &[(f64, Vec<Body>)], velocities: bool, diagnostics: bool) -> Self {
+        let n = states.first().map_or(0, |(_, b)| b.len());
+        let mut columns = vec!["t".to_string()];
+        for i in 1..=n {
+            columns.extend(["x", "y", "z"].map(|c| format!("{c}{i}")));
+            if velocities {
+                columns.extend(["vx", "vy", "vz"].map(|c| format!("{c}{i}")));
+            }
+        }
+        if diagnostics {
+            columns.extend(DIAGNOSTICS.map(String::from));
+        }
+        let rows = states
+            .iter()
+            .map(|(t, bodies)| {
+                let mut row = vec![*t];
+                for b in bodies {
+                    row.extend(b.r);
+                    if velocities {
+                        row.extend(b.v);
+                    }
+                }
+                if diagnostics {
+                    let d = Invariants::of(bodies, *t);
+                    row.push(d.energy);
+                    row.extend(d.momentum);
+                    row.extend(d.centre);
+                    row.extend(d.angular_momentum);
+                    row.extend([d.inertia, d.virial, d.sundman]);
+                }
+                row
+            })
+            .collect();
+        Table { columns, rows }
+    }
+
+    /// Table of an `evolve` history (`[x, y, z] * n, t` per step; positions only).
+    pub fn positions(series: &[f64], bodies: usize) -> Self {
+        let mut columns = vec!["t".to_string()];
+        for i in 1..=bodies {
+            columns.extend(["x", "y", "z"].map(|c| format!("{c}{i}")));
+        }
+        let rows = series
+            .chunks_exact(3 * bodies + 1)
+            .map(|s| {
+                let mut row = vec![s[3 * bodies]];
+                row.extend_from_slice(&s[..3 * bodies]);
+                row
+            })
+            .collect();
+        Table { columns, rows }
+    }
+
+    fn column(&self, j: usize) -> impl Iterator<Item = f64> + '_ {
+        self.rows.iter().map(move |r| r[j])
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = self.columns.join(",") + "\n";
+        for row in &self.rows {
+            let cells: Vec<String> = row.iter().map(|x| format!("{x:?}")).collect();
+            csv.push_str(&cells.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// `{"columns": [...], "data": [[row], ...]}`, non-finite values as null.
+    pub fn to_json(&self) -> String {
+        let number = |x: &f64| {
+            if x.is_finite() {
+                format!("{x:?}")
+            } else {
+                "null".to_string()
+            }
+        };
+        let columns: Vec<String> = self.columns.iter().map(|c| format!("\"{c}\"")).collect();
+        let rows: Vec<String> = self
+            .rows
+            .iter()
+            .map(|r| format!("[{}]", r.iter().map(number).collect::<Vec<_>>().join(", ")))
+            .collect();
+        format!(
+            "{{\n  \"columns\": [{}],\n  \"data\": [\n    {}\n  ]\n}}\n",
+            columns.join(", "),
+            rows.join(",\n    ")
+        )
+    }
+
+    /// `.npy` (format 1.0) of a structured array with one `<f8` field per column.
+    pub fn to_npy(&self) -> Vec<u8> {
+        let fields: Vec<String> = self
+            .columns
+            .iter()
+            .map(|c| format!("('{c}', '<f8')"))
+            .collect();
+        let mut out = npy_header(&format!("[{}]", fields.join(", ")), self.rows.len());
+        for row in &self.rows {
+            for x in row {
+                out.extend_from_slice(&x.to_le_bytes());
+            }
+        }
+        out
+    }
+
+    /// `.npz`: one `<f8` array per column, stored without compression.
+    pub fn to_npz(&self) -> Vec<u8> {
+        let files: Vec<(String, Vec<u8>)> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(j, c)| {
+                let mut npy = npy_header("'<f8'", self.rows.len());
+                for x in self.column(j) {
+                    npy.extend_from_slice(&x.to_le_bytes());
+                }
+                (format!("{c}.npy"), npy)
+            })
+            .collect();
+        zip_stored(&files)
+    }
+}
+
+/// Magic, version 1.0 and the header dict of a 1-D array of `len` items of type
+/// `descr`, padded so the data starts at a multiple of 64 bytes.
+fn npy_header(descr: &str, len: usize) -> Vec<u8> {
+    let mut dict = format!("{{'descr': {descr}, 'fortran_order': False, 'shape': ({len},), }}");
+    let unpadded = 10 + dict.len() + 1;
+    dict.push_str(&" ".repeat(unpadded.next_multiple_of(64) - unpadded));
+    dict.push('\n');
+    let mut out = b"\x93NUMPY\x01\x00".to_vec();
+    out.extend_from_slice(&(dict.len() as u16).to_le_bytes());
+    out.extend_from_slice(dict.as_bytes());
+    out
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+/// Zip archive of `files` (name, contents) with the "stored" method. No zip64, so every
+/// file and the whole archive must stay under 4 GiB.
+fn zip_stored(files: &[(String, Vec<u8>)]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut central = Vec::new();
+    let u16le = |v: usize| (v as u16).to_le_bytes();
+    let u32le = |v: usize| (v as u32).to_le_bytes();
+    for (name, data) in files {
+        let offset = out.len();
+        let crc = crc32(data).to_le_bytes();
+        // Version 2.0, no flags, stored, DOS time and date 0
+        let common = [
+            &u16le(20)[..],
+            &u16le(0),
+            &u16le(0),
+            &u16le(0),
+            &u16le(0),
+            &crc,
+            &u32le(data.len()),
+            &u32le(data.len()),
+            &u16le(name.len()),
+            &u16le(0),
+        ]
+        .concat();
+        out.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        out.extend_from_slice(&common);
+        out.extend_from_slice(name.as_bytes());
+        out.extend_from_slice(data);
+
+        central.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        central.extend_from_slice(&u16le(20));
+        central.extend_from_slice(&common);
+        // Comment length, disk, internal and external attributes, offset
+        central.extend_from_slice(&u16le(0));
+        central.extend_from_slice(&u16le(0));
+        central.extend_from_slice(&u16le(0));
+        central.extend_from_slice(&u32le(0));
+        central.extend_from_slice(&u32le(offset));
+        central.extend_from_slice(name.as_bytes());
+    }
+    let central_offset = out.len();
+    out.extend_from_slice(&central);
+    out.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+    out.extend_from_slice(&u16le(0));
+    out.extend_from_slice(&u16le(0));
+    out.extend_from_slice(&u16le(files.len()));
+    out.extend_from_slice(&u16le(files.len()));
+    out.extend_from_slice(&u32le(central.len()));
+    out.extend_from_slice(&u32le(central_offset));
+    out.extend_from_slice(&u16le(0));
+    out
+}
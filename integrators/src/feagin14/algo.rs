@@ -4,8 +4,11 @@ use once_cell::sync::Lazy;
 use std::str::FromStr;
 
 use crate::{
+    adaptive::{AdaptiveSettings, Controller, EmbeddedPair, drive, evolve_positions},
+    erk::deriv,
     feagin14::coef::{B_STR, C_STR},
     types::Body,
+    variational,
 };
 
 // ---------- helpers to build BD constants ----------
@@ -27,7 +30,7 @@ const ERR_I2: usize = 33;
 static ERR_SCALE: Lazy<BD> = Lazy::new(|| bd("0.001"));
 
 // Working scale for BigDecimal (digits after decimal). Tune as you like.
-const BD_SCALE: i64 = 60;
+pub(crate) const BD_SCALE: i64 = 60;
 
 // ---------- pack/unpack as BigDecimal ----------
 fn pack_state_bd(bodies: &[Body]) -> Vec<BD> {
@@ -44,6 +47,17 @@ fn pack_state_bd(bodies: &[Body]) -> Vec<BD> {
     y
 }
 
+/// [y(0), I] for the variational system (see `variational`).
+fn pack_augmented_bd(bodies: &[Body]) -> Vec<BD> {
+    let dim = 6 * bodies.len();
+    let mut y = pack_state_bd(bodies);
+    y.resize(dim + dim * dim, BD::zero());
+    for p in 0..dim {
+        y[dim + p * dim + p] = BD::one();
+    }
+    y
+}
+
 fn unpack_state_bd(y: &[BD], bodies: &mut [Body]) {
     for (i, b) in bodies.iter_mut().enumerate() {
         let o = 6 * i;
@@ -149,27 +163,91 @@ fn deriv_bd(y: &[BD], masses: &[BD]) -> Vec<BD> {
     dy
 }
 
+// ---------- BigDecimal variational derivative d/dt [y, Φ] = [f(y), A(y) Φ] ----------
+fn deriv_variational_bd(y: &[BD], masses: &[BD]) -> Vec<BD> {
+    let n = masses.len();
+    let dim = 6 * n;
+    let mut dy = deriv_bd(&y[..dim], masses);
+
+    // ∂a/∂r (3N×3N): pair blocks ±m K with K = I/r^3 - 3 d d^T/r^5
+    let w = 3 * n;
+    let mut jac = vec![BD::zero(); w * w];
+    let three = BD::from(3);
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let d: [BD; 3] = std::array::from_fn(|c| with_scale(&y[6 * j + c] - &y[6 * i + c]));
+            let r2 = with_scale(&d[0] * &d[0] + &d[1] * &d[1] + &d[2] * &d[2]);
+            if r2.is_zero() {
+                continue;
+            }
+            let inv_r3 = with_scale(BD::one() / (r2.sqrt().unwrap() * &r2));
+            let inv_r5 = with_scale(&inv_r3 / &r2);
+            for c in 0..3 {
+                for e in 0..3 {
+                    let mut k = with_scale(-(&three * &d[c] * &d[e] * &inv_r5));
+                    if c == e {
+                        k += &inv_r3;
+                    }
+                    let kj = with_scale(&masses[j] * &k);
+                    let ki = with_scale(&masses[i] * &k);
+                    jac[(3 * i + c) * w + 3 * j + e] += &kj;
+                    jac[(3 * i + c) * w + 3 * i + e] -= &kj;
+                    jac[(3 * j + c) * w + 3 * i + e] += &ki;
+                    jac[(3 * j + c) * w + 3 * j + e] -= &ki;
+                }
+            }
+        }
+    }
+
+    let phi = &y[dim..];
+    dy.resize(dim + dim * dim, BD::zero());
+    for i in 0..n {
+        for c in 0..3 {
+            let (pr, pv) = (6 * i + c, 6 * i + 3 + c);
+            for q in 0..dim {
+                dy[dim + pr * dim + q] = phi[pv * dim + q].clone();
+                let mut acc = BD::zero();
+                for j in 0..n {
+                    for e in 0..3 {
+                        let a = &jac[(3 * i + c) * w + 3 * j + e];
+                        if !a.is_zero() {
+                            acc += a * &phi[(6 * j + e) * dim + q];
+                        }
+                    }
+                }
+                dy[dim + pv * dim + q] = with_scale(acc);
+            }
+        }
+    }
+    dy
+}
+
 // ---------- One trial step (BigDecimal everything) ----------
-fn erk_trial_bd(y: &[BD], h: f64, masses: &[BD], rtol: f64, atol: f64) -> (Vec<BD>, f64) {
+fn erk_trial_bd(
+    y: &[BD],
+    h_bd: &BD,
+    masses: &[BD],
+    rhs: fn(&[BD], &[BD]) -> Vec<BD>,
+    rtol: f64,
+    atol: f64,
+) -> (Vec<BD>, f64) {
     let n = y.len();
     let s = B_BD.len(); // 35
     let mut k: Vec<Vec<BD>> = (0..s).map(|_| vec![BD::zero(); n]).collect();
 
     // k0
-    k[0] = deriv_bd(y, masses);
-
-    let h_bd = BD::from_f64(h).unwrap();
+    k[0] = rhs(y, masses);
 
     // stages i = 1..s-1
     for i in 1..s {
         let mut ytmp = y.to_vec();
         for (j, aij) in B_BD[i].iter().enumerate() {
             if !aij.is_zero() {
-                let coeff = &h_bd * aij;
+                let coeff = h_bd * aij;
                 saxpy_into_bd(&mut ytmp, &coeff, &k[j]);
             }
         }
-        k[i] = deriv_bd(&ytmp, masses);
+        k[i] = rhs(&ytmp, masses);
     }
 
     // high-order solution
@@ -177,7 +255,7 @@ fn erk_trial_bd(y: &[BD], h: f64, masses: &[BD], rtol: f64, atol: f64) -> (Vec<B
     for i in 0..s {
         let bi = &C_BD[i];
         if !bi.is_zero() {
-            let coeff = &h_bd * bi;
+            let coeff = h_bd * bi;
             saxpy_into_bd(&mut y_hi, &coeff, &k[i]);
         }
     }
@@ -185,82 +263,151 @@ fn erk_trial_bd(y: &[BD], h: f64, masses: &[BD], rtol: f64, atol: f64) -> (Vec<B
     // error vector via stage-difference
     let mut errv = vec![BD::zero(); n];
     for (m, err) in errv.iter_mut().enumerate() {
-        *err = with_scale(ERR_SCALE.clone() * (&h_bd * (&k[ERR_I1][m] - &k[ERR_I2][m])));
+        *err = with_scale(ERR_SCALE.clone() * (h_bd * (&k[ERR_I1][m] - &k[ERR_I2][m])));
     }
 
     let errn = error_norm_bd(&errv, y, &y_hi, rtol, atol);
     (y_hi, errn)
 }
 
-fn push_sample(result: &mut Vec<f64>, y: &[BD], t: f64, n: usize) {
-    for i in 0..n {
-        let o = 6 * i;
-        result.push(y[o].to_f64().unwrap());
-        result.push(y[o + 1].to_f64().unwrap());
-        result.push(y[o + 2].to_f64().unwrap());
-    }
-    result.push(t);
+struct Feagin14 {
+    masses: Vec<BD>,
+    masses_f64: Vec<f64>,
+    y: Vec<BD>,
+    y_trial: Vec<BD>,
+    // the state carries the variational equations
+    variational: bool,
+    // time of `y` as the exact sum of the accepted steps, and the trial step
+    t: BD,
+    h_trial: BD,
 }
 
-// ---------- Public evolve (same API) ----------
-pub fn evolve(bodies: &mut [Body], t_end: f64) -> (Vec<f64>, f64) {
-    let n_b = bodies.len();
-    let masses_bd: Vec<BD> = bodies.iter().map(|b| BD::from_f64(b.m).unwrap()).collect();
-
-    // tolerances & controller
-    const RTOL: f64 = 1e-18;
-    const ATOL: f64 = 1e-18;
-    const SAFETY: f64 = 0.9;
-    const FAC_MIN: f64 = 0.2;
-    const FAC_MAX: f64 = 5.0;
-    const P: f64 = 14.0;
-    const INV_EXP: f64 = 1.0 / (P + 1.0); // 1/15
-
-    let mut y = pack_state_bd(bodies);
-    let mut t = 0.0;
-
-    // initial h
-    let dir = if t_end >= 0.0 { 1.0 } else { -1.0 };
-    let mut h = (t_end - t).abs().max(1e-16) * 1e-3 * dir;
+impl Feagin14 {
+    fn new(masses: &[f64], y: Vec<BD>, variational: bool) -> Self {
+        Feagin14 {
+            masses: masses.iter().map(|&m| BD::from_f64(m).unwrap()).collect(),
+            masses_f64: masses.to_vec(),
+            y,
+            y_trial: Vec::new(),
+            variational,
+            t: BD::zero(),
+            h_trial: BD::zero(),
+        }
+    }
 
-    let mut result = Vec::<f64>::new();
-    push_sample(&mut result, &y, t, n_b);
+    fn trial(&mut self, rtol: f64, atol: f64) -> f64 {
+        let rhs = if self.variational {
+            deriv_variational_bd
+        } else {
+            deriv_bd
+        };
+        let (y_trial, errn) = erk_trial_bd(&self.y, &self.h_trial, &self.masses, rhs, rtol, atol);
+        self.y_trial = y_trial;
+        errn
+    }
+}
 
-    let max_steps = 10_000_000usize;
-    let mut steps = 0usize;
+impl EmbeddedPair for Feagin14 {
+    fn order(&self) -> f64 {
+        14.0
+    }
 
-    while (t - t_end).abs() > 0.0 && steps < max_steps {
-        // Don’t overshoot t_end
-        if (t + h - t_end) * dir > 0.0 {
-            h = t_end - t;
-        }
+    fn try_step(&mut self, h: f64, rtol: f64, atol: f64) -> f64 {
+        self.h_trial = BD::from_f64(h).unwrap();
+        self.trial(rtol, atol)
+    }
 
-        let (y_trial, errn) = erk_trial_bd(&y, h, &masses_bd, RTOL, ATOL);
+    fn accept(&mut self) {
+        std::mem::swap(&mut self.y, &mut self.y_trial);
+        self.t += &self.h_trial;
+    }
 
-        if errn <= 1.0 || ((t - t_end) * dir) >= 0.0 {
-            // accept
-            y = y_trial;
-            t += h;
-            push_sample(&mut result, &y, t, n_b);
+    fn state_f64(&self) -> Vec<f64> {
+        self.y.iter().map(|x| x.to_f64().unwrap()).collect()
+    }
 
-            let fac = if errn == 0.0 {
-                FAC_MAX
-            } else {
-                (SAFETY * errn.powf(-INV_EXP)).clamp(FAC_MIN, FAC_MAX)
-            };
-            h *= fac;
+    fn rhs_f64(&self, y: &[f64]) -> Vec<f64> {
+        if self.variational {
+            variational::deriv(y, &self.masses_f64, 0.0)
         } else {
-            // reject -> shrink
-            let fac = (SAFETY * errn.powf(-INV_EXP)).clamp(0.1, 0.5);
-            h *= fac;
+            deriv(y, &self.masses_f64, 0.0)
         }
+    }
 
-        steps += 1;
-        if h.abs() < 1e-22 {
-            break;
+    fn push_positions(&self, out: &mut Vec<f64>) {
+        for o in (0..6 * self.masses.len()).step_by(6) {
+            out.push(self.y[o].to_f64().unwrap());
+            out.push(self.y[o + 1].to_f64().unwrap());
+            out.push(self.y[o + 2].to_f64().unwrap());
         }
     }
 
-    unpack_state_bd(&y, bodies);
-    (result, t)
+    fn unpack(&self, bodies: &mut [Body]) {
+        unpack_state_bd(&self.y, bodies);
+    }
+}
+
+pub const SETTINGS: AdaptiveSettings = AdaptiveSettings {
+    rtol: 1e-18,
+    atol: 1e-18,
+    max_steps: 10_000_000,
+    h_min: 1e-22,
+    h_max: f64::INFINITY,
+    controller: Controller::PI,
+};
+
+// ---------- Public evolve (same API) ----------
+pub fn evolve(bodies: &mut [Body], t_end: f64) -> (Vec<f64>, f64) {
+    evolve_with(bodies, t_end, &SETTINGS)
+}
+
+/// `evolve` with explicit tolerances and controller.
+pub fn evolve_with(
+    bodies: &mut [Body],
+    t_end: f64,
+    settings: &AdaptiveSettings,
+) -> (Vec<f64>, f64) {
+    let masses: Vec<f64> = bodies.iter().map(|b| b.m).collect();
+    let mut pair = Feagin14::new(&masses, pack_state_bd(bodies), false);
+    evolve_positions(&mut pair, bodies, t_end, settings)
+}
+
+/// Integrate the trajectory together with its variational equations, all in BigDecimal.
+/// Returns (Φ(t) = ∂y(t)/∂y(0) as a 6N×6N row-major matrix, final time reached) and
+/// leaves the final state in `bodies`. Much slower than `evolve`: Φ adds 36N² components.
+pub fn evolve_variational(
+    bodies: &mut [Body],
+    t_end: f64,
+    settings: &AdaptiveSettings,
+) -> (Vec<f64>, f64) {
+    let masses: Vec<f64> = bodies.iter().map(|b| b.m).collect();
+    let mut pair = Feagin14::new(&masses, pack_augmented_bd(bodies), true);
+    let t = drive(&mut pair, t_end, settings, |_, _, _| {});
+    pair.unpack(bodies);
+    let dim = 6 * bodies.len();
+    let stm = pair.y[dim..].iter().map(|x| x.to_f64().unwrap()).collect();
+    (stm, t)
+}
+
+/// Flow of the packed BigDecimal state `y0` over a BigDecimal time `t_end`.
+/// The adaptive steps are driven in f64 as usual; a last step of size `t_end` minus the
+/// exact sum of the accepted steps lands on `t_end` to full BigDecimal precision.
+pub(crate) fn flow_bd(
+    y0: &[BD],
+    masses: &[f64],
+    t_end: &BD,
+    settings: &AdaptiveSettings,
+) -> Result<Vec<BD>, String> {
+    let t_end_f64 = t_end.to_f64().unwrap();
+    let mut pair = Feagin14::new(masses, y0.to_vec(), false);
+    let t = drive(&mut pair, t_end_f64, settings, |_, _, _| {});
+    if t != t_end_f64 {
+        return Err(format!("Integration stopped at t = {t} before {t_end_f64}"));
+    }
+    pair.h_trial = with_scale(t_end - &pair.t);
+    if !pair.h_trial.is_zero() {
+        pair.trial(settings.rtol, settings.atol);
+        pair.accept();
+    }
+    Ok(pair.y)
 }
@@ -1,3 +1,4 @@
 mod algo;
 mod coef;
-pub use algo::evolve;
+pub(crate) use algo::{BD_SCALE, flow_bd};
+pub use algo::{SETTINGS, evolve, evolve_variational, evolve_with};
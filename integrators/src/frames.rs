@@ -0,0 +1,89 @@
+// Trajectories in rotating frames.
+//
+// A relative periodic orbit returns to its initial state rotated by θ about z, so it only
+// closes in a frame turning at Ω = θ/T (the viewer's `theta_max` over the period). The
+// co-rotating frames instead turn with the configuration: one puts a chosen pair of
+// bodies on the +x axis, the other the major principal axis of inertia (kept continuous
+// from sample to sample, since its sign is arbitrary).
+//
+// All frames other than the inertial one are centred at the centre of mass, and rotate
+// about z; z components are left alone.
+
+use crate::utils::sub;
+
+/// Frame a trajectory is expressed in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Frame {
+    Inertial,
+    /// Rotating at a constant angular rate Ω (counter-clockwise positive): positions at
+    /// time t are rotated by -Ω t.
+    Rotating {
+        rate: f64,
+    },
+    /// Bodies i and j (0-based) on the x axis, r_j - r_i pointing to +x.
+    Pair(usize, usize),
+    /// Major principal axis of the x-y inertia tensor along x.
+    PrincipalAxes,
+}
+
+/// `series` (the flat `[x, y, z] * 3, t` history returned by the `evolve` functions) in
+/// `frame`.
+pub fn to_frame(series: &[f64], masses: &[f64; 3], frame: Frame) -> Result<Vec<f64>, String> {
+    if let Frame::Pair(i, j) = frame
+        && (i >= 3 || j >= 3 || i == j)
+    {
+        return Err(format!(
+            "Pair frame needs two different bodies out of 3, got {} and {}",
+            i + 1,
+            j + 1
+        ));
+    }
+    if frame == Frame::Inertial {
+        return Ok(series.to_vec());
+    }
+    let mass: f64 = masses.iter().sum();
+    let mut out = Vec::with_capacity(series.len());
+    let mut previous_axis: Option<f64> = None;
+    for s in series.chunks_exact(10) {
+        let mut r = [[s[0], s[1], s[2]], [s[3], s[4], s[5]], [s[6], s[7], s[8]]];
+        let t = s[9];
+        let mut centre = [0.0; 3];
+        for (ri, m) in r.iter().zip(masses) {
+            for k in 0..3 {
+                centre[k] += m * ri[k] / mass;
+            }
+        }
+        for ri in r.iter_mut() {
+            *ri = sub(*ri, centre);
+        }
+        let angle = match frame {
+            Frame::Inertial => 0.0,
+            Frame::Rotating { rate } => rate * t,
+            Frame::Pair(i, j) => {
+                let d = sub(r[j], r[i]);
+                d[1].atan2(d[0])
+            }
+            Frame::PrincipalAxes => {
+                let (mut sxx, mut syy, mut sxy) = (0.0, 0.0, 0.0);
+                for (ri, m) in r.iter().zip(masses) {
+                    sxx += m * ri[0] * ri[0];
+                    syy += m * ri[1] * ri[1];
+                    sxy += m * ri[0] * ri[1];
+                }
+                let mut axis = 0.5 * (2.0 * sxy).atan2(sxx - syy);
+                // The axis is only defined up to a half turn: stay close to the last one
+                if let Some(prev) = previous_axis {
+                    axis -= std::f64::consts::PI * ((axis - prev) / std::f64::consts::PI).round();
+                }
+                previous_axis = Some(axis);
+                axis
+            }
+        };
+        let (sin, cos) = (-angle).sin_cos();
+        for ri in &r {
+            out.extend_from_slice(&[cos * ri[0] - sin * ri[1], sin * ri[0] + cos * ri[1], ri[2]]);
+        }
+        out.push(t);
+    }
+    Ok(out)
+}
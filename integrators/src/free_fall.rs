@@ -0,0 +1,303 @@
+// Periodic orbits of the free-fall problem (brake orbits).
+//
+// Three bodies start at rest at (-0.5, 0), (x, y) and (0.5, 0). If at some time τ all
+// velocities vanish again, the motion retraces itself backwards and the orbit is periodic
+// with period T = 2τ: at T the bodies are back at rest where they started. Like the
+// published tables (`sd_80.csv`, the F-list of `free_fall.js`) we report T.
+// Since momentum and angular momentum are zero throughout, the three unknowns (x, y, τ)
+// match the independent conditions on v(τ) = 0.
+//
+// Brake points are detected as local minima of the kinetic energy K(t), located on the
+// cubic Hermite interpolant of K between accepted steps (dK/dt = Σ m_i v_i · a_i), and
+// polished by Gauss–Newton on v(τ). Starting points
+// (x, y) are taken in the Agekyan–Anosova domain: x, y >= 0, (x + 0.5)² + y² <= 1.
+
+use crate::{
+    adaptive::{AdaptiveSettings, drive},
+    dop853,
+    erk::{self, ErkPair},
+    ic, linalg,
+    parallel::par_map,
+    search::Axis,
+    utils::total_energy,
+};
+
+// Points of the Hermite interpolant examined per bracketing step
+const HERMITE_SAMPLES: usize = 16;
+// Central-difference step in x and y
+const FD_STEP: f64 = 1e-7;
+// Orbits closer than this in (x, y) and T are the same
+const SAME_ORBIT: f64 = 1e-7;
+
+/// Options of the brake-orbit search and corrector.
+#[derive(Clone, Copy, Debug)]
+pub struct FreeFallOptions {
+    /// Masses of the bodies at (-0.5, 0), (x, y) and (0.5, 0).
+    pub masses: [f64; 3],
+    /// Settings of the DOP853 integrations of the corrector.
+    pub settings: AdaptiveSettings,
+    /// Looser settings for the brake-point detection of `find_brake`.
+    pub search_settings: AdaptiveSettings,
+    /// Brake points before this time are ignored.
+    pub t_min: f64,
+    /// Look for a brake point up to this time.
+    pub t_max: f64,
+    /// A kinetic-energy minimum counts as a brake candidate below this fraction of |E|.
+    pub max_kinetic: f64,
+    /// Stop once |v(τ)| is below this (integration errors near close encounters keep it
+    /// from going much lower).
+    pub tol: f64,
+    pub max_iterations: usize,
+    /// Worker threads; 0 uses all available cores.
+    pub threads: usize,
+}
+
+impl Default for FreeFallOptions {
+    fn default() -> Self {
+        FreeFallOptions {
+            masses: [1.0; 3],
+            settings: AdaptiveSettings {
+                rtol: 1e-13,
+                atol: 1e-15,
+                ..dop853::SETTINGS
+            },
+            search_settings: AdaptiveSettings {
+                rtol: 1e-10,
+                atol: 1e-12,
+                ..dop853::SETTINGS
+            },
+            t_min: 0.5,
+            t_max: 10.0,
+            max_kinetic: 1e-2,
+            tol: 1e-7,
+            max_iterations: 20,
+            threads: 0,
+        }
+    }
+}
+
+/// A converged brake orbit.
+#[derive(Clone, Debug)]
+pub struct BrakeOrbit {
+    pub x: f64,
+    pub y: f64,
+    /// Time of the brake point, half the period.
+    pub t_brake: f64,
+    pub energy: f64,
+    /// |v(τ)| at the solution.
+    pub residual: f64,
+    pub iterations: usize,
+}
+
+impl BrakeOrbit {
+    pub fn period(&self) -> f64 {
+        2.0 * self.t_brake
+    }
+
+    /// Scale-invariant period T |E|^(3/2), the last column of `sd_80.csv`.
+    pub fn scaled_period(&self) -> f64 {
+        self.period() * self.energy.abs().powf(1.5)
+    }
+}
+
+/// Whether (x, y) lies in the Agekyan–Anosova domain.
+pub fn in_domain(x: f64, y: f64) -> bool {
+    x >= 0.0 && y >= 0.0 && (x + 0.5).powi(2) + y * y <= 1.0
+}
+
+/// Kinetic energy and its time derivative of the packed state `y`.
+fn kinetic(y: &[f64], masses: &[f64]) -> (f64, f64) {
+    let f = erk::deriv(y, masses, 0.0);
+    let mut k = 0.0;
+    let mut dk = 0.0;
+    for (i, m) in masses.iter().enumerate() {
+        for c in 0..3 {
+            let v = y[6 * i + 3 + c];
+            k += 0.5 * m * v * v;
+            dk += m * v * f[6 * i + 3 + c];
+        }
+    }
+    (k, dk)
+}
+
+/// The deepest kinetic-energy minimum in [t_min, t_max] starting from (x, y), as
+/// (time, K / |E|), if one falls below `max_kinetic`.
+pub fn find_brake(x: f64, y: f64, options: &FreeFallOptions) -> Option<(f64, f64)> {
+    let bodies = ic::free_fall(x, y, options.masses);
+    let masses = options.masses;
+    let scale = total_energy(&bodies).abs();
+    let mut best = (f64::NAN, f64::INFINITY);
+    let mut prev = (0.0, 0.0, 0.0);
+    let mut pair = ErkPair::new(&dop853::TABLEAU, &bodies, 0.0);
+    drive(
+        &mut pair,
+        options.t_max,
+        &options.search_settings,
+        |p, t, h| {
+            let (k, dk) = kinetic(p.state(), &masses);
+            let (t0, k0, dk0) = prev;
+            if t0 >= options.t_min && dk0 < 0.0 && dk >= 0.0 {
+                for s in 1..HERMITE_SAMPLES {
+                    let s = s as f64 / HERMITE_SAMPLES as f64;
+                    let (s2, s3) = (s * s, s * s * s);
+                    let ks = (2.0 * s3 - 3.0 * s2 + 1.0) * k0
+                        + (s3 - 2.0 * s2 + s) * h * dk0
+                        + (-2.0 * s3 + 3.0 * s2) * k
+                        + (s3 - s2) * h * dk;
+                    if ks / scale < best.1 {
+                        best = (t0 + s * h, ks.max(0.0) / scale);
+                    }
+                }
+            }
+            prev = (t, k, dk);
+        },
+    );
+    (best.1 <= options.max_kinetic).then_some(best)
+}
+
+/// Velocities at `tau`, packed as [v0, v1, v2].
+fn velocities(x: f64, y: f64, tau: f64, options: &FreeFallOptions) -> Result<Vec<f64>, String> {
+    let mut bodies = ic::free_fall(x, y, options.masses);
+    let (_, t) = dop853::evolve_with(&mut bodies, tau, &options.settings);
+    if t != tau {
+        return Err(format!("Integration stopped at t = {t} before {tau}"));
+    }
+    let v: Vec<f64> = bodies.iter().flat_map(|b| b.v).collect();
+    if v.iter().any(|x| !x.is_finite()) {
+        return Err("Velocities are not finite".to_string());
+    }
+    Ok(v)
+}
+
+/// ∂v(τ)/∂(x, y, τ) as a 9×3 row-major matrix: central differences in x and y (the
+/// variational equations stall in the close encounters free fall is full of), and the
+/// accelerations at τ for the last column.
+fn jacobian(x: f64, y: f64, tau: f64, options: &FreeFallOptions) -> Result<Vec<f64>, String> {
+    let h = FD_STEP;
+    let dx: Vec<f64> = velocities(x + h, y, tau, options)?
+        .iter()
+        .zip(velocities(x - h, y, tau, options)?)
+        .map(|(p, m)| (p - m) / (2.0 * h))
+        .collect();
+    let dy: Vec<f64> = velocities(x, y + h, tau, options)?
+        .iter()
+        .zip(velocities(x, y - h, tau, options)?)
+        .map(|(p, m)| (p - m) / (2.0 * h))
+        .collect();
+    let mut bodies = ic::free_fall(x, y, options.masses);
+    dop853::evolve_with(&mut bodies, tau, &options.settings);
+    let f = erk::deriv(&erk::pack_state(&bodies), &options.masses, 0.0);
+    let mut a = Vec::with_capacity(27);
+    for i in 0..3 {
+        for c in 0..3 {
+            a.extend_from_slice(&[dx[3 * i + c], dy[3 * i + c], f[6 * i + 3 + c]]);
+        }
+    }
+    Ok(a)
+}
+
+fn norm(v: &[f64]) -> f64 {
+    v.iter().map(|x| x * x).sum::<f64>().sqrt()
+}
+
+/// Gauss–Newton on v(τ) = 0 from the guess (x, y, τ = `t_brake`); the step is halved
+/// while it does not reduce |v(τ)|.
+pub fn solve_brake_orbit(
+    x: f64,
+    y: f64,
+    t_brake: f64,
+    options: &FreeFallOptions,
+) -> Result<BrakeOrbit, String> {
+    let mut p = [x, y, t_brake];
+    let mut v = velocities(p[0], p[1], p[2], options)?;
+    let mut iterations = 0;
+    while norm(&v) > options.tol && iterations < options.max_iterations {
+        let a = jacobian(p[0], p[1], p[2], options)?;
+        let b: Vec<f64> = v.iter().map(|x| -x).collect();
+        let delta = linalg::least_squares(&a, 9, 3, &b)?;
+        let mut scale = 1.0;
+        loop {
+            let q = [
+                p[0] + scale * delta[0],
+                p[1] + scale * delta[1],
+                p[2] + scale * delta[2],
+            ];
+            if let Ok(vq) = velocities(q[0], q[1], q[2], options)
+                && norm(&vq) < norm(&v)
+            {
+                (p, v) = (q, vq);
+                break;
+            }
+            scale /= 2.0;
+            if scale < 1e-4 {
+                return Err(format!(
+                    "Newton step failed to reduce |v| = {:e} after {iterations} iterations",
+                    norm(&v)
+                ));
+            }
+        }
+        iterations += 1;
+    }
+    let residual = norm(&v);
+    if residual > options.tol {
+        return Err(format!(
+            "Did not converge in {iterations} iterations (|v| = {residual:e})"
+        ));
+    }
+    Ok(BrakeOrbit {
+        x: p[0],
+        y: p[1],
+        t_brake: p[2],
+        energy: total_energy(&ic::free_fall(p[0], p[1], options.masses)),
+        residual,
+        iterations,
+    })
+}
+
+/// Look for brake orbits from every grid point inside the Agekyan–Anosova domain, on a
+/// thread pool. Converged orbits that left the domain are dropped and duplicates merged;
+/// the rest are sorted by period. `progress(done, total)` runs on the calling thread.
+pub fn search_brake_orbits(
+    x: &Axis,
+    y: &Axis,
+    options: &FreeFallOptions,
+    progress: impl FnMut(usize, usize),
+) -> Vec<BrakeOrbit> {
+    let grid: Vec<[f64; 2]> = (0..y.steps)
+        .flat_map(|j| (0..x.steps).map(move |i| [x.value(i), y.value(j)]))
+        .filter(|&[x, y]| in_domain(x, y))
+        .collect();
+    let results = par_map(
+        &grid,
+        options.threads,
+        |&[x, y]| {
+            let (tau, _) = find_brake(x, y, options).ok_or("no brake point")?;
+            let mut orbit = solve_brake_orbit(x, y, tau, options)?;
+            // The return to the start at 2τ is a zero-velocity point too: halve while the
+            // orbit already brakes at τ/2
+            while let Ok(half) = solve_brake_orbit(orbit.x, orbit.y, orbit.t_brake / 2.0, options)
+                && (half.x - orbit.x).abs() < SAME_ORBIT
+                && (half.y - orbit.y).abs() < SAME_ORBIT
+                && (2.0 * half.t_brake - orbit.t_brake).abs() < SAME_ORBIT * orbit.t_brake
+            {
+                orbit = half;
+            }
+            Ok(orbit)
+        },
+        progress,
+    );
+
+    let mut orbits: Vec<BrakeOrbit> = Vec::new();
+    for orbit in results.into_iter().flatten() {
+        let same = |o: &BrakeOrbit| {
+            (o.x - orbit.x).abs() < SAME_ORBIT
+                && (o.y - orbit.y).abs() < SAME_ORBIT
+                && (o.t_brake - orbit.t_brake).abs() < SAME_ORBIT * orbit.t_brake
+        };
+        if in_domain(orbit.x, orbit.y) && !orbits.iter().any(same) {
+            orbits.push(orbit);
+        }
+    }
+    orbits.sort_by(|a, b| a.t_brake.total_cmp(&b.t_brake));
+    orbits
+}
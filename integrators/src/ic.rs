@@ -0,0 +1,88 @@
+// Initial conditions of the published catalogs, from the parameters their tables list.
+//
+// Each builder reproduces the arithmetic of the corresponding example list of the viewer
+// (`docs/examples/*.js`), body order included, so a row of a table gives the same three
+// bodies here, in the CLI and in the browser. Catalogs that list full states (Matt
+// Sheen's solutions, custom examples) need no builder.
+//
+// All orbits are planar except Li & Liao's 3D ones. Only `broucke` and `bhh` move the
+// bodies to the centre-of-mass frame; the others are set up there already, or (free fall)
+// list positions as they are.
+
+use crate::types::Body;
+
+fn body(m: f64, r: [f64; 3], v: [f64; 3]) -> Body {
+    Body { m, r, v }
+}
+
+/// Šuvakov & Dmitrašinović's collinear (Euler) start: unit masses at (-1, 0) and (1, 0)
+/// moving with (v1, v2), and mass `m3` at the origin moving with -2 (v1, v2) / m3 (zero
+/// total momentum). The equal-mass catalog has m3 = 1; Li, Dmitrašinović & Liao's
+/// unequal-mass one lists m3.
+pub fn suvakov(v1: f64, v2: f64, m3: f64) -> Vec<Body> {
+    vec![
+        body(1.0, [-1.0, 0.0, 0.0], [v1, v2, 0.0]),
+        body(m3, [0.0; 3], [-2.0 * v1 / m3, -2.0 * v2 / m3, 0.0]),
+        body(1.0, [1.0, 0.0, 0.0], [v1, v2, 0.0]),
+    ]
+}
+
+/// Free fall (Puzynin et al., the `sd_80.csv` and `sol_80_600.csv` tables and the F-list):
+/// bodies at rest at (-0.5, 0), (x, y) and (0.5, 0) with `masses` in that order. The F-list
+/// names its masses `F(m1, m3, m2)`.
+pub fn free_fall(x: f64, y: f64, masses: [f64; 3]) -> Vec<Body> {
+    [[-0.5, 0.0], [x, y], [0.5, 0.0]]
+        .iter()
+        .zip(masses)
+        .map(|(r, m)| body(m, [r[0], r[1], 0.0], [0.0; 3]))
+        .collect()
+}
+
+/// Broucke's collinear start: bodies 1 and 2 at x1 and x2 on the x axis moving along y with
+/// v1 and v2, body 3 at rest at the origin, then moved to the centre-of-mass frame.
+pub fn broucke(masses: [f64; 3], x1: f64, v1: f64, x2: f64, v2: f64) -> Vec<Body> {
+    let [m1, m2, m3] = masses;
+    let mass = m1 + m2 + m3;
+    let xc = (m1 * x1 + m2 * x2) / mass;
+    let vc = (m1 * v1 + m2 * v2) / mass;
+    vec![
+        body(m1, [x1 - xc, 0.0, 0.0], [0.0, v1 - vc, 0.0]),
+        body(m2, [x2 - xc, 0.0, 0.0], [0.0, v2 - vc, 0.0]),
+        body(m3, [-xc, 0.0, 0.0], [0.0, -vc, 0.0]),
+    ]
+}
+
+/// Broucke, Hadjidemetriou & Hénon's satellites: bodies at x1, 0 and 1 on the x axis moving
+/// along y with v1, v2 and v3, where v2 makes the total momentum vanish, centred at the
+/// centre of mass.
+pub fn bhh(masses: [f64; 3], x1: f64, v1: f64, v3: f64) -> Vec<Body> {
+    let [m1, m2, m3] = masses;
+    let v2 = -(m1 * v1 + m3 * v3) / m2;
+    let xc = (m1 * x1 + m3) / (m1 + m2 + m3);
+    vec![
+        body(m1, [x1 - xc, 0.0, 0.0], [0.0, v1, 0.0]),
+        body(m2, [-xc, 0.0, 0.0], [0.0, v2, 0.0]),
+        body(m3, [1.0 - xc, 0.0, 0.0], [0.0, v3, 0.0]),
+    ]
+}
+
+/// Li & Liao's 3D orbits (`data.csv`): unit masses at (∓1, 0, 0) moving with
+/// (vx, vy, ±vz), and mass `m3` at (0, 0, z0) moving with -2 (vx, vy, 0) / m3.
+pub fn li_liao_3d(z0: f64, vx: f64, vy: f64, vz: f64, m3: f64) -> Vec<Body> {
+    vec![
+        body(1.0, [-1.0, 0.0, 0.0], [vx, vy, vz]),
+        body(1.0, [1.0, 0.0, 0.0], [vx, vy, -vz]),
+        body(m3, [0.0, 0.0, z0], [-2.0 * vx / m3, -2.0 * vy / m3, 0.0]),
+    ]
+}
+
+/// Symmetric choreography start of the `choreographies` list: masses 1/3 at (-2c1, 0),
+/// (c1, c2) and (c1, -c2) with velocities (0, -2c4), (c3, c4) and (-c3, c4).
+pub fn choreography(c1: f64, c2: f64, c3: f64, c4: f64) -> Vec<Body> {
+    let m = 1.0 / 3.0;
+    vec![
+        body(m, [-2.0 * c1, 0.0, 0.0], [0.0, -2.0 * c4, 0.0]),
+        body(m, [c1, c2, 0.0], [c3, c4, 0.0]),
+        body(m, [c1, -c2, 0.0], [-c3, c4, 0.0]),
+    ]
+}
@@ -1,16 +1,93 @@
+mod adaptive;
+mod batch;
+mod bogacki_shampine;
+mod cash_karp;
+mod catalog;
+mod continuation;
+mod coordinates;
+mod diagnostics;
 mod dop853;
+mod dopri5;
+mod erk;
+mod export;
 mod feagin14;
+mod frames;
+mod free_fall;
+pub mod ic;
+mod linalg;
+mod method;
+mod normalize;
+mod parallel;
+mod period;
 mod runge_kutta;
+mod search;
+mod shape_sphere;
+mod shooting;
+mod symmetry;
+mod topology;
 mod types;
 mod utils;
+mod variational;
 mod velocity_verlet;
 
+pub use bogacki_shampine::SETTINGS as BS32_SETTINGS;
+pub use bogacki_shampine::evolve as evolve_bs32;
+pub use bogacki_shampine::evolve_batch as evolve_bs32_batch;
+pub use bogacki_shampine::evolve_with as evolve_bs32_with;
+pub use cash_karp::SETTINGS as CASH_KARP_SETTINGS;
+pub use cash_karp::evolve as evolve_cash_karp;
+pub use cash_karp::evolve_batch as evolve_cash_karp_batch;
+pub use cash_karp::evolve_with as evolve_cash_karp_with;
+pub use dop853::SETTINGS as DOP853_SETTINGS;
 pub use dop853::evolve as evolve_dop853;
+pub use dop853::evolve_batch as evolve_dop853_batch;
+pub use dop853::evolve_variational as evolve_dop853_variational;
+pub use dop853::evolve_with as evolve_dop853_with;
+pub use dopri5::SETTINGS as DOPRI5_SETTINGS;
+pub use dopri5::evolve as evolve_dopri5;
+pub use dopri5::evolve_batch as evolve_dopri5_batch;
+pub use dopri5::evolve_dense as evolve_dopri5_dense;
+pub use dopri5::evolve_with as evolve_dopri5_with;
+pub use feagin14::SETTINGS as FEAGIN14_SETTINGS;
 pub use feagin14::evolve as evolve_feagin14;
+pub use feagin14::evolve_variational as evolve_feagin14_variational;
+pub use feagin14::evolve_with as evolve_feagin14_with;
 pub use runge_kutta::evolve as evolve_rk4;
 pub use velocity_verlet::evolve as evolve_verlet;
 
-pub use crate::{types::Body, utils::total_angular_momentum, utils::total_energy};
+pub use bigdecimal::BigDecimal;
+
+pub use crate::{
+    adaptive::{AdaptiveSettings, Controller},
+    catalog::{CatalogEntry, Stability, parse_free_fall, parse_li_liao_3d},
+    continuation::{ContinuationOptions, Event, Family, FamilyPoint, Parameter, continue_family},
+    coordinates::{Hyperspherical, Jacobi, reduced_masses},
+    diagnostics::{Diagnostics, Drift, Invariants, diagnose},
+    export::Table,
+    frames::{Frame, to_frame},
+    free_fall::{
+        BrakeOrbit, FreeFallOptions, find_brake, in_domain, search_brake_orbits, solve_brake_orbit,
+    },
+    linalg::eigenvalues,
+    method::Method,
+    normalize::{ScaleTarget, Scaling, remove_net_momentum, rescale, rescale_to, to_barycentric},
+    parallel::{BatchOptions, Job, JobResult, par_map, run_batch, run_job, worker_count},
+    period::{PeriodEstimate, PeriodOptions, estimate_periods},
+    search::{Axis, NearReturn, Scan, SearchOptions, scan_grid},
+    shape_sphere::{collision_points, jacobi_vectors, lagrange_points, shape_point, shape_sphere},
+    shooting::{Refined, ShootingOptions, refine_orbit, refine_orbit_bd},
+    symmetry::{Symmetries, SymmetryOp, SymmetryOptions, find_symmetries},
+    topology::{
+        Syzygy, Topology, canonical_word, classify, cyclic_reduce, inverse_word, reduce_word,
+        syzygies, syzygy_word,
+    },
+    types::Body,
+    utils::{
+        centre_of_mass, kinetic_energy, moment_of_inertia, potential_energy, sundman_invariant,
+        total_angular_momentum, total_energy, total_linear_momentum, virial_ratio,
+    },
+    variational::{Monodromy, STABILITY_TOL, monodromy},
+};
 
 pub fn sum(a: &str, b: &str) -> String {
     let a: bigdecimal::BigDecimal = a.parse().unwrap();
@@ -0,0 +1,362 @@
+// Small dense linear algebra on row-major `Vec<f64>` matrices.
+//
+// Eigenvalues of a general real matrix: balancing, reduction to upper Hessenberg form
+// by stabilized elementary similarity transforms, then the shifted double-step QR
+// algorithm (EISPACK balanc/elmhes/hqr, as in Numerical Recipes §11.5–11.6).
+
+const RADIX: f64 = 2.0;
+// QR steps allowed per eigenvalue, on average (EISPACK hqr allows 30n in total)
+const MAX_QR_ITERATIONS: usize = 30;
+
+/// Balance `a` (n×n) in place: a diagonal similarity transform that makes row and
+/// column norms comparable, which improves the accuracy of the eigenvalues.
+fn balance(a: &mut [f64], n: usize) {
+    let sqrdx = RADIX * RADIX;
+    let mut done = false;
+    while !done {
+        done = true;
+        for i in 0..n {
+            let (mut r, mut c) = (0.0, 0.0);
+            for j in 0..n {
+                if j != i {
+                    c += a[j * n + i].abs();
+                    r += a[i * n + j].abs();
+                }
+            }
+            if c == 0.0 || r == 0.0 {
+                continue;
+            }
+            let s = c + r;
+            let mut f = 1.0;
+            let mut g = r / RADIX;
+            while c < g {
+                f *= RADIX;
+                c *= sqrdx;
+            }
+            g = r * RADIX;
+            while c > g {
+                f /= RADIX;
+                c /= sqrdx;
+            }
+            if (c + r) / f < 0.95 * s {
+                done = false;
+                let g = 1.0 / f;
+                for j in 0..n {
+                    a[i * n + j] *= g;
+                }
+                for j in 0..n {
+                    a[j * n + i] *= f;
+                }
+            }
+        }
+    }
+}
+
+/// Reduce `a` (n×n) to upper Hessenberg form in place, by Gaussian elimination with
+/// pivoting. Entries below the subdiagonal are zeroed.
+fn hessenberg(a: &mut [f64], n: usize) {
+    for m in 1..n.saturating_sub(1) {
+        let mut x: f64 = 0.0;
+        let mut i = m;
+        for j in m..n {
+            if a[j * n + m - 1].abs() > x.abs() {
+                x = a[j * n + m - 1];
+                i = j;
+            }
+        }
+        if i != m {
+            for j in (m - 1)..n {
+                a.swap(i * n + j, m * n + j);
+            }
+            for j in 0..n {
+                a.swap(j * n + i, j * n + m);
+            }
+        }
+        if x != 0.0 {
+            for i in (m + 1)..n {
+                let mut y = a[i * n + m - 1];
+                if y != 0.0 {
+                    y /= x;
+                    a[i * n + m - 1] = y;
+                    for j in m..n {
+                        a[i * n + j] -= y * a[m * n + j];
+                    }
+                    for j in 0..n {
+                        a[j * n + m] += y * a[j * n + i];
+                    }
+                }
+            }
+        }
+    }
+    for i in 2..n {
+        for j in 0..i - 1 {
+            a[i * n + j] = 0.0;
+        }
+    }
+}
+
+fn sign(a: f64, b: f64) -> f64 {
+    if b >= 0.0 { a.abs() } else { -a.abs() }
+}
+
+/// Eigenvalues of an upper Hessenberg matrix (destroyed), as (re, im).
+fn hessenberg_qr(h: &mut [f64], n: usize) -> Result<Vec<(f64, f64)>, String> {
+    // 1-based indexing keeps the loops identical to the reference algorithm
+    let ix = |i: usize, j: usize| (i - 1) * n + (j - 1);
+    let mut wr = vec![0.0; n + 1];
+    let mut wi = vec![0.0; n + 1];
+
+    let mut anorm = 0.0;
+    for i in 1..=n {
+        for j in i.saturating_sub(1).max(1)..=n {
+            anorm += h[ix(i, j)].abs();
+        }
+    }
+
+    let mut nn = n;
+    let mut t = 0.0;
+    let mut total_its = 0;
+    let (mut p, mut q, mut r): (f64, f64, f64);
+    while nn >= 1 {
+        let mut its = 0;
+        loop {
+            // Look for a single small subdiagonal element
+            let mut l = nn;
+            while l >= 2 {
+                let mut s = h[ix(l - 1, l - 1)].abs() + h[ix(l, l)].abs();
+                if s == 0.0 {
+                    s = anorm;
+                }
+                if h[ix(l, l - 1)].abs() + s == s {
+                    h[ix(l, l - 1)] = 0.0;
+                    break;
+                }
+                l -= 1;
+            }
+            let mut x = h[ix(nn, nn)];
+            if l == nn {
+                // One root found
+                wr[nn] = x + t;
+                wi[nn] = 0.0;
+                nn -= 1;
+            } else {
+                let mut y = h[ix(nn - 1, nn - 1)];
+                let mut w = h[ix(nn, nn - 1)] * h[ix(nn - 1, nn)];
+                if l == nn - 1 {
+                    // Two roots found
+                    p = 0.5 * (y - x);
+                    q = p * p + w;
+                    let mut z = q.abs().sqrt();
+                    x += t;
+                    if q >= 0.0 {
+                        z = p + sign(z, p);
+                        wr[nn - 1] = x + z;
+                        wr[nn] = x + z;
+                        if z != 0.0 {
+                            wr[nn] = x - w / z;
+                        }
+                        wi[nn - 1] = 0.0;
+                        wi[nn] = 0.0;
+                    } else {
+                        wr[nn - 1] = x + p;
+                        wr[nn] = x + p;
+                        wi[nn - 1] = -z;
+                        wi[nn] = z;
+                    }
+                    nn -= 2;
+                } else {
+                    if total_its == MAX_QR_ITERATIONS * n {
+                        return Err("eigenvalues: QR iteration did not converge".to_string());
+                    }
+                    if its > 0 && its % 10 == 0 {
+                        // Exceptional shift
+                        t += x;
+                        for i in 1..=nn {
+                            h[ix(i, i)] -= x;
+                        }
+                        let s = h[ix(nn, nn - 1)].abs() + h[ix(nn - 1, nn - 2)].abs();
+                        x = 0.75 * s;
+                        y = x;
+                        w = -0.4375 * s * s;
+                    }
+                    its += 1;
+                    total_its += 1;
+
+                    // Form the shift and look for two consecutive small subdiagonal elements
+                    let mut m = nn - 2;
+                    loop {
+                        let z = h[ix(m, m)];
+                        let rr = x - z;
+                        let ss = y - z;
+                        p = (rr * ss - w) / h[ix(m + 1, m)] + h[ix(m, m + 1)];
+                        q = h[ix(m + 1, m + 1)] - z - rr - ss;
+                        r = h[ix(m + 2, m + 1)];
+                        let s = p.abs() + q.abs() + r.abs();
+                        p /= s;
+                        q /= s;
+                        r /= s;
+                        if m == l {
+                            break;
+                        }
+                        let u = h[ix(m, m - 1)].abs() * (q.abs() + r.abs());
+                        let v = p.abs()
+                            * (h[ix(m - 1, m - 1)].abs() + z.abs() + h[ix(m + 1, m + 1)].abs());
+                        if u + v == v {
+                            break;
+                        }
+                        m -= 1;
+                    }
+                    for i in (m + 2)..=nn {
+                        h[ix(i, i - 2)] = 0.0;
+                        if i != m + 2 {
+                            h[ix(i, i - 3)] = 0.0;
+                        }
+                    }
+
+                    // Double QR step on rows l..nn and columns m..nn
+                    for k in m..nn {
+                        if k != m {
+                            p = h[ix(k, k - 1)];
+                            q = h[ix(k + 1, k - 1)];
+                            r = if k != nn - 1 {
+                                h[ix(k + 2, k - 1)]
+                            } else {
+                                0.0
+                            };
+                            x = p.abs() + q.abs() + r.abs();
+                            if x != 0.0 {
+                                p /= x;
+                                q /= x;
+                                r /= x;
+                            }
+                        }
+                        let s = sign((p * p + q * q + r * r).sqrt(), p);
+                        if s == 0.0 {
+                            continue;
+                        }
+                        if k == m {
+                            if l != m {
+                                h[ix(k, k - 1)] = -h[ix(k, k - 1)];
+                            }
+                        } else {
+                            h[ix(k, k - 1)] = -s * x;
+                        }
+                        p += s;
+                        x = p / s;
+                        y = q / s;
+                        let z = r / s;
+                        q /= p;
+                        r /= p;
+                        for j in k..=nn {
+                            let mut pp = h[ix(k, j)] + q * h[ix(k + 1, j)];
+                            if k != nn - 1 {
+                                pp += r * h[ix(k + 2, j)];
+                                h[ix(k + 2, j)] -= pp * z;
+                            }
+                            h[ix(k + 1, j)] -= pp * y;
+                            h[ix(k, j)] -= pp * x;
+                        }
+                        let mmin = nn.min(k + 3);
+                        for i in l..=mmin {
+                            let mut pp = x * h[ix(i, k)] + y * h[ix(i, k + 1)];
+                            if k != nn - 1 {
+                                pp += z * h[ix(i, k + 2)];
+                                h[ix(i, k + 2)] -= pp * r;
+                            }
+                            h[ix(i, k + 1)] -= pp * q;
+                            h[ix(i, k)] -= pp;
+                        }
+                    }
+                }
+            }
+            // Keep iterating on the same block only after a QR step
+            if l + 1 >= nn {
+                break;
+            }
+        }
+    }
+    Ok(wr[1..]
+        .iter()
+        .copied()
+        .zip(wi[1..].iter().copied())
+        .collect())
+}
+
+/// Eigenvalues of the n×n row-major matrix `a`, as (re, im) pairs sorted by
+/// decreasing modulus. Complex eigenvalues come in conjugate pairs.
+pub fn eigenvalues(a: &[f64], n: usize) -> Result<Vec<(f64, f64)>, String> {
+    if a.len() != n * n {
+        return Err(format!(
+            "eigenvalues: expected {} entries, got {}",
+            n * n,
+            a.len()
+        ));
+    }
+    if a.iter().any(|x| !x.is_finite()) {
+        return Err("eigenvalues: matrix is not finite".to_string());
+    }
+    let mut h = a.to_vec();
+    balance(&mut h, n);
+    hessenberg(&mut h, n);
+    let mut ev = hessenberg_qr(&mut h, n)?;
+    ev.sort_by(|a, b| b.0.hypot(b.1).total_cmp(&a.0.hypot(a.1)));
+    Ok(ev)
+}
+
+/// Least-squares solution of the overdetermined system A x ≈ b (A is rows×cols,
+/// row-major, rows >= cols, full column rank) by Householder QR.
+pub fn least_squares(a: &[f64], rows: usize, cols: usize, b: &[f64]) -> Result<Vec<f64>, String> {
+    if rows < cols || a.len() != rows * cols || b.len() != rows {
+        return Err(format!(
+            "least_squares: bad shapes ({rows}x{cols} matrix, {} entries, {} rhs)",
+            a.len(),
+            b.len()
+        ));
+    }
+    let mut a = a.to_vec();
+    let mut b = b.to_vec();
+    let mut v = vec![0.0; rows];
+    for k in 0..cols {
+        // Householder vector zeroing column k below the diagonal
+        let norm = (k..rows)
+            .map(|i| a[i * cols + k] * a[i * cols + k])
+            .sum::<f64>()
+            .sqrt();
+        if norm == 0.0 {
+            return Err("least_squares: matrix is rank deficient".to_string());
+        }
+        let alpha = if a[k * cols + k] > 0.0 { -norm } else { norm };
+        for i in k..rows {
+            v[i] = a[i * cols + k];
+        }
+        v[k] -= alpha;
+        let vnorm2: f64 = v[k..rows].iter().map(|x| x * x).sum();
+        if vnorm2 == 0.0 {
+            continue;
+        }
+        for j in k..cols {
+            let s: f64 = (k..rows).map(|i| v[i] * a[i * cols + j]).sum();
+            let f = 2.0 * s / vnorm2;
+            for i in k..rows {
+                a[i * cols + j] -= f * v[i];
+            }
+        }
+        let s: f64 = (k..rows).map(|i| v[i] * b[i]).sum();
+        let f = 2.0 * s / vnorm2;
+        for i in k..rows {
+            b[i] -= f * v[i];
+        }
+    }
+
+    // Back substitution with R
+    let mut x = vec![0.0; cols];
+    for k in (0..cols).rev() {
+        let r = a[k * cols + k];
+        if r == 0.0 {
+            return Err("least_squares: matrix is rank deficient".to_string());
+        }
+        let s: f64 = ((k + 1)..cols).map(|j| a[k * cols + j] * x[j]).sum();
+        x[k] = (b[k] - s) / r;
+    }
+    Ok(x)
+}
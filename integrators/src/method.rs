@@ -0,0 +1,129 @@
+// Integrator selection by name, shared by the CLI, the wasm bindings and the batch runner.
+
+use crate::{
+    adaptive::AdaptiveSettings, bogacki_shampine, cash_karp, dop853, dopri5, erk::Tableau,
+    feagin14, runge_kutta, types::Body, velocity_verlet,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Method {
+    Dop853,
+    Dopri5,
+    Bs32,
+    CashKarp,
+    Rk4,
+    Verlet,
+    Feagin14,
+}
+
+impl Method {
+    pub const ALL: [Method; 7] = [
+        Method::Dop853,
+        Method::Dopri5,
+        Method::Bs32,
+        Method::CashKarp,
+        Method::Rk4,
+        Method::Verlet,
+        Method::Feagin14,
+    ];
+
+    /// Name used in configs and on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            Method::Dop853 => "dop853",
+            Method::Dopri5 => "dopri5",
+            Method::Bs32 => "bs32",
+            Method::CashKarp => "cash_karp",
+            Method::Rk4 => "rk4",
+            Method::Verlet => "verlet",
+            Method::Feagin14 => "feagin14",
+        }
+    }
+
+    /// Default settings of an adaptive method, `None` for the fixed-step ones.
+    pub fn default_settings(self) -> Option<AdaptiveSettings> {
+        match self {
+            Method::Dop853 => Some(dop853::SETTINGS),
+            Method::Dopri5 => Some(dopri5::SETTINGS),
+            Method::Bs32 => Some(bogacki_shampine::SETTINGS),
+            Method::CashKarp => Some(cash_karp::SETTINGS),
+            Method::Feagin14 => Some(feagin14::SETTINGS),
+            Method::Rk4 | Method::Verlet => None,
+        }
+    }
+
+    /// Tableau of the f64 embedded pairs that step through `ErkPair`.
+    pub(crate) fn tableau(self) -> Option<&'static Tableau> {
+        match self {
+            Method::Dop853 => Some(&dop853::TABLEAU),
+            Method::Dopri5 => Some(&dopri5::TABLEAU),
+            Method::Bs32 => Some(&bogacki_shampine::TABLEAU),
+            Method::CashKarp => Some(&cash_karp::TABLEAU),
+            Method::Rk4 | Method::Verlet | Method::Feagin14 => None,
+        }
+    }
+
+    /// Evolve `bodies` to `t_end` like the method's own `evolve`.
+    /// `settings` replaces the defaults of adaptive methods and is ignored by fixed-step ones.
+    pub fn evolve(
+        self,
+        bodies: &mut [Body],
+        t_end: f64,
+        settings: Option<&AdaptiveSettings>,
+    ) -> (Vec<f64>, f64) {
+        let s = |default: AdaptiveSettings| settings.copied().unwrap_or(default);
+        match self {
+            Method::Dop853 => dop853::evolve_with(bodies, t_end, &s(dop853::SETTINGS)),
+            Method::Dopri5 => dopri5::evolve_with(bodies, t_end, &s(dopri5::SETTINGS)),
+            Method::Bs32 => {
+                bogacki_shampine::evolve_with(bodies, t_end, &s(bogacki_shampine::SETTINGS))
+            }
+            Method::CashKarp => cash_karp::evolve_with(bodies, t_end, &s(cash_karp::SETTINGS)),
+            Method::Feagin14 => feagin14::evolve_with(bodies, t_end, &s(feagin14::SETTINGS)),
+            Method::Rk4 => runge_kutta::evolve(bodies, t_end),
+            Method::Verlet => velocity_verlet::evolve(bodies, t_end),
+        }
+    }
+
+    /// States at `samples` equally spaced times after t = 0 (and at t = 0), each evolved
+    /// from the previous one with `evolve`. Stops early, with the last state reached, if
+    /// the integrator gives up.
+    pub fn sample(
+        self,
+        bodies: &[Body],
+        t_end: f64,
+        settings: Option<&AdaptiveSettings>,
+        samples: usize,
+    ) -> Vec<(f64, Vec<Body>)> {
+        let mut state = bodies.to_vec();
+        let mut out = vec![(0.0, state.clone())];
+        let mut t = 0.0;
+        for k in 1..=samples {
+            let interval = t_end * k as f64 / samples as f64 - t;
+            let (_, reached) = self.evolve(&mut state, interval, settings);
+            t += reached;
+            out.push((t, state.clone()));
+            if reached < interval * (1.0 - 1e-12) {
+                break;
+            }
+        }
+        out
+    }
+}
+
+impl std::str::FromStr for Method {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Method::ALL
+            .into_iter()
+            .find(|m| m.name() == s)
+            .ok_or_else(|| format!("Unknown method: {s}"))
+    }
+}
+
+impl std::fmt::Display for Method {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
@@ -0,0 +1,118 @@
+// Normalization of initial conditions: barycentric frame and the N-body scaling symmetry.
+//
+// With the masses fixed, r -> α r, v -> v / √α, t -> α^(3/2) t maps solutions to
+// solutions (G = 1). Energies scale as 1/α, the moment of inertia as α², angular momenta
+// as √α, and periods as α^(3/2), so E T^(2/3) and the Sundman invariant c²E do not change.
+// Catalogs normalize differently (Li & Liao use E = -1/2, others fix the period or the
+// size); rescaling to one convention makes their orbits directly comparable.
+
+use crate::{
+    types::Body,
+    utils::{centre_of_mass, moment_of_inertia, smul, sub, total_energy},
+};
+
+/// Move `bodies` to the barycentric frame: centre of mass at the origin, at rest.
+pub fn to_barycentric(bodies: &mut [Body]) {
+    let (r, v) = centre_of_mass(bodies);
+    for b in bodies.iter_mut() {
+        b.r = sub(b.r, r);
+        b.v = sub(b.v, v);
+    }
+}
+
+/// Subtract the centre-of-mass velocity, leaving positions alone (zero net momentum).
+pub fn remove_net_momentum(bodies: &mut [Body]) {
+    let (_, v) = centre_of_mass(bodies);
+    for b in bodies.iter_mut() {
+        b.v = sub(b.v, v);
+    }
+}
+
+/// What `rescale_to` fixes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScaleTarget {
+    /// Total energy (same sign as the current one).
+    Energy(f64),
+    /// Period: the orbit's current period and the one wanted.
+    Period { current: f64, target: f64 },
+    /// Moment of inertia about the centre of mass.
+    Inertia(f64),
+}
+
+/// A scaling r -> α r (masses fixed) and how other quantities transform under it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Scaling {
+    /// α.
+    pub length: f64,
+}
+
+impl Scaling {
+    /// Factor of the velocities, 1/√α.
+    pub fn velocity(&self) -> f64 {
+        1.0 / self.length.sqrt()
+    }
+
+    /// Factor of times (and periods), α^(3/2).
+    pub fn time(&self) -> f64 {
+        self.length * self.length.sqrt()
+    }
+
+    /// Factor of energies, 1/α.
+    pub fn energy(&self) -> f64 {
+        1.0 / self.length
+    }
+
+    /// The period of the rescaled orbit.
+    pub fn period(&self, period: f64) -> f64 {
+        period * self.time()
+    }
+}
+
+/// Rescale `bodies` by `scaling` about the origin.
+pub fn rescale(bodies: &mut [Body], scaling: Scaling) {
+    let v = scaling.velocity();
+    for b in bodies.iter_mut() {
+        b.r = smul(scaling.length, b.r);
+        b.v = smul(v, b.v);
+    }
+}
+
+/// Rescale `bodies` about the origin so that `target` holds, and return the scaling
+/// (apply `Scaling::period` to get the new period). Move them to the barycentric frame
+/// first if the centre of mass should stay put.
+pub fn rescale_to(bodies: &mut [Body], target: ScaleTarget) -> Result<Scaling, String> {
+    let length = match target {
+        ScaleTarget::Energy(e) => {
+            let current = total_energy(bodies);
+            if current == 0.0 || e == 0.0 || current.signum() != e.signum() {
+                return Err(format!(
+                    "cannot rescale energy {current} to {e}: scaling keeps its sign and cannot reach or leave 0"
+                ));
+            }
+            current / e
+        }
+        ScaleTarget::Period { current, target } => {
+            if !(current > 0.0 && target > 0.0) {
+                return Err(format!(
+                    "periods must be positive, got {current} and {target}"
+                ));
+            }
+            (target / current).powf(2.0 / 3.0)
+        }
+        ScaleTarget::Inertia(i) => {
+            let current = moment_of_inertia(bodies);
+            if !(current > 0.0 && i > 0.0) {
+                return Err(format!(
+                    "moments of inertia must be positive, got {current} and {i}"
+                ));
+            }
+            (i / current).sqrt()
+        }
+    };
+    if !length.is_finite() {
+        return Err(format!("scale factor is not finite ({length})"));
+    }
+    let scaling = Scaling { length };
+    rescale(bodies, scaling);
+    Ok(scaling)
+}
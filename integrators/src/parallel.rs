@@ -0,0 +1,170 @@
+// Run independent integrations on a pool of `std::thread` workers.
+//
+// Workers pull job indices from a shared counter and send (index, result) back over a
+// channel; the calling thread reports progress and puts every result in its input slot,
+// so the output order never depends on scheduling. A panicking job is caught and turned
+// into an `Err` for that job only.
+
+use std::{
+    panic::{AssertUnwindSafe, catch_unwind},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+};
+
+use crate::{adaptive::AdaptiveSettings, method::Method, types::Body, utils::total_energy};
+
+/// One initial condition to integrate from t=0 to `t_end`.
+#[derive(Clone, Debug)]
+pub struct Job {
+    pub bodies: Vec<Body>,
+    pub t_end: f64,
+}
+
+/// Integrator and pool options shared by every job of a batch.
+#[derive(Clone, Copy, Debug)]
+pub struct BatchOptions {
+    pub method: Method,
+    /// Replaces the method's default settings (adaptive methods only).
+    pub settings: Option<AdaptiveSettings>,
+    /// Worker threads; 0 uses all available cores.
+    pub threads: usize,
+}
+
+/// Output of one successful job.
+#[derive(Clone, Debug)]
+pub struct JobResult {
+    /// Flat `[x, y, z] * n, t` history, as returned by `evolve`.
+    pub series: Vec<f64>,
+    /// Final time reached.
+    pub t: f64,
+    /// State at `t`.
+    pub bodies: Vec<Body>,
+}
+
+/// Number of workers for a requested thread count (0 = all cores) and `jobs` jobs.
+pub fn worker_count(threads: usize, jobs: usize) -> usize {
+    let threads = if threads == 0 {
+        thread::available_parallelism().map_or(1, |n| n.get())
+    } else {
+        threads
+    };
+    threads.min(jobs).max(1)
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        format!("panicked: {s}")
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        format!("panicked: {s}")
+    } else {
+        "panicked".to_string()
+    }
+}
+
+/// Apply `f` to every item on `threads` workers (0 = all cores).
+///
+/// Results come back in input order. `progress(done, total)` is called on the calling
+/// thread after each job finishes. With a single worker everything runs on the calling
+/// thread and no thread is spawned.
+pub fn par_map<T, R, F>(
+    items: &[T],
+    threads: usize,
+    f: F,
+    mut progress: impl FnMut(usize, usize),
+) -> Vec<Result<R, String>>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> Result<R, String> + Sync,
+{
+    let total = items.len();
+    let run = |item: &T| {
+        catch_unwind(AssertUnwindSafe(|| f(item))).unwrap_or_else(|p| Err(panic_message(p)))
+    };
+
+    let workers = worker_count(threads, total);
+    if workers == 1 {
+        return items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let r = run(item);
+                progress(i + 1, total);
+                r
+            })
+            .collect();
+    }
+
+    let mut results: Vec<Option<Result<R, String>>> = (0..total).map(|_| None).collect();
+    let next = AtomicUsize::new(0);
+    thread::scope(|scope| {
+        let (tx, rx) = mpsc::channel();
+        for _ in 0..workers {
+            let tx = tx.clone();
+            let (next, run) = (&next, &run);
+            scope.spawn(move || {
+                loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    if i >= total {
+                        break;
+                    }
+                    if tx.send((i, run(&items[i]))).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(tx);
+        for (done, (i, r)) in rx.iter().enumerate() {
+            results[i] = Some(r);
+            progress(done + 1, total);
+        }
+    });
+    results
+        .into_iter()
+        .map(|r| r.expect("every job reports back"))
+        .collect()
+}
+
+/// Integrate one job, rejecting inputs and results that are not finite.
+pub fn run_job(
+    job: &Job,
+    method: Method,
+    settings: Option<&AdaptiveSettings>,
+) -> Result<JobResult, String> {
+    if !job.t_end.is_finite() {
+        return Err(format!("t_end must be finite, got {}", job.t_end));
+    }
+    let finite = |bodies: &[Body]| {
+        bodies
+            .iter()
+            .all(|b| b.m.is_finite() && b.r.iter().chain(&b.v).all(|x| x.is_finite()))
+    };
+    if !finite(&job.bodies) {
+        return Err("initial conditions are not finite".to_string());
+    }
+    let mut bodies = job.bodies.clone();
+    let (series, t) = method.evolve(&mut bodies, job.t_end, settings);
+    if !finite(&bodies) || !total_energy(&bodies).is_finite() {
+        return Err(format!("state became non-finite (reached t = {t})"));
+    }
+    Ok(JobResult { series, t, bodies })
+}
+
+/// Integrate every job with the same method on a thread pool.
+/// Results are in job order; `progress(done, total)` runs on the calling thread.
+pub fn run_batch(
+    jobs: &[Job],
+    options: &BatchOptions,
+    progress: impl FnMut(usize, usize),
+) -> Vec<Result<JobResult, String>> {
+    par_map(
+        jobs,
+        options.threads,
+        |job| run_job(job, options.method, options.settings.as_ref()),
+        progress,
+    )
+}
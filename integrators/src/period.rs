@@ -0,0 +1,174 @@
+// Period estimation from a single trajectory.
+//
+// The initial conditions are moved to the centre-of-mass frame and integrated up to
+// `t_max`. Candidate periods are the local minima (for t >= `t_min`) of the phase-space
+// distance to the initial state,
+//
+//     d(t)² = min_θ |R(-θ) y(t) - y0|²,
+//
+// where R(θ) rotates every position and velocity about the z axis. For a relative
+// periodic orbit, y(T) = R(θ) y0, the minimizing θ is the rotation angle over a period
+// (the `theta_max` of the viewer's examples). Writing A = Σ (x x0 + y y0) and
+// B = Σ (y x0 - x y0) over the x-y components, the minimum is at θ = atan2(B, A)
+// (without rotation, θ = 0 and d² = |y - y0|²). Minima are bracketed by the sign change
+// of d/dt d² between accepted steps and then located by integrating from the start of
+// the step, so the closure error is not limited by interpolation. A periodic orbit
+// returns at every multiple of its period too, so minima that close about as well as the
+// best one are ranked by period.
+
+use crate::{
+    adaptive::{AdaptiveSettings, drive},
+    erk::{self, ErkPair},
+    method::Method,
+    search::locate_minimum,
+    shooting::{bodies_from, to_centre_of_mass},
+    types::Body,
+};
+
+// Minima within this factor of the best closure count as equally good
+const SAME_CLOSURE: f64 = 10.0;
+
+/// Integrator and time window of a period estimate.
+#[derive(Clone, Copy, Debug)]
+pub struct PeriodOptions {
+    /// One of the f64 embedded pairs (dop853, dopri5, bs32, cash_karp).
+    pub method: Method,
+    /// Replaces the method's default settings.
+    pub settings: Option<AdaptiveSettings>,
+    /// Returns before this time are ignored (the orbit has to leave its start first).
+    pub t_min: f64,
+    pub t_max: f64,
+    /// Allow a return up to a rotation about the z axis.
+    pub rotation: bool,
+    /// Number of candidates to keep.
+    pub count: usize,
+}
+
+impl Default for PeriodOptions {
+    fn default() -> Self {
+        PeriodOptions {
+            method: Method::Dop853,
+            settings: None,
+            t_min: 0.5,
+            t_max: 50.0,
+            rotation: false,
+            count: 10,
+        }
+    }
+}
+
+/// A local minimum of the return distance.
+#[derive(Clone, Copy, Debug)]
+pub struct PeriodEstimate {
+    pub period: f64,
+    /// Rotation angle over the period, in (-π, π] (0 without rotation).
+    pub theta: f64,
+    /// Closure error: phase-space distance of the rotated return to the initial state.
+    pub distance: f64,
+}
+
+/// d², its time derivative and the minimizing angle at state `y` with derivative `f`.
+fn proximity(y: &[f64], f: &[f64], y0: &[f64], rotation: bool) -> (f64, f64, f64) {
+    let theta = if rotation {
+        let (mut a, mut b) = (0.0, 0.0);
+        for k in (0..y.len()).step_by(3) {
+            a += y[k] * y0[k] + y[k + 1] * y0[k + 1];
+            b += y[k + 1] * y0[k] - y[k] * y0[k + 1];
+        }
+        if a == 0.0 && b == 0.0 {
+            0.0
+        } else {
+            b.atan2(a)
+        }
+    } else {
+        0.0
+    };
+    // d² at the optimal θ; since ∂d²/∂θ = 0 there, d/dt d² = 2 (R(-θ) y - y0) · R(-θ) f
+    let (s, c) = (-theta).sin_cos();
+    let (mut g, mut dg) = (0.0, 0.0);
+    for k in (0..y.len()).step_by(3) {
+        let e = [
+            c * y[k] - s * y[k + 1] - y0[k],
+            s * y[k] + c * y[k + 1] - y0[k + 1],
+            y[k + 2] - y0[k + 2],
+        ];
+        let rf = [c * f[k] - s * f[k + 1], s * f[k] + c * f[k + 1], f[k + 2]];
+        for i in 0..3 {
+            g += e[i] * e[i];
+            dg += 2.0 * e[i] * rf[i];
+        }
+    }
+    (g, dg, theta)
+}
+
+/// Candidate periods of `bodies`: the local minima of the return distance for t in
+/// [t_min, t_max] (at most `options.count`), by increasing distance except that minima
+/// within a factor 10 of the best one come first by increasing period, so a multiple of
+/// the period does not win on integration noise. If the integration stops early, the
+/// minima found up to then are returned.
+pub fn estimate_periods(
+    bodies: &[Body],
+    options: &PeriodOptions,
+) -> Result<Vec<PeriodEstimate>, String> {
+    let tableau = options.method.tableau().ok_or_else(|| {
+        format!(
+            "Period estimation needs an f64 embedded pair (dop853, dopri5, bs32, cash_karp), not {}",
+            options.method
+        )
+    })?;
+    let settings = options
+        .settings
+        .or(options.method.default_settings())
+        .expect("embedded pairs have default settings");
+
+    // Centre-of-mass frame
+    let masses: Vec<f64> = bodies.iter().map(|b| b.m).collect();
+    let mut y0 = erk::pack_state(bodies);
+    to_centre_of_mass(&mut y0, &masses);
+    let start = bodies_from(&y0, &masses);
+
+    let measure = |y: &[f64]| proximity(y, &erk::deriv(y, &masses, 0.0), &y0, options.rotation);
+
+    // Steps over which d² turns around: (t0, y(t0), h, d²' at t0, d²' at t0 + h)
+    let mut brackets = Vec::new();
+    let mut prev = (0.0, y0.clone(), measure(&y0).1);
+    let mut pair = ErkPair::new(tableau, &start, 0.0);
+    let t_end = drive(&mut pair, options.t_max, &settings, |p, t, h| {
+        let y = p.state();
+        let dg = measure(y).1;
+        let (t0, y_t0, dg0) = std::mem::replace(&mut prev, (t, y.to_vec(), dg));
+        if dg0 < 0.0 && dg >= 0.0 && t >= options.t_min {
+            brackets.push((t0, y_t0, h, dg0, dg));
+        }
+    });
+
+    // Locate each minimum, integrating from the start of its step
+    let mut minima = Vec::new();
+    for (t0, y_t0, h, dg0, dg1) in brackets {
+        let start = bodies_from(&y_t0, &masses);
+        let (tau, y) = locate_minimum(tableau, &settings, &start, t0, (h, dg0, dg1), |y| {
+            measure(y).1
+        });
+        let (g, _, theta) = measure(&y);
+        if t0 + tau >= options.t_min {
+            minima.push(PeriodEstimate {
+                period: t0 + tau,
+                theta,
+                distance: g.sqrt(),
+            });
+        }
+    }
+    if minima.is_empty() {
+        return Err(format!(
+            "no return after t_min (integration stopped at t = {t_end})"
+        ));
+    }
+
+    // Returns within SAME_CLOSURE of the best are equally good: shortest period first
+    minima.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+    let close = minima[0].distance * SAME_CLOSURE;
+    let equal = minima.partition_point(|m| m.distance <= close);
+    minima[..equal].sort_by(|a, b| a.period.total_cmp(&b.period));
+    minima.truncate(options.count);
+    Ok(minima)
+}
@@ -2,59 +2,66 @@
 
 use crate::{
     types::Body,
-    utils::{accelerations, add, smul},
+    utils::{accelerations_into, add, smul},
 };
 
-/// Advance the system by one RK4 step of size `dt`.
-fn step_rk4(bodies: &mut [Body], dt: f64, eps2: f64) {
-    let n = bodies.len();
-
-    // Stage 1 (k1)
-    let r0: Vec<[f64; 3]> = bodies.iter().map(|b| b.r).collect();
-    let v0: Vec<[f64; 3]> = bodies.iter().map(|b| b.v).collect();
-    let m: Vec<f64> = bodies.iter().map(|b| b.m).collect();
-    let a0 = accelerations(&r0, &m, eps2);
+/// Scratch buffers for `step_rk4`, allocated once per run.
+struct Workspace {
+    m: Vec<f64>,
+    // stage position, velocity and acceleration
+    r: Vec<[f64; 3]>,
+    v: Vec<[f64; 3]>,
+    a: Vec<[f64; 3]>,
+    // weighted sums of the stage derivatives
+    dr: Vec<[f64; 3]>,
+    dv: Vec<[f64; 3]>,
+}
 
-    // Stage 2 (k2) @ t + dt/2
-    let r_half: Vec<[f64; 3]> = (0..n).map(|i| add(r0[i], smul(0.5 * dt, v0[i]))).collect();
-    let v_half1: Vec<[f64; 3]> = (0..n).map(|i| add(v0[i], smul(0.5 * dt, a0[i]))).collect();
-    let a_half1 = accelerations(&r_half, &m, eps2);
+impl Workspace {
+    fn new(bodies: &[Body]) -> Self {
+        let n = bodies.len();
+        Workspace {
+            m: bodies.iter().map(|b| b.m).collect(),
+            r: vec![[0.0; 3]; n],
+            v: vec![[0.0; 3]; n],
+            a: vec![[0.0; 3]; n],
+            dr: vec![[0.0; 3]; n],
+            dv: vec![[0.0; 3]; n],
+        }
+    }
+}
 
-    // Stage 3 (k3) @ t + dt/2
-    let r_half2: Vec<[f64; 3]> = (0..n)
-        .map(|i| add(r0[i], smul(0.5 * dt, v_half1[i])))
-        .collect();
-    let v_half2: Vec<[f64; 3]> = (0..n)
-        .map(|i| add(v0[i], smul(0.5 * dt, a_half1[i])))
-        .collect();
-    let a_half2 = accelerations(&r_half2, &m, eps2);
+/// Advance the system by one RK4 step of size `dt`.
+fn step_rk4(bodies: &mut [Body], dt: f64, eps2: f64, ws: &mut Workspace) {
+    // Stage 1 (k1)
+    for (i, b) in bodies.iter().enumerate() {
+        ws.r[i] = b.r;
+        ws.v[i] = b.v;
+    }
+    accelerations_into(&ws.r, &ws.m, eps2, &mut ws.a);
+    ws.dr.copy_from_slice(&ws.v);
+    ws.dv.copy_from_slice(&ws.a);
 
-    // Stage 4 (k4) @ t + dt
-    let r_end: Vec<[f64; 3]> = (0..n).map(|i| add(r0[i], smul(dt, v_half2[i]))).collect();
-    let v_end: Vec<[f64; 3]> = (0..n).map(|i| add(v0[i], smul(dt, a_half2[i]))).collect();
-    let a_end = accelerations(&r_end, &m, eps2);
+    // Stages 2 and 3 (k2, k3) @ t + dt/2, stage 4 (k4) @ t + dt
+    for (c, w) in [(0.5 * dt, 2.0), (0.5 * dt, 2.0), (dt, 1.0)] {
+        for (i, b) in bodies.iter().enumerate() {
+            // r uses the previous stage velocity, so update it before v
+            ws.r[i] = add(b.r, smul(c, ws.v[i]));
+            ws.v[i] = add(b.v, smul(c, ws.a[i]));
+        }
+        accelerations_into(&ws.r, &ws.m, eps2, &mut ws.a);
+        for i in 0..bodies.len() {
+            ws.dr[i] = add(ws.dr[i], smul(w, ws.v[i]));
+            ws.dv[i] = add(ws.dv[i], smul(w, ws.a[i]));
+        }
+    }
 
     // Combine increments
-    for i in 0..n {
-        // r_{n+1} = r_n + dt/6 * (v0 + 2*v_half1 + 2*v_half2 + v_end)
-        let dr = smul(
-            dt / 6.0,
-            add(
-                add(v0[i], smul(2.0, v_half1[i])),
-                add(smul(2.0, v_half2[i]), v_end[i]),
-            ),
-        );
-        // v_{n+1} = v_n + dt/6 * (a0 + 2*a_half1 + 2*a_half2 + a_end)
-        let dv = smul(
-            dt / 6.0,
-            add(
-                add(a0[i], smul(2.0, a_half1[i])),
-                add(smul(2.0, a_half2[i]), a_end[i]),
-            ),
-        );
-
-        bodies[i].r = add(bodies[i].r, dr);
-        bodies[i].v = add(bodies[i].v, dv);
+    // r_{n+1} = r_n + dt/6 * (v0 + 2*v_half1 + 2*v_half2 + v_end)
+    // v_{n+1} = v_n + dt/6 * (a0 + 2*a_half1 + 2*a_half2 + a_end)
+    for (i, b) in bodies.iter_mut().enumerate() {
+        b.r = add(b.r, smul(dt / 6.0, ws.dr[i]));
+        b.v = add(b.v, smul(dt / 6.0, ws.dv[i]));
     }
 }
 
@@ -64,15 +71,16 @@ pub fn evolve(bodies: &mut [Body], t_end: f64) -> (Vec<f64>, f64) {
     let dt = 1e-5;
     // softening^2; set 0.0 to disable
     let eps2 = 0.0; // 1e-8;
+    let mut ws = Workspace::new(bodies);
     let mut t = 0.0;
-    let mut result = Vec::new();
+    let mut result = Vec::with_capacity(((t_end / dt).ceil() as usize + 1) * 10);
     while t < t_end {
         let h = if t + dt > t_end { t_end - t } else { dt };
-        result.append(&mut bodies[0].r.to_vec());
-        result.append(&mut bodies[1].r.to_vec());
-        result.append(&mut bodies[2].r.to_vec());
+        result.extend_from_slice(&bodies[0].r);
+        result.extend_from_slice(&bodies[1].r);
+        result.extend_from_slice(&bodies[2].r);
         result.push(t);
-        step_rk4(bodies, h, eps2);
+        step_rk4(bodies, h, eps2, &mut ws);
         t += h;
     }
     (result, t)
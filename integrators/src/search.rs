@@ -0,0 +1,300 @@
+// Grid search for periodic-orbit candidates (Šuvakov & Dmitrašinović scan the (v1, v2)
+// plane the same way).
+//
+// Every point of a 2-D parameter grid is mapped to initial conditions by a user family and
+// integrated up to `t_max`. Along the way we track the return proximity in phase space,
+//
+//     d(t)² = Σ_i |r_i(t) - r_i(0)|² + |v_i(t) - v_i(0)|²,
+//
+// and keep its smallest value for t >= `t_min` together with the time it occurs. Minima
+// between accepted steps are bracketed by the sign change of d/dt d² = 2 (y - y0) · f(y).
+// When the cubic Hermite interpolant of d² over the step says one may beat the best so far,
+// it is located by integrating from the start of the step (the interpolant itself is too
+// rough near a close return and can even go negative). Candidates are the local minima of
+// d over the grid, ranked by d; their return time is the initial guess for the period when
+// refining them.
+
+use crate::{
+    adaptive::{AdaptiveSettings, drive},
+    erk::{self, ErkPair, Tableau},
+    method::Method,
+    parallel::par_map,
+    shooting,
+    types::Body,
+};
+
+// Points of the Hermite interpolant examined per bracketing step
+const HERMITE_SAMPLES: usize = 16;
+// Regula falsi steps when locating a minimum inside a step
+const REFINE_ITERATIONS: usize = 60;
+
+/// `steps` equally spaced values from `min` to `max` (both included).
+#[derive(Clone, Copy, Debug)]
+pub struct Axis {
+    pub min: f64,
+    pub max: f64,
+    pub steps: usize,
+}
+
+impl Axis {
+    pub fn value(&self, i: usize) -> f64 {
+        if self.steps <= 1 {
+            self.min
+        } else {
+            self.min + (self.max - self.min) * i as f64 / (self.steps - 1) as f64
+        }
+    }
+}
+
+/// Integrator, time window and pool options of a grid search.
+#[derive(Clone, Copy, Debug)]
+pub struct SearchOptions {
+    /// One of the f64 embedded pairs (dop853, dopri5, bs32, cash_karp).
+    pub method: Method,
+    /// Replaces the method's default settings.
+    pub settings: Option<AdaptiveSettings>,
+    /// Returns before this time are ignored (the orbit has to leave its start first).
+    pub t_min: f64,
+    pub t_max: f64,
+    /// Worker threads; 0 uses all available cores.
+    pub threads: usize,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        SearchOptions {
+            method: Method::Dop853,
+            settings: None,
+            t_min: 1.0,
+            t_max: 30.0,
+            threads: 0,
+        }
+    }
+}
+
+/// Closest return of one grid point.
+#[derive(Clone, Debug)]
+pub struct NearReturn {
+    /// Grid indices along x and y.
+    pub index: [usize; 2],
+    /// Family parameters (x, y).
+    pub params: [f64; 2],
+    /// Initial conditions from the family.
+    pub bodies: Vec<Body>,
+    /// Smallest phase-space return distance for t in [t_min, t_max].
+    pub distance: f64,
+    /// Time of that return; the period guess for refinement.
+    pub time: f64,
+}
+
+/// Result of `scan_grid`: one entry per grid point, x varying fastest.
+#[derive(Clone, Debug)]
+pub struct Scan {
+    pub x: Axis,
+    pub y: Axis,
+    pub points: Vec<Result<NearReturn, String>>,
+}
+
+impl Scan {
+    /// Up to `count` grid points whose distance is a local minimum over their (up to 8)
+    /// neighbours, by increasing distance.
+    pub fn candidates(&self, count: usize) -> Vec<NearReturn> {
+        let (nx, ny) = (self.x.steps, self.y.steps);
+        let distance = |i: usize, j: usize| match &self.points[j * nx + i] {
+            Ok(p) => p.distance,
+            Err(_) => f64::INFINITY,
+        };
+        let mut minima: Vec<NearReturn> = self
+            .points
+            .iter()
+            .filter_map(|p| p.as_ref().ok())
+            .filter(|p| {
+                let [i, j] = p.index;
+                p.distance.is_finite()
+                    && (j.saturating_sub(1)..(j + 2).min(ny)).all(|jj| {
+                        (i.saturating_sub(1)..(i + 2).min(nx))
+                            .all(|ii| (ii, jj) == (i, j) || distance(ii, jj) >= p.distance)
+                    })
+            })
+            .cloned()
+            .collect();
+        minima.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        minima.truncate(count);
+        minima
+    }
+}
+
+/// Locate the minimum of d² inside the step of length `h` from `start` (at time `t0`),
+/// over which its time derivative goes from `dg0` < 0 to `dg1` >= 0: regula falsi
+/// (Illinois) on `slope(y)` = d/dt d², integrating from `start` for every evaluation.
+/// Returns the offset of the minimum from `t0` and the state there.
+pub(crate) fn locate_minimum(
+    tableau: &'static Tableau,
+    settings: &AdaptiveSettings,
+    start: &[Body],
+    t0: f64,
+    (h, dg0, dg1): (f64, f64, f64),
+    slope: impl Fn(&[f64]) -> f64,
+) -> (f64, Vec<f64>) {
+    let state_at = |tau: f64| {
+        let mut pair = ErkPair::new(tableau, start, 0.0);
+        drive(&mut pair, tau, settings, |_, _, _| {});
+        pair.state().to_vec()
+    };
+    let (mut a, mut fa, mut b, mut fb) = (0.0, dg0, h, dg1);
+    let mut y = state_at(b);
+    let mut side = 0;
+    for _ in 0..REFINE_ITERATIONS {
+        if (b - a).abs() <= 4.0 * f64::EPSILON * (t0 + h) {
+            break;
+        }
+        let c = (a * fb - b * fa) / (fb - fa);
+        y = state_at(c);
+        let fc = slope(&y);
+        if fc == 0.0 {
+            (a, b) = (c, c);
+            break;
+        }
+        if fc < 0.0 {
+            (a, fa) = (c, fc);
+            if side == -1 {
+                fb *= 0.5;
+            }
+            side = -1;
+        } else {
+            (b, fb) = (c, fc);
+            if side == 1 {
+                fa *= 0.5;
+            }
+            side = 1;
+        }
+    }
+    let tau = 0.5 * (a + b);
+    if tau != b {
+        y = state_at(tau);
+    }
+    (tau, y)
+}
+
+/// Closest return in phase space of `bodies` for t in [t_min, t_max].
+fn closest_return(
+    bodies: &[Body],
+    tableau: &'static Tableau,
+    settings: &AdaptiveSettings,
+    t_min: f64,
+    t_max: f64,
+) -> Result<(f64, f64), String> {
+    let masses: Vec<f64> = bodies.iter().map(|b| b.m).collect();
+    let y0 = erk::pack_state(bodies);
+    // d² and its time derivative at the current state
+    let proximity = |y: &[f64]| {
+        let f = erk::deriv(y, &masses, 0.0);
+        let mut g = 0.0;
+        let mut dg = 0.0;
+        for ((yi, y0i), fi) in y.iter().zip(&y0).zip(&f) {
+            g += (yi - y0i) * (yi - y0i);
+            dg += 2.0 * (yi - y0i) * fi;
+        }
+        (g, dg)
+    };
+
+    let mut best = (f64::INFINITY, f64::NAN);
+    let (g, dg) = proximity(&y0);
+    let mut prev = (0.0, y0.clone(), g, dg);
+    let mut pair = ErkPair::new(tableau, bodies, 0.0);
+    let t_end = drive(&mut pair, t_max, settings, |p, t, h| {
+        let y = p.state();
+        let (g, dg) = proximity(y);
+        let (t0, y_t0, g0, dg0) = std::mem::replace(&mut prev, (t, y.to_vec(), g, dg));
+        if t >= t_min && g < best.0 {
+            best = (g, t);
+        }
+        if dg0 < 0.0 && dg >= 0.0 && t >= t_min {
+            // d² turns around inside the step: if its cubic Hermite interpolant dips below
+            // the best so far, locate the minimum
+            let promising = (1..HERMITE_SAMPLES).any(|k| {
+                let s = k as f64 / HERMITE_SAMPLES as f64;
+                let (s2, s3) = (s * s, s * s * s);
+                let gs = (2.0 * s3 - 3.0 * s2 + 1.0) * g0
+                    + (s3 - 2.0 * s2 + s) * h * dg0
+                    + (-2.0 * s3 + 3.0 * s2) * g
+                    + (s3 - s2) * h * dg;
+                gs < best.0
+            });
+            if promising {
+                let start = shooting::bodies_from(&y_t0, &masses);
+                let (tau, y) = locate_minimum(tableau, settings, &start, t0, (h, dg0, dg), |y| {
+                    proximity(y).1
+                });
+                let (gm, _) = proximity(&y);
+                if t0 + tau >= t_min && gm < best.0 {
+                    best = (gm, t0 + tau);
+                }
+            }
+        }
+    });
+    if !best.0.is_finite() {
+        return Err(format!(
+            "no return after t_min (integration stopped at t = {t_end})"
+        ));
+    }
+    Ok((best.0.sqrt(), best.1))
+}
+
+/// Integrate `family(x, y)` for every point of the grid `x` × `y` on a thread pool and
+/// record its closest return. `progress(done, total)` runs on the calling thread.
+/// Fails only if `options.method` is not one of the f64 embedded pairs.
+pub fn scan_grid<F>(
+    family: F,
+    x: &Axis,
+    y: &Axis,
+    options: &SearchOptions,
+    progress: impl FnMut(usize, usize),
+) -> Result<Scan, String>
+where
+    F: Fn(f64, f64) -> Vec<Body> + Sync,
+{
+    let tableau = options.method.tableau().ok_or_else(|| {
+        format!(
+            "Grid search needs an f64 embedded pair (dop853, dopri5, bs32, cash_karp), not {}",
+            options.method
+        )
+    })?;
+    let settings = options
+        .settings
+        .or(options.method.default_settings())
+        .expect("embedded pairs have default settings");
+    let grid: Vec<[usize; 2]> = (0..y.steps)
+        .flat_map(|j| (0..x.steps).map(move |i| [i, j]))
+        .collect();
+
+    let points = par_map(
+        &grid,
+        options.threads,
+        |&[i, j]| {
+            let params = [x.value(i), y.value(j)];
+            let bodies = family(params[0], params[1]);
+            if bodies
+                .iter()
+                .any(|b| !b.m.is_finite() || b.r.iter().chain(&b.v).any(|v| !v.is_finite()))
+            {
+                return Err("initial conditions are not finite".to_string());
+            }
+            let (distance, time) =
+                closest_return(&bodies, tableau, &settings, options.t_min, options.t_max)?;
+            Ok(NearReturn {
+                index: [i, j],
+                params,
+                bodies,
+                distance,
+                time,
+            })
+        },
+        progress,
+    );
+    Ok(Scan {
+        x: *x,
+        y: *y,
+        points,
+    })
+}
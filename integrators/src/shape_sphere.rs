@@ -0,0 +1,90 @@
+// Montgomery's shape sphere: planar three-body configurations modulo translation,
+// rotation and scale.
+//
+// With mass-weighted Jacobi vectors
+//
+//     ρ = √μ₁ (r₂ - r₁),            μ₁ = m₁m₂ / (m₁ + m₂)
+//     λ = √μ₂ (r₃ - c₁₂),           μ₂ = m₃(m₁ + m₂) / M,  c₁₂ = (m₁r₁ + m₂r₂) / (m₁ + m₂)
+//
+// the moment of inertia about the centre of mass is I = |ρ|² + |λ|², and the shape is the
+// unit vector
+//
+//     n = (|ρ|² - |λ|², 2 ρ·λ, 2 (ρ × λ)_z) / I.
+//
+// The equator n₃ = 0 holds the collinear configurations (syzygies), among them the three
+// binary collision points; the two Lagrange (equilateral) points are the images of the
+// two orientations of the equilateral triangle. For equal masses these are the poles.
+// Only the x-y components of the positions are used.
+
+/// Mass-weighted Jacobi vectors (ρ, λ) of three bodies.
+pub fn jacobi_vectors(positions: &[[f64; 3]; 3], masses: &[f64; 3]) -> [[f64; 3]; 2] {
+    let [m1, m2, m3] = *masses;
+    let m12 = m1 + m2;
+    let mu1 = m1 * m2 / m12;
+    let mu2 = m3 * m12 / (m12 + m3);
+    let [r1, r2, r3] = positions;
+    let mut rho = [0.0; 3];
+    let mut lambda = [0.0; 3];
+    for k in 0..3 {
+        rho[k] = mu1.sqrt() * (r2[k] - r1[k]);
+        lambda[k] = mu2.sqrt() * (r3[k] - (m1 * r1[k] + m2 * r2[k]) / m12);
+    }
+    [rho, lambda]
+}
+
+/// Point of the shape sphere of a planar configuration; `None` at triple collision.
+pub fn shape_point(positions: &[[f64; 3]; 3], masses: &[f64; 3]) -> Option<[f64; 3]> {
+    let [rho, lambda] = jacobi_vectors(positions, masses);
+    let rr = rho[0] * rho[0] + rho[1] * rho[1];
+    let ll = lambda[0] * lambda[0] + lambda[1] * lambda[1];
+    let inertia = rr + ll;
+    if inertia == 0.0 {
+        return None;
+    }
+    Some([
+        (rr - ll) / inertia,
+        2.0 * (rho[0] * lambda[0] + rho[1] * lambda[1]) / inertia,
+        2.0 * (rho[0] * lambda[1] - rho[1] * lambda[0]) / inertia,
+    ])
+}
+
+/// Shape-sphere curve of a trajectory given as the flat `[x, y, z] * 3, t` history
+/// returned by the `evolve` functions. The result is flat `[n1, n2, n3, t]` per sample;
+/// samples at triple collision are skipped.
+pub fn shape_sphere(series: &[f64], masses: &[f64; 3]) -> Vec<f64> {
+    let mut out = Vec::with_capacity(series.len() / 10 * 4);
+    for s in series.chunks_exact(10) {
+        let positions = [[s[0], s[1], s[2]], [s[3], s[4], s[5]], [s[6], s[7], s[8]]];
+        if let Some(n) = shape_point(&positions, masses) {
+            out.extend_from_slice(&n);
+            out.push(s[9]);
+        }
+    }
+    out
+}
+
+/// The two Lagrange points: equilateral triangles with bodies 1, 2, 3 counter-clockwise
+/// (n₃ > 0) and clockwise (n₃ < 0).
+pub fn lagrange_points(masses: &[f64; 3]) -> [[f64; 3]; 2] {
+    let h = 3f64.sqrt() / 2.0;
+    [1.0, -1.0].map(|orientation| {
+        let positions = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.5, orientation * h, 0.0],
+        ];
+        shape_point(&positions, masses).expect("equilateral triangle has positive inertia")
+    })
+}
+
+/// The binary collision points, in the order r₁ = r₂, r₂ = r₃, r₃ = r₁.
+pub fn collision_points(masses: &[f64; 3]) -> [[f64; 3]; 3] {
+    [
+        [[0.0; 3], [0.0; 3], [1.0, 0.0, 0.0]],
+        [[1.0, 0.0, 0.0], [0.0; 3], [0.0; 3]],
+        [[0.0; 3], [1.0, 0.0, 0.0], [0.0; 3]],
+    ]
+    .map(|positions| {
+        shape_point(&positions, masses).expect("binary collision has positive inertia")
+    })
+}
@@ -0,0 +1,565 @@
+// Newton shooting for periodic (and relative periodic) orbits.
+//
+// Unknowns: the initial state y0 (6N), the period T and, for orbits that close up to a
+// rotation about z, the angle θ. Residual of the return map:
+//
+//     F(y0, T, θ) = R_z(-θ) y(T; y0) - y0
+//
+// with Jacobian [R_z(-θ) Φ(T) - I | R_z(-θ) f(y(T)) | -ẑ × R_z(-θ) y(T)]. The problem is
+// degenerate: every point of the orbit (time shift), every rotated or translated copy and,
+// with T free, the scaled family along the energy are solutions too. Phase conditions pin
+// these down — δy0 ⟂ f(y0), δy0 ⟂ the rotation generators, fixed centre of mass, zero total
+// momentum and (optionally) fixed energy — and are appended to the Newton system, which is
+// solved in the least-squares sense with Levenberg–Marquardt damping.
+//
+// The residual is integrated in f64 with any method, or in BigDecimal with Feagin14 for
+// high precision; in both cases the Jacobian comes from the DOP853 variational equations,
+// which is plenty for a (quasi-)Newton iteration.
+
+use bigdecimal::BigDecimal as BD;
+use bigdecimal::num_traits::{FromPrimitive, One, ToPrimitive, Zero};
+
+use crate::{
+    adaptive::AdaptiveSettings,
+    dop853, erk,
+    feagin14::{self, BD_SCALE, flow_bd},
+    linalg,
+    method::Method,
+    types::Body,
+};
+
+/// Tolerances for the DOP853 variational integration that provides the Jacobian.
+pub(crate) const JACOBIAN_SETTINGS: AdaptiveSettings = AdaptiveSettings {
+    rtol: 1e-12,
+    atol: 1e-14,
+    ..dop853::SETTINGS
+};
+
+// Levenberg–Marquardt damping (relative to the largest column norm)
+const LAMBDA_START: f64 = 1e-6;
+const LAMBDA_MIN: f64 = 1e-12;
+const LAMBDA_MAX: f64 = 1e6;
+
+/// Options of the periodic-orbit corrector.
+#[derive(Clone, Copy, Debug)]
+pub struct ShootingOptions {
+    /// Integrator for the residual in f64 (ignored when `high_precision` is set).
+    pub method: Method,
+    /// Settings of the residual integrator; `None` uses the method's defaults.
+    pub settings: Option<AdaptiveSettings>,
+    /// Integrate the residual with Feagin14 in BigDecimal and keep y0, T and θ in
+    /// BigDecimal, so the residual can go well below f64 round-off.
+    pub high_precision: bool,
+    /// Also solve for a rotation angle θ about z (relative periodic orbits).
+    pub rotation: bool,
+    /// Keep the energy of the initial guess (otherwise it may drift along the family).
+    pub fix_energy: bool,
+    pub max_iterations: usize,
+    /// Stop once the closure residual |F| is below this.
+    pub tol: f64,
+}
+
+impl Default for ShootingOptions {
+    fn default() -> Self {
+        ShootingOptions {
+            method: Method::Dop853,
+            settings: Some(AdaptiveSettings {
+                rtol: 1e-13,
+                atol: 1e-15,
+                ..dop853::SETTINGS
+            }),
+            high_precision: false,
+            rotation: false,
+            fix_energy: true,
+            max_iterations: 20,
+            tol: 1e-11,
+        }
+    }
+}
+
+impl ShootingOptions {
+    /// Feagin14 in BigDecimal with tolerances far below f64 round-off.
+    pub fn high_precision() -> Self {
+        ShootingOptions {
+            method: Method::Feagin14,
+            settings: Some(AdaptiveSettings {
+                rtol: 1e-30,
+                atol: 1e-30,
+                ..feagin14::SETTINGS
+            }),
+            high_precision: true,
+            tol: 1e-25,
+            ..Self::default()
+        }
+    }
+}
+
+/// Result of a correction.
+#[derive(Clone, Debug)]
+pub struct Refined {
+    /// Corrected initial state, packed as [r0, v0, r1, v1, ...].
+    pub state: Vec<BD>,
+    pub period: BD,
+    /// Rotation about z that closes the orbit (0 unless `rotation` was requested).
+    pub theta: BD,
+    /// `state` rounded to f64.
+    pub bodies: Vec<Body>,
+    pub residual_before: f64,
+    pub residual_after: f64,
+    /// Accepted Newton steps.
+    pub iterations: usize,
+    pub converged: bool,
+    /// |F| after every accepted step, starting with the initial guess.
+    pub history: Vec<f64>,
+}
+
+/// Unknowns of the shooting problem.
+#[derive(Clone)]
+struct Point {
+    y: Vec<BD>,
+    t: BD,
+    theta: BD,
+}
+
+impl Point {
+    fn y_f64(&self) -> Vec<f64> {
+        self.y.iter().map(|x| x.to_f64().unwrap()).collect()
+    }
+
+    /// Point + δ, with δ = [δy0, δT, (δθ)]. Rounded to f64 values unless `exact`.
+    fn apply(&self, delta: &[f64], exact: bool) -> Point {
+        let dim = self.y.len();
+        let add = |x: &BD, d: f64| {
+            let s = (x + BD::from_f64(d).unwrap()).with_scale(BD_SCALE);
+            if exact { s } else { round(&s) }
+        };
+        Point {
+            y: self.y.iter().zip(delta).map(|(x, &d)| add(x, d)).collect(),
+            t: add(&self.t, delta[dim]),
+            theta: match delta.get(dim + 1) {
+                Some(&d) => add(&self.theta, d),
+                None => self.theta.clone(),
+            },
+        }
+    }
+}
+
+fn round(x: &BD) -> BD {
+    BD::from_f64(x.to_f64().unwrap()).unwrap()
+}
+
+pub(crate) fn bodies_from(y: &[f64], masses: &[f64]) -> Vec<Body> {
+    let mut bodies: Vec<Body> = masses
+        .iter()
+        .map(|&m| Body {
+            m,
+            r: [0.0; 3],
+            v: [0.0; 3],
+        })
+        .collect();
+    erk::unpack_state(y, &mut bodies);
+    bodies
+}
+
+/// Move the packed state `y` to its centre-of-mass frame (positions and velocities).
+pub(crate) fn to_centre_of_mass(y: &mut [f64], masses: &[f64]) {
+    let n = masses.len();
+    let total: f64 = masses.iter().sum();
+    for k in 0..6 {
+        let mean = (0..n).map(|i| masses[i] * y[6 * i + k]).sum::<f64>() / total;
+        for i in 0..n {
+            y[6 * i + k] -= mean;
+        }
+    }
+}
+
+/// Rotate every 3-vector of the packed state by φ about z.
+pub(crate) fn rotate_z(y: &mut [f64], phi: f64) {
+    let (s, c) = phi.sin_cos();
+    for w in y.chunks_exact_mut(3) {
+        let (x, yy) = (w[0], w[1]);
+        w[0] = c * x - s * yy;
+        w[1] = s * x + c * yy;
+    }
+}
+
+/// sin and cos by their Taylor series, to BD_SCALE digits.
+fn sin_cos_bd(x: &BD) -> (BD, BD) {
+    let x2 = (x * x).with_scale(BD_SCALE);
+    let (mut sin, mut cos) = (x.clone(), BD::one());
+    let (mut ts, mut tc) = (x.clone(), BD::one());
+    let mut k = 1u64;
+    loop {
+        ts = (-(&ts * &x2) / BD::from((2 * k) * (2 * k + 1))).with_scale(BD_SCALE);
+        tc = (-(&tc * &x2) / BD::from((2 * k - 1) * (2 * k))).with_scale(BD_SCALE);
+        if ts.is_zero() && tc.is_zero() {
+            break;
+        }
+        sin += &ts;
+        cos += &tc;
+        k += 1;
+    }
+    (sin, cos)
+}
+
+pub(crate) fn norm(v: &[f64]) -> f64 {
+    v.iter().map(|x| x * x).sum::<f64>().sqrt()
+}
+
+struct Shooter<'a> {
+    masses: &'a [f64],
+    options: &'a ShootingOptions,
+}
+
+impl Shooter<'_> {
+    /// Closure residual F at `p`.
+    fn residual(&self, p: &Point) -> Result<Vec<f64>, String> {
+        let dim = p.y.len();
+        let f: Vec<f64> = if self.options.high_precision {
+            let settings = self.options.settings.unwrap_or(feagin14::SETTINGS);
+            let mut y_t = flow_bd(&p.y, self.masses, &p.t, &settings)?;
+            if !p.theta.is_zero() {
+                let (s, c) = sin_cos_bd(&-&p.theta);
+                for w in y_t.chunks_exact_mut(3) {
+                    let x = (&c * &w[0] - &s * &w[1]).with_scale(BD_SCALE);
+                    let y = (&s * &w[0] + &c * &w[1]).with_scale(BD_SCALE);
+                    (w[0], w[1]) = (x, y);
+                }
+            }
+            y_t.iter()
+                .zip(&p.y)
+                .map(|(a, b)| (a - b).to_f64().unwrap())
+                .collect()
+        } else {
+            let y0 = p.y_f64();
+            let t = p.t.to_f64().unwrap();
+            let mut bodies = bodies_from(&y0, self.masses);
+            let (_, t_reached) =
+                self.options
+                    .method
+                    .evolve(&mut bodies, t, self.options.settings.as_ref());
+            if t_reached != t {
+                return Err(format!("Integration stopped at t = {t_reached} before {t}"));
+            }
+            let mut y_t = erk::pack_state(&bodies);
+            rotate_z(&mut y_t, -p.theta.to_f64().unwrap());
+            y_t.iter().zip(&y0).map(|(a, b)| a - b).collect()
+        };
+        if f.len() != dim || f.iter().any(|x| !x.is_finite()) {
+            return Err("Closure residual is not finite".to_string());
+        }
+        Ok(f)
+    }
+
+    /// Newton system A δ = b: the Jacobian of F followed by the phase conditions.
+    /// Returns (A row-major, rows, cols, b).
+    fn system(
+        &self,
+        p: &Point,
+        f: &[f64],
+        targets: &PhaseTargets,
+    ) -> Result<(Vec<f64>, usize, usize, Vec<f64>), String> {
+        let dim = p.y.len();
+        let cols = dim + 1 + usize::from(self.options.rotation);
+        let y0 = p.y_f64();
+        let t = p.t.to_f64().unwrap();
+        let theta = self.options.rotation.then(|| p.theta.to_f64().unwrap());
+        let (mut a, _) = return_map_rows(&y0, self.masses, t, theta, cols)?;
+        let mut b: Vec<f64> = f.iter().map(|x| -x).collect();
+        let (rows, rhs) = phase_rows(&y0, self.masses, targets, cols);
+        a.extend(rows);
+        b.extend(rhs);
+        Ok((a, b.len(), cols, b))
+    }
+}
+
+/// Rows of the return-map Jacobian [R(-θ) Φ - I | R(-θ) f(y(T)) | -ẑ × R(-θ) y(T)],
+/// `cols` wide (extra columns are zero). The θ column is only present when `theta` is
+/// given; `None` means no rotation. Also returns Φ(T).
+pub(crate) fn return_map_rows(
+    y0: &[f64],
+    masses: &[f64],
+    t: f64,
+    theta: Option<f64>,
+    cols: usize,
+) -> Result<(Vec<f64>, Vec<f64>), String> {
+    let dim = y0.len();
+    let mut bodies = bodies_from(y0, masses);
+    let (phi, t_reached) = dop853::evolve_variational(&mut bodies, t, &JACOBIAN_SETTINGS);
+    if t_reached != t {
+        return Err(format!(
+            "Variational integration stopped at t = {t_reached}"
+        ));
+    }
+    let y_t = erk::pack_state(&bodies);
+    let f_t = erk::deriv(&y_t, masses, 0.0);
+
+    // One 3-row block at a time: R(-θ) acts within each block
+    let mut a = Vec::with_capacity(dim * cols);
+    let (s, c) = (-theta.unwrap_or(0.0)).sin_cos();
+    let rot = |x: f64, y: f64, z: f64| [c * x - s * y, s * x + c * y, z];
+    for blk in (0..dim).step_by(3) {
+        let mut rows3 = [vec![0.0; cols], vec![0.0; cols], vec![0.0; cols]];
+        for q in 0..dim {
+            let v = rot(
+                phi[blk * dim + q],
+                phi[(blk + 1) * dim + q],
+                phi[(blk + 2) * dim + q],
+            );
+            for k in 0..3 {
+                rows3[k][q] = v[k];
+            }
+        }
+        let v = rot(f_t[blk], f_t[blk + 1], f_t[blk + 2]);
+        for k in 0..3 {
+            rows3[k][dim] = v[k];
+            rows3[k][blk + k] -= 1.0;
+        }
+        if theta.is_some() {
+            // d/dθ R(-θ) y = -ẑ × R(-θ) y
+            let v = rot(y_t[blk], y_t[blk + 1], y_t[blk + 2]);
+            rows3[0][dim + 1] = v[1];
+            rows3[1][dim + 1] = -v[0];
+        }
+        for r in &rows3 {
+            a.extend_from_slice(r);
+        }
+    }
+    Ok((a, phi))
+}
+
+/// Values the phase conditions hold the corrected initial state to.
+pub(crate) struct PhaseTargets {
+    /// Energy to keep (None leaves it free).
+    pub energy: Option<f64>,
+    /// Σ m_i r_i to keep.
+    pub centre: [f64; 3],
+    /// Mass k is an unknown, in the last column.
+    pub mass_param: Option<usize>,
+}
+
+impl PhaseTargets {
+    pub fn at(y0: &[f64], masses: &[f64], fix_energy: bool) -> Self {
+        PhaseTargets {
+            energy: fix_energy.then(|| energy(y0, masses)),
+            centre: std::array::from_fn(|k| {
+                masses
+                    .iter()
+                    .enumerate()
+                    .map(|(i, m)| m * y0[6 * i + k])
+                    .sum()
+            }),
+            mass_param: None,
+        }
+    }
+}
+
+/// Total energy of a packed state.
+pub(crate) fn energy(y: &[f64], masses: &[f64]) -> f64 {
+    crate::utils::total_energy(&bodies_from(y, masses))
+}
+
+/// ∂E/∂y0: -m_i a_i for the positions, m_i v_i for the velocities.
+pub(crate) fn energy_gradient(y0: &[f64], masses: &[f64]) -> Vec<f64> {
+    let mut g = erk::deriv(y0, masses, 0.0);
+    for (i, m) in masses.iter().enumerate() {
+        for k in 0..3 {
+            g[6 * i + k] = -m * g[6 * i + 3 + k];
+            g[6 * i + 3 + k] = m * y0[6 * i + 3 + k];
+        }
+    }
+    g
+}
+
+/// Phase conditions on δy0 as linearized equations `cols` wide (δy0 in the first 6N
+/// columns, ∂/∂m_k in the last one for mass continuation), with their right-hand sides:
+/// no time shift (δy0 ⟂ f(y0)), no rotation (δy0 ⟂ ê × (r_i, v_i) for each axis),
+/// energy and centre of mass at their targets, zero total momentum.
+pub(crate) fn phase_rows(
+    y0: &[f64],
+    masses: &[f64],
+    targets: &PhaseTargets,
+    cols: usize,
+) -> (Vec<f64>, Vec<f64>) {
+    let n = masses.len();
+    let dim = 6 * n;
+    let mut a = Vec::new();
+    let mut b = Vec::new();
+    let mut row = vec![0.0; cols];
+    let mut push = |row: &mut Vec<f64>, rhs: f64| {
+        a.extend_from_slice(row);
+        b.push(rhs);
+        row.fill(0.0);
+    };
+
+    let f0 = erk::deriv(y0, masses, 0.0);
+    row[..dim].copy_from_slice(&f0);
+    push(&mut row, 0.0);
+
+    for axis in 0..3 {
+        for w in 0..2 * n {
+            let o = 3 * w;
+            let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+            row[o + v] = y0[o + u];
+            row[o + u] = -y0[o + v];
+        }
+        push(&mut row, 0.0);
+    }
+
+    if let Some(e) = targets.energy {
+        row[..dim].copy_from_slice(&energy_gradient(y0, masses));
+        if let Some(k) = targets.mass_param {
+            // ∂E/∂m_k = v_k²/2 - Σ_j m_j / r_kj
+            let vk = &y0[6 * k + 3..6 * k + 6];
+            let mut d = 0.5 * (vk[0] * vk[0] + vk[1] * vk[1] + vk[2] * vk[2]);
+            for j in (0..n).filter(|&j| j != k) {
+                let r: f64 = (0..3)
+                    .map(|c| (y0[6 * j + c] - y0[6 * k + c]).powi(2))
+                    .sum::<f64>()
+                    .sqrt();
+                d -= masses[j] / r;
+            }
+            row[cols - 1] = d;
+        }
+        push(&mut row, e - energy(y0, masses));
+    }
+
+    for k in 0..3 {
+        let mut centre = 0.0;
+        let mut momentum = 0.0;
+        for (i, &m) in masses.iter().enumerate() {
+            row[6 * i + k] = m;
+            centre += m * y0[6 * i + k];
+        }
+        if let Some(p) = targets.mass_param {
+            row[cols - 1] = y0[6 * p + k];
+        }
+        push(&mut row, targets.centre[k] - centre);
+        for (i, &m) in masses.iter().enumerate() {
+            row[6 * i + 3 + k] = m;
+            momentum += m * y0[6 * i + 3 + k];
+        }
+        if let Some(p) = targets.mass_param {
+            row[cols - 1] = y0[6 * p + 3 + k];
+        }
+        push(&mut row, -momentum);
+    }
+    (a, b)
+}
+
+/// Damped least-squares step: minimize |A δ - b|² + (λ s)² |δ|², s = largest column norm.
+pub(crate) fn damped_step(
+    a: &[f64],
+    rows: usize,
+    cols: usize,
+    b: &[f64],
+    lambda: f64,
+) -> Result<Vec<f64>, String> {
+    let scale = (0..cols)
+        .map(|j| {
+            (0..rows)
+                .map(|i| a[i * cols + j] * a[i * cols + j])
+                .sum::<f64>()
+                .sqrt()
+        })
+        .fold(0.0, f64::max);
+    let mut m = a.to_vec();
+    let mut rhs = b.to_vec();
+    for j in 0..cols {
+        let mut row = vec![0.0; cols];
+        row[j] = lambda * scale;
+        m.extend_from_slice(&row);
+        rhs.push(0.0);
+    }
+    linalg::least_squares(&m, rows + cols, cols, &rhs)
+}
+
+/// Refine an approximate periodic orbit given in f64 (see `refine_orbit_bd`).
+pub fn refine_orbit(
+    bodies: &[Body],
+    period: f64,
+    options: &ShootingOptions,
+) -> Result<Refined, String> {
+    let state: Vec<BD> = erk::pack_state(bodies)
+        .iter()
+        .map(|&x| BD::from_f64(x).unwrap())
+        .collect();
+    let masses: Vec<f64> = bodies.iter().map(|b| b.m).collect();
+    let period = BD::from_f64(period).ok_or("period must be finite")?;
+    refine_orbit_bd(&state, &masses, &period, options)
+}
+
+/// Refine the initial state (packed [r0, v0, r1, v1, ...]) and period of an approximate
+/// periodic orbit by damped Newton shooting on the return map.
+///
+/// In f64 mode the state is rounded to f64 before every integration; with
+/// `options.high_precision` it stays in BigDecimal throughout.
+pub fn refine_orbit_bd(
+    state: &[BD],
+    masses: &[f64],
+    period: &BD,
+    options: &ShootingOptions,
+) -> Result<Refined, String> {
+    if state.len() != 6 * masses.len() {
+        return Err(format!(
+            "State must have 6 values per body, got {} for {} bodies",
+            state.len(),
+            masses.len()
+        ));
+    }
+    let exact = options.high_precision;
+    let start = Point {
+        y: state.to_vec(),
+        t: period.clone(),
+        theta: BD::zero(),
+    };
+    let mut p = if exact {
+        start
+    } else {
+        start.apply(&vec![0.0; state.len() + 1], false)
+    };
+    let shooter = Shooter { masses, options };
+    let targets = PhaseTargets::at(&p.y_f64(), masses, options.fix_energy);
+
+    let mut f = shooter.residual(&p)?;
+    let residual_before = norm(&f);
+    let mut history = vec![residual_before];
+    let mut lambda = LAMBDA_START;
+    let mut iterations = 0;
+    while norm(&f) > options.tol && iterations < options.max_iterations {
+        let (a, rows, cols, b) = shooter.system(&p, &f, &targets)?;
+        let mut accepted = false;
+        while lambda <= LAMBDA_MAX {
+            let delta = damped_step(&a, rows, cols, &b, lambda)?;
+            let candidate = p.apply(&delta, exact);
+            match shooter.residual(&candidate) {
+                Ok(fc) if norm(&fc) < norm(&f) => {
+                    p = candidate;
+                    f = fc;
+                    lambda = (lambda / 10.0).max(LAMBDA_MIN);
+                    accepted = true;
+                    break;
+                }
+                _ => lambda *= 10.0,
+            }
+        }
+        if !accepted {
+            break;
+        }
+        iterations += 1;
+        history.push(norm(&f));
+    }
+
+    let residual_after = norm(&f);
+    Ok(Refined {
+        bodies: bodies_from(&p.y_f64(), masses),
+        state: p.y,
+        period: p.t,
+        theta: p.theta,
+        residual_before,
+        residual_after,
+        iterations,
+        converged: residual_after <= options.tol,
+        history,
+    })
+}
@@ -0,0 +1,403 @@
+// Symmetries of a periodic orbit.
+//
+// A symmetry is a body permutation σ, a time shift τ, an optional time reversal
+// (ε = -1) and an orthogonal map M of the x-y plane with
+//
+//     r_σ(i)(ε t + τ) = M r_i(t)    for all t and every body i,
+//
+// in the centre-of-mass frame (z components must match as they are). M is a rotation
+// R(φ) or a reflection R(φ) F, F = diag(1, -1), the reflection in the line at angle φ/2.
+// A choreography has σ a cycle of all bodies, τ = ±T/n and M = I. The figure-eight has the
+// full group of order 12: choreography, reflections and time reversal. The orbit has to
+// be periodic in the inertial frame; relative periodic orbits (returning up to a
+// rotation) only show approximate symmetries.
+//
+// The orbit is integrated once over the period and interpolated by quintic Hermite
+// polynomials (positions, velocities, accelerations) between accepted steps. For every σ,
+// ε and kind of M, τ is scanned over a uniform grid of `samples` times (the best M is the
+// 2-D Procrustes fit), and minima of the residual are refined by golden-section search.
+
+use crate::{
+    adaptive::{AdaptiveSettings, drive},
+    erk::{self, ErkPair},
+    method::Method,
+    shooting::{bodies_from, to_centre_of_mass},
+    types::Body,
+};
+
+// More bodies than this would make the permutation scan too long
+const MAX_BODIES: usize = 6;
+// Grid residuals below this are refined
+const COARSE_TOL: f64 = 0.1;
+const GOLDEN_ITERATIONS: usize = 60;
+// Shifts (fractions of T) and angles closer than this to zero count as zero
+const SHIFT_TOL: f64 = 1e-6;
+const ANGLE_TOL: f64 = 1e-6;
+
+/// Integrator, resolution and tolerance of the symmetry scan.
+#[derive(Clone, Copy, Debug)]
+pub struct SymmetryOptions {
+    /// One of the f64 embedded pairs (dop853, dopri5, bs32, cash_karp).
+    pub method: Method,
+    /// Replaces the method's default settings.
+    pub settings: Option<AdaptiveSettings>,
+    /// Points of the time grid the residuals are measured on.
+    pub samples: usize,
+    /// Largest relative residual of an accepted symmetry.
+    pub tol: f64,
+}
+
+impl Default for SymmetryOptions {
+    fn default() -> Self {
+        SymmetryOptions {
+            method: Method::Dop853,
+            settings: None,
+            samples: 360,
+            tol: 1e-6,
+        }
+    }
+}
+
+/// One symmetry operation; see the module comment for its meaning.
+#[derive(Clone, Debug)]
+pub struct SymmetryOp {
+    /// σ: body i is mapped onto body `permutation[i]`.
+    pub permutation: Vec<usize>,
+    /// τ as a fraction of the period, in [0, 1).
+    pub shift: f64,
+    pub time_reversal: bool,
+    /// M = R(angle) F instead of R(angle).
+    pub reflection: bool,
+    /// φ in (-π, π].
+    pub angle: f64,
+    /// RMS of r_σ(i)(ε t + τ) - M r_i(t) over the grid, relative to the RMS radius.
+    pub residual: f64,
+}
+
+impl SymmetryOp {
+    /// σ is a single cycle through all bodies.
+    pub fn is_cyclic(&self) -> bool {
+        let n = self.permutation.len();
+        let mut i = 0;
+        for k in 1..=n {
+            i = self.permutation[i];
+            if i == 0 {
+                return k == n;
+            }
+        }
+        false
+    }
+
+    /// M is the identity (up to `tol` in the angle).
+    pub fn is_translation(&self, tol: f64) -> bool {
+        !self.reflection && self.angle.abs() <= tol
+    }
+}
+
+/// Symmetries found, without the identity.
+#[derive(Clone, Debug)]
+pub struct Symmetries {
+    pub ops: Vec<SymmetryOp>,
+}
+
+impl Symmetries {
+    /// Order of the group (the identity included).
+    pub fn order(&self) -> usize {
+        self.ops.len() + 1
+    }
+
+    /// Some op is a time shift with a cyclic permutation and M = I.
+    pub fn is_choreography(&self) -> bool {
+        self.ops
+            .iter()
+            .any(|op| op.is_cyclic() && !op.time_reversal && op.is_translation(ANGLE_TOL))
+    }
+
+    pub fn has_time_reversal(&self) -> bool {
+        self.ops.iter().any(|op| op.time_reversal)
+    }
+
+    pub fn has_reflection(&self) -> bool {
+        self.ops.iter().any(|op| op.reflection)
+    }
+
+    /// Short description, e.g. `order 12: choreography, reflection, time reversal`.
+    pub fn label(&self) -> String {
+        let mut parts = Vec::new();
+        if self.is_choreography() {
+            parts.push("choreography");
+        }
+        if self.has_reflection() {
+            parts.push("reflection");
+        }
+        if self.has_time_reversal() {
+            parts.push("time reversal");
+        }
+        if parts.is_empty() {
+            format!("order {}", self.order())
+        } else {
+            format!("order {}: {}", self.order(), parts.join(", "))
+        }
+    }
+}
+
+/// Positions over one period, interpolated between accepted steps.
+struct Trajectory {
+    period: f64,
+    times: Vec<f64>,
+    // Per accepted step: positions, velocities and accelerations of all bodies
+    states: Vec<Vec<[[f64; 3]; 3]>>,
+}
+
+impl Trajectory {
+    fn positions_at(&self, t: f64) -> Vec<[f64; 3]> {
+        let t = t.rem_euclid(self.period);
+        let k = self
+            .times
+            .partition_point(|&tk| tk <= t)
+            .clamp(1, self.times.len() - 1);
+        let (t0, t1) = (self.times[k - 1], self.times[k]);
+        let h = t1 - t0;
+        let s = (t - t0) / h;
+        let (s2, s3) = (s * s, s * s * s);
+        let (s4, s5) = (s3 * s, s3 * s2);
+        let w = [
+            1.0 - 10.0 * s3 + 15.0 * s4 - 6.0 * s5,
+            h * (s - 6.0 * s3 + 8.0 * s4 - 3.0 * s5),
+            h * h * 0.5 * (s2 - 3.0 * s3 + 3.0 * s4 - s5),
+            10.0 * s3 - 15.0 * s4 + 6.0 * s5,
+            h * (-4.0 * s3 + 7.0 * s4 - 3.0 * s5),
+            h * h * 0.5 * (s3 - 2.0 * s4 + s5),
+        ];
+        self.states[k - 1]
+            .iter()
+            .zip(&self.states[k])
+            .map(|(a, b)| {
+                std::array::from_fn(|c| {
+                    w[0] * a[0][c]
+                        + w[1] * a[1][c]
+                        + w[2] * a[2][c]
+                        + w[3] * b[0][c]
+                        + w[4] * b[1][c]
+                        + w[5] * b[2][c]
+                })
+            })
+            .collect()
+    }
+}
+
+/// Position, velocity and acceleration of every body at the packed state `y`.
+fn kinematics(y: &[f64], masses: &[f64]) -> Vec<[[f64; 3]; 3]> {
+    let f = erk::deriv(y, masses, 0.0);
+    (0..masses.len())
+        .map(|i| {
+            let b = 6 * i;
+            [
+                [y[b], y[b + 1], y[b + 2]],
+                [y[b + 3], y[b + 4], y[b + 5]],
+                [f[b + 3], f[b + 4], f[b + 5]],
+            ]
+        })
+        .collect()
+}
+
+/// All permutations of 0..n.
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+    if n == 0 {
+        return vec![Vec::new()];
+    }
+    let mut out = Vec::new();
+    for p in permutations(n - 1) {
+        for k in 0..n {
+            let mut q = p.clone();
+            q.insert(k, n - 1);
+            out.push(q);
+        }
+    }
+    out
+}
+
+/// Best M (of the given kind) taking `source` to `target`, and the relative residual.
+fn fit(
+    source: &[Vec<[f64; 3]>],
+    target: &[Vec<[f64; 3]>],
+    reflection: bool,
+    scale: f64,
+) -> (f64, f64) {
+    let flip = if reflection { -1.0 } else { 1.0 };
+    let (mut dot, mut cross) = (0.0, 0.0);
+    for (ps, qs) in source.iter().zip(target) {
+        for (p, q) in ps.iter().zip(qs) {
+            let (x, y) = (p[0], flip * p[1]);
+            dot += x * q[0] + y * q[1];
+            cross += x * q[1] - y * q[0];
+        }
+    }
+    let angle = if dot == 0.0 && cross == 0.0 {
+        0.0
+    } else if cross.abs() <= ANGLE_TOL * dot.abs() && dot < 0.0 {
+        // A half turn: report π rather than -π
+        std::f64::consts::PI
+    } else {
+        cross.atan2(dot)
+    };
+    let (s, c) = angle.sin_cos();
+    let mut sum = 0.0;
+    let mut count = 0;
+    for (ps, qs) in source.iter().zip(target) {
+        for (p, q) in ps.iter().zip(qs) {
+            let (x, y) = (p[0], flip * p[1]);
+            sum += (c * x - s * y - q[0]).powi(2)
+                + (s * x + c * y - q[1]).powi(2)
+                + (p[2] - q[2]).powi(2);
+            count += 1;
+        }
+    }
+    (angle, (sum / count as f64).sqrt() / scale)
+}
+
+/// Test the periodic orbit (`bodies`, `period`) against every candidate symmetry.
+pub fn find_symmetries(
+    bodies: &[Body],
+    period: f64,
+    options: &SymmetryOptions,
+) -> Result<Symmetries, String> {
+    let n = bodies.len();
+    if n == 0 || n > MAX_BODIES {
+        return Err(format!(
+            "Symmetry detection needs 1 to {MAX_BODIES} bodies, got {n}"
+        ));
+    }
+    if period <= 0.0 || !period.is_finite() {
+        return Err(format!("period must be positive, got {period}"));
+    }
+    if options.samples < 2 {
+        return Err(format!(
+            "samples must be at least 2, got {}",
+            options.samples
+        ));
+    }
+    let tableau = options.method.tableau().ok_or_else(|| {
+        format!(
+            "Symmetry detection needs an f64 embedded pair (dop853, dopri5, bs32, cash_karp), not {}",
+            options.method
+        )
+    })?;
+    let settings = options
+        .settings
+        .or(options.method.default_settings())
+        .expect("embedded pairs have default settings");
+
+    let masses: Vec<f64> = bodies.iter().map(|b| b.m).collect();
+    let mut y0 = erk::pack_state(bodies);
+    to_centre_of_mass(&mut y0, &masses);
+    let mut trajectory = Trajectory {
+        period,
+        times: vec![0.0],
+        states: vec![kinematics(&y0, &masses)],
+    };
+    let mut pair = ErkPair::new(tableau, &bodies_from(&y0, &masses), 0.0);
+    let t_end = drive(&mut pair, period, &settings, |p, t, _| {
+        trajectory.times.push(t);
+        trajectory.states.push(kinematics(p.state(), &masses));
+    });
+    if t_end != period {
+        return Err(format!("Integration stopped at t = {t_end}"));
+    }
+
+    let m = options.samples;
+    let dt = period / m as f64;
+    let grid: Vec<Vec<[f64; 3]>> = (0..m)
+        .map(|k| trajectory.positions_at(k as f64 * dt))
+        .collect();
+    let scale = (grid
+        .iter()
+        .flatten()
+        .map(|r| r[0] * r[0] + r[1] * r[1] + r[2] * r[2])
+        .sum::<f64>()
+        / (m * n) as f64)
+        .sqrt();
+    if scale == 0.0 {
+        return Err("all bodies stay at the centre of mass".to_string());
+    }
+
+    let mut ops = Vec::new();
+    for sigma in permutations(n) {
+        let identity = sigma.iter().enumerate().all(|(i, &j)| i == j);
+        for time_reversal in [false, true] {
+            let eps = if time_reversal { -1.0 } else { 1.0 };
+            // Target positions r_σ(i)(ε t_k + τ) for every grid time t_k
+            let target_at = |tau: f64| -> Vec<Vec<[f64; 3]>> {
+                (0..m)
+                    .map(|k| {
+                        let r = trajectory.positions_at(eps * k as f64 * dt + tau);
+                        sigma.iter().map(|&j| r[j]).collect()
+                    })
+                    .collect()
+            };
+            for reflection in [false, true] {
+                // Residual at τ = j dt, on the grid itself
+                let coarse: Vec<f64> = (0..m)
+                    .map(|j| {
+                        let target: Vec<Vec<[f64; 3]>> = (0..m)
+                            .map(|k| {
+                                let idx = (j as isize + eps as isize * k as isize)
+                                    .rem_euclid(m as isize)
+                                    as usize;
+                                sigma.iter().map(|&s| grid[idx][s]).collect()
+                            })
+                            .collect();
+                        fit(&grid, &target, reflection, scale).1
+                    })
+                    .collect();
+                for j in 0..m {
+                    let (before, after) = (coarse[(j + m - 1) % m], coarse[(j + 1) % m]);
+                    if coarse[j] > COARSE_TOL || coarse[j] > before || coarse[j] >= after {
+                        continue;
+                    }
+                    // Golden-section search on [τ_j - dt, τ_j + dt]
+                    let residual = |tau: f64| fit(&grid, &target_at(tau), reflection, scale);
+                    let ratio = (5f64.sqrt() - 1.0) / 2.0;
+                    let (mut a, mut b) = ((j as f64 - 1.0) * dt, (j as f64 + 1.0) * dt);
+                    let mut c = b - ratio * (b - a);
+                    let mut d = a + ratio * (b - a);
+                    let (mut fc, mut fd) = (residual(c).1, residual(d).1);
+                    for _ in 0..GOLDEN_ITERATIONS {
+                        if fc < fd {
+                            (b, d, fd) = (d, c, fc);
+                            c = b - ratio * (b - a);
+                            fc = residual(c).1;
+                        } else {
+                            (a, c, fc) = (c, d, fd);
+                            d = a + ratio * (b - a);
+                            fd = residual(d).1;
+                        }
+                    }
+                    let tau = 0.5 * (a + b);
+                    let (angle, res) = residual(tau);
+                    let mut shift = (tau / period).rem_euclid(1.0);
+                    if 1.0 - shift < SHIFT_TOL {
+                        shift = 0.0;
+                    }
+                    let op = SymmetryOp {
+                        permutation: sigma.clone(),
+                        shift,
+                        time_reversal,
+                        reflection,
+                        angle,
+                        residual: res,
+                    };
+                    if res <= options.tol
+                        && !(identity
+                            && !time_reversal
+                            && shift < SHIFT_TOL
+                            && op.is_translation(ANGLE_TOL))
+                    {
+                        ops.push(op);
+                    }
+                }
+            }
+        }
+    }
+    Ok(Symmetries { ops })
+}
@@ -0,0 +1,220 @@
+// Topological classification of planar three-body orbits (Montgomery; Šuvakov &
+// Dmitrašinović).
+//
+// A syzygy is an instant when the three bodies are collinear: the signed area of the
+// triangle, ((r2 - r1) × (r3 - r1))_z, changes sign. It is labelled by the body that lies
+// between the other two. Over one period the cyclic syzygy sequence, with stutters (the
+// same label twice in a row) cancelled, is read in pairs as a word in the free group on
+// two letters:
+//
+//     12 = a, 23 = b, 21 = A, 32 = B, 13 = ab, 31 = BA
+//
+// (A = a⁻¹, B = b⁻¹). A sequence of odd length is read over two periods. The orbit's
+// class is the conjugacy class of the word up to inversion (time reversal); we label it
+// by the smallest cyclic rotation of the word or of its inverse, with a < b < A < B.
+// The figure-eight is `abAB`.
+
+use crate::{adaptive::AdaptiveSettings, method::Method, types::Body};
+
+/// A collinear configuration along the trajectory.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Syzygy {
+    /// Time of the sign change of the signed area (linearly interpolated between samples).
+    pub t: f64,
+    /// Index (0-based) of the body between the other two.
+    pub middle: usize,
+}
+
+/// Syzygies of a three-body trajectory given as the flat `[x, y, z] * 3, t` history
+/// returned by the `evolve` functions, using the x-y projection.
+///
+/// The history is taken to cover a period: a zero area takes the sign of the sample
+/// before it, cyclically, so an orbit that starts (and ends) collinear counts that
+/// syzygy once.
+pub fn syzygies(series: &[f64]) -> Vec<Syzygy> {
+    let samples: Vec<&[f64]> = series.chunks_exact(10).collect();
+    let area: Vec<f64> = samples
+        .iter()
+        .map(|s| (s[3] - s[0]) * (s[7] - s[1]) - (s[4] - s[1]) * (s[6] - s[0]))
+        .collect();
+    let mut sign = vec![0.0; area.len()];
+    let mut last = area
+        .iter()
+        .rev()
+        .find(|a| **a != 0.0)
+        .map_or(0.0, |a| a.signum());
+    for (k, a) in area.iter().enumerate() {
+        if *a != 0.0 {
+            last = a.signum();
+        }
+        sign[k] = last;
+    }
+
+    let mut out = Vec::new();
+    for k in 1..samples.len() {
+        if sign[k - 1] == sign[k] {
+            continue;
+        }
+        // Positions at the zero of the linearly interpolated area
+        let (a0, a1) = (area[k - 1], area[k]);
+        let s = a0 / (a0 - a1);
+        let (w0, w1) = (samples[k - 1], samples[k]);
+        let p: Vec<f64> = (0..10).map(|i| w0[i] + s * (w1[i] - w0[i])).collect();
+        // Order the bodies along the line through them
+        let dir = if (p[3] - p[0]).abs() + (p[4] - p[1]).abs() > 0.0 {
+            [p[3] - p[0], p[4] - p[1]]
+        } else {
+            [p[6] - p[0], p[7] - p[1]]
+        };
+        let along = |i: usize| p[3 * i] * dir[0] + p[3 * i + 1] * dir[1];
+        let mut order = [0, 1, 2];
+        order.sort_by(|&i, &j| along(i).total_cmp(&along(j)));
+        out.push(Syzygy {
+            t: p[9],
+            middle: order[1],
+        });
+    }
+    out
+}
+
+/// Cancel stutters (equal neighbours) of a cyclic sequence until none is left.
+fn remove_stutters(sequence: &[usize]) -> Vec<usize> {
+    let mut stack: Vec<usize> = Vec::with_capacity(sequence.len());
+    for &s in sequence {
+        if stack.last() == Some(&s) {
+            stack.pop();
+        } else {
+            stack.push(s);
+        }
+    }
+    // Wrap around
+    while stack.len() >= 2 && stack.first() == stack.last() {
+        stack.pop();
+        stack.remove(0);
+    }
+    stack
+}
+
+fn inverse_letter(c: char) -> char {
+    if c.is_ascii_lowercase() {
+        c.to_ascii_uppercase()
+    } else {
+        c.to_ascii_lowercase()
+    }
+}
+
+/// Free reduction: cancel adjacent `aA`, `Aa`, `bB` and `Bb`.
+pub fn reduce_word(word: &str) -> String {
+    let mut out: Vec<char> = Vec::with_capacity(word.len());
+    for c in word.chars() {
+        if out.last() == Some(&inverse_letter(c)) {
+            out.pop();
+        } else {
+            out.push(c);
+        }
+    }
+    out.into_iter().collect()
+}
+
+/// Free and cyclic reduction: also cancel letters that are inverse across the ends.
+pub fn cyclic_reduce(word: &str) -> String {
+    let mut w: Vec<char> = reduce_word(word).chars().collect();
+    while w.len() >= 2 && w[0] == inverse_letter(w[w.len() - 1]) {
+        w.pop();
+        w.remove(0);
+    }
+    w.into_iter().collect()
+}
+
+/// Inverse word: reversed, with every letter inverted.
+pub fn inverse_word(word: &str) -> String {
+    word.chars().rev().map(inverse_letter).collect()
+}
+
+/// Representative of the conjugacy class of `word` up to inversion: the smallest cyclic
+/// rotation of the cyclically reduced word or of its inverse, ordering a < b < A < B.
+pub fn canonical_word(word: &str) -> String {
+    let rank = |c: char| "abAB".find(c).unwrap_or(4);
+    let w = cyclic_reduce(word);
+    let inv = inverse_word(&w);
+    let n = w.len();
+    let rotations = (0..n.max(1)).flat_map(|k| {
+        [&w, &inv].map(|s| {
+            let (head, tail) = s.split_at(k.min(s.len()));
+            format!("{tail}{head}")
+        })
+    });
+    rotations
+        .min_by(|x, y| x.chars().map(rank).cmp(y.chars().map(rank)))
+        .unwrap_or_default()
+}
+
+/// Free-group word of a cyclic syzygy sequence (body indices 0, 1, 2), freely reduced.
+pub fn syzygy_word(sequence: &[usize]) -> String {
+    let mut s = remove_stutters(sequence);
+    if s.len() % 2 == 1 {
+        s.extend_from_within(..);
+    }
+    let word: String = s
+        .chunks_exact(2)
+        .map(|pair| match (pair[0], pair[1]) {
+            (0, 1) => "a",
+            (1, 0) => "A",
+            (1, 2) => "b",
+            (2, 1) => "B",
+            (0, 2) => "ab",
+            (2, 0) => "BA",
+            _ => "",
+        })
+        .collect();
+    reduce_word(&word)
+}
+
+/// Topological label of a periodic orbit.
+#[derive(Clone, Debug)]
+pub struct Topology {
+    pub syzygies: Vec<Syzygy>,
+    /// Free-group word read from the syzygy sequence (freely reduced).
+    pub word: String,
+    /// Class label (see `canonical_word`); equal labels mean topologically equal orbits.
+    pub canonical: String,
+}
+
+impl Topology {
+    /// The syzygy sequence as 1-based body labels, e.g. `123123`.
+    pub fn sequence(&self) -> String {
+        self.syzygies
+            .iter()
+            .map(|s| char::from(b'1' + s.middle as u8))
+            .collect()
+    }
+}
+
+/// Integrate a planar three-body orbit over one `period` and classify it. `settings`
+/// replaces the method's defaults for adaptive methods.
+pub fn classify(
+    bodies: &[Body],
+    period: f64,
+    method: Method,
+    settings: Option<&AdaptiveSettings>,
+) -> Result<Topology, String> {
+    if bodies.len() != 3 {
+        return Err(format!(
+            "Syzygies need exactly 3 bodies, got {}",
+            bodies.len()
+        ));
+    }
+    let mut end = bodies.to_vec();
+    let (series, t) = method.evolve(&mut end, period, settings);
+    if (t - period).abs() > 1e-9 * period.abs().max(1.0) {
+        return Err(format!("Integration stopped at t = {t} before {period}"));
+    }
+    let syzygies = syzygies(&series);
+    let sequence: Vec<usize> = syzygies.iter().map(|s| s.middle).collect();
+    let word = syzygy_word(&sequence);
+    Ok(Topology {
+        canonical: canonical_word(&word),
+        syzygies,
+        word,
+    })
+}
@@ -1,35 +1,90 @@
 use crate::types::Body;
 
-/// Total energy = kinetic + potential (G=1).
-pub fn total_energy(bodies: &[Body]) -> f64 {
+/// Kinetic energy Σ m v² / 2.
+pub fn kinetic_energy(bodies: &[Body]) -> f64 {
+    bodies.iter().map(|b| 0.5 * b.m * dot(b.v, b.v)).sum()
+}
+
+/// Potential energy -Σ m_i m_j / r_ij (G=1).
+pub fn potential_energy(bodies: &[Body]) -> f64 {
     let n = bodies.len();
-    // Kinetic
-    let mut ke = 0.0;
-    for b in bodies {
-        let v2 = b.v[0] * b.v[0] + b.v[1] * b.v[1] + b.v[2] * b.v[2];
-        ke += 0.5 * b.m * v2;
-    }
-    // Potential
     let mut pe = 0.0;
     for i in 0..n {
         for j in (i + 1)..n {
             let rij = sub(bodies[j].r, bodies[i].r);
-            let r = (rij[0] * rij[0] + rij[1] * rij[1] + rij[2] * rij[2]).sqrt();
-            pe += -bodies[i].m * bodies[j].m / r;
+            pe += -bodies[i].m * bodies[j].m / dot(rij, rij).sqrt();
         }
     }
-    ke + pe
+    pe
 }
 
-/// Angular momentum
+/// Total energy = kinetic + potential (G=1).
+pub fn total_energy(bodies: &[Body]) -> f64 {
+    kinetic_energy(bodies) + potential_energy(bodies)
+}
+
+/// Angular momentum Σ m r × v about the origin.
 pub fn total_angular_momentum(bodies: &[Body]) -> [f64; 3] {
     let mut total = [0.0; 3];
     for b in bodies {
-        total = add(total, cross(b.r, b.v));
+        total = add(total, smul(b.m, cross(b.r, b.v)));
+    }
+    total
+}
+
+/// Linear momentum Σ m v.
+pub fn total_linear_momentum(bodies: &[Body]) -> [f64; 3] {
+    let mut total = [0.0; 3];
+    for b in bodies {
+        total = add(total, smul(b.m, b.v));
     }
     total
 }
 
+/// Position and velocity of the centre of mass.
+pub fn centre_of_mass(bodies: &[Body]) -> ([f64; 3], [f64; 3]) {
+    let mass: f64 = bodies.iter().map(|b| b.m).sum();
+    let mut r = [0.0; 3];
+    let mut v = [0.0; 3];
+    for b in bodies {
+        r = add(r, smul(b.m / mass, b.r));
+        v = add(v, smul(b.m / mass, b.v));
+    }
+    (r, v)
+}
+
+/// Moment of inertia about the centre of mass, Σ m |r - R|².
+pub fn moment_of_inertia(bodies: &[Body]) -> f64 {
+    let (centre, _) = centre_of_mass(bodies);
+    bodies
+        .iter()
+        .map(|b| {
+            let d = sub(b.r, centre);
+            b.m * dot(d, d)
+        })
+        .sum()
+}
+
+/// Virial ratio 2K / |U|; it averages to 1 over a periodic orbit.
+pub fn virial_ratio(bodies: &[Body]) -> f64 {
+    2.0 * kinetic_energy(bodies) / potential_energy(bodies).abs()
+}
+
+/// Sundman's scale-invariant c² E, with c the angular momentum about the centre of mass.
+pub fn sundman_invariant(bodies: &[Body]) -> f64 {
+    let (r, v) = centre_of_mass(bodies);
+    let mut c = [0.0; 3];
+    for b in bodies {
+        c = add(c, smul(b.m, cross(sub(b.r, r), sub(b.v, v))));
+    }
+    dot(c, c) * total_energy(bodies)
+}
+
+#[inline]
+pub fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
 #[inline]
 pub fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
     [
@@ -54,27 +109,89 @@ pub fn smul(s: f64, a: [f64; 3]) -> [f64; 3] {
     [s * a[0], s * a[1], s * a[2]]
 }
 
-/// Compute accelerations a_i = Σ_{j≠i} m_j r_ij / |r_ij|^3  (G = 1)
+/// Accelerations of a pair (G = 1): returns (a_i from j, a_j from i).
+/// a_i = m_j r_ij / |r_ij|^3 and, by Newton's third law, a_j = -m_i r_ij / |r_ij|^3.
 /// Optional Plummer softening via eps2 to avoid singularities in close encounters.
-pub fn accelerations(positions: &[[f64; 3]], masses: &[f64], eps2: f64) -> Vec<[f64; 3]> {
-    let n = positions.len();
-    let mut a = vec![[0.0; 3]; n];
+#[inline]
+pub fn pair_accelerations(
+    ri: [f64; 3],
+    rj: [f64; 3],
+    mi: f64,
+    mj: f64,
+    eps2: f64,
+) -> ([f64; 3], [f64; 3]) {
+    let rij = sub(rj, ri);
+    let r2 = rij[0] * rij[0] + rij[1] * rij[1] + rij[2] * rij[2] + eps2;
+    let inv_r3 = 1.0 / (r2 * r2.sqrt());
+    (smul(mj * inv_r3, rij), smul(-mi * inv_r3, rij))
+}
 
+/// Compute accelerations a_i = Σ_{j≠i} m_j r_ij / |r_ij|^3  (G = 1) into `out`.
+/// Each pair is evaluated once and applied to both bodies.
+pub fn accelerations_into(positions: &[[f64; 3]], masses: &[f64], eps2: f64, out: &mut [[f64; 3]]) {
+    let n = positions.len();
+    out.fill([0.0; 3]);
     for i in 0..n {
-        let pi = positions[i];
-        let mut ai = [0.0; 3];
-        for j in 0..n {
-            if i == j {
-                continue;
+        for j in (i + 1)..n {
+            let (ai, aj) =
+                pair_accelerations(positions[i], positions[j], masses[i], masses[j], eps2);
+            out[i] = add(out[i], ai);
+            out[j] = add(out[j], aj);
+        }
+    }
+}
+
+/// Tidal tensor of a pair: K = I / |r_ij|^3 - 3 r_ij r_ij^T / |r_ij|^5 (softened like
+/// `pair_accelerations`). It is the Jacobian of r_ij / |r_ij|^3 with respect to r_j, so
+/// ∂a_i/∂r_j = m_j K, ∂a_j/∂r_i = m_i K, and the diagonal blocks get -m_j K and -m_i K.
+#[inline]
+pub fn pair_jacobian(ri: [f64; 3], rj: [f64; 3], eps2: f64) -> [[f64; 3]; 3] {
+    let rij = sub(rj, ri);
+    let r2 = rij[0] * rij[0] + rij[1] * rij[1] + rij[2] * rij[2] + eps2;
+    let inv_r3 = 1.0 / (r2 * r2.sqrt());
+    let inv_r5 = inv_r3 / r2;
+    let mut k = [[0.0; 3]; 3];
+    for (c, row) in k.iter_mut().enumerate() {
+        for (d, x) in row.iter_mut().enumerate() {
+            *x = -3.0 * rij[c] * rij[d] * inv_r5;
+        }
+        row[c] += inv_r3;
+    }
+    k
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pairwise_accelerations_match_the_direct_sum() {
+        let positions = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.5, 0.0],
+            [-0.3, 1.2, 0.4],
+            [0.7, -0.8, 0.2],
+        ];
+        let masses = [1.0, 0.3, 2.0, 0.7];
+        let eps2 = 1e-3;
+        let mut a = [[0.0; 3]; 4];
+        accelerations_into(&positions, &masses, eps2, &mut a);
+        for i in 0..4 {
+            let mut direct = [0.0; 3];
+            for j in (0..4).filter(|&j| j != i) {
+                let rij = sub(positions[j], positions[i]);
+                let r2 = dot(rij, rij) + eps2;
+                direct = add(direct, smul(masses[j] / (r2 * r2.sqrt()), rij));
+            }
+            for (a, d) in a[i].iter().zip(direct) {
+                assert!((a - d).abs() < 1e-14, "body {i}");
             }
-            let rij = sub(positions[j], pi);
-            let r2 = rij[0] * rij[0] + rij[1] * rij[1] + rij[2] * rij[2] + eps2;
-            let r = r2.sqrt();
-            let r3 = r2 * r;
-            // G = 1, so a_i += m_j * r_ij / |r_ij|^3
-            ai = add(ai, smul(masses[j] / r3, rij));
         }
-        a[i] = ai;
+        // Newton's third law: Σ m a = 0
+        let mut force = [0.0; 3];
+        for (m, a) in masses.iter().zip(a) {
+            force = add(force, smul(*m, a));
+        }
+        assert!(force.iter().all(|f| f.abs() < 1e-14), "{force:?}");
     }
-    a
 }
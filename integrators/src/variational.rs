@@ -0,0 +1,155 @@
+// Variational equations and the monodromy matrix.
+//
+// Alongside y = [r0, v0, r1, v1, ...] we integrate the state-transition matrix
+// Φ(t) = ∂y(t)/∂y(0), with Φ' = A(y) Φ and Φ(0) = I, where
+//
+//     A = | 0      I |   (per body, in the packed 6N ordering)
+//         | ∂a/∂r  0 |
+//
+// and ∂a/∂r comes from the analytic pair Jacobian (`utils::pair_jacobian`). The augmented
+// state is [y, Φ] with Φ stored row-major, 6N + (6N)^2 values. Over one period Φ(T) is
+// the monodromy matrix; its eigenvalues are the Floquet multipliers.
+
+use crate::{
+    adaptive::AdaptiveSettings, dop853, erk, feagin14, linalg, method::Method, types::Body,
+    utils::pair_jacobian,
+};
+
+/// Multipliers with |λ| <= 1 + STABILITY_TOL count as lying on the unit circle.
+/// The trivial multipliers at 1 (energy, momentum, rotations) come in Jordan blocks,
+/// so integration errors of size δ spread them by ~sqrt(δ); this leaves room for that.
+pub const STABILITY_TOL: f64 = 1e-3;
+
+/// Augmented initial state [y(0), I].
+pub(crate) fn pack_augmented(bodies: &[Body]) -> Vec<f64> {
+    let dim = 6 * bodies.len();
+    let mut y = erk::pack_state(bodies);
+    y.resize(dim + dim * dim, 0.0);
+    for p in 0..dim {
+        y[dim + p * dim + p] = 1.0;
+    }
+    y
+}
+
+/// ∂a/∂r (3N×3N, row-major) at the positions of the packed state `y`.
+pub(crate) fn jacobian_into(y: &[f64], masses: &[f64], eps2: f64, jac: &mut [f64]) {
+    let n = masses.len();
+    let w = 3 * n;
+    jac.fill(0.0);
+    for i in 0..n {
+        let ri = [y[6 * i], y[6 * i + 1], y[6 * i + 2]];
+        for j in (i + 1)..n {
+            let rj = [y[6 * j], y[6 * j + 1], y[6 * j + 2]];
+            let k = pair_jacobian(ri, rj, eps2);
+            for c in 0..3 {
+                for d in 0..3 {
+                    let kcd = k[c][d];
+                    jac[(3 * i + c) * w + 3 * j + d] += masses[j] * kcd;
+                    jac[(3 * i + c) * w + 3 * i + d] -= masses[j] * kcd;
+                    jac[(3 * j + c) * w + 3 * i + d] += masses[i] * kcd;
+                    jac[(3 * j + c) * w + 3 * j + d] -= masses[i] * kcd;
+                }
+            }
+        }
+    }
+}
+
+/// d/dt [y, Φ] = [f(y), A(y) Φ]. `jac` is scratch space for 3N×3N values.
+pub(crate) fn deriv_into(y: &[f64], masses: &[f64], eps2: f64, jac: &mut [f64], dy: &mut [f64]) {
+    let n = masses.len();
+    let dim = 6 * n;
+    erk::deriv_into(&y[..dim], masses, eps2, &mut dy[..dim]);
+    jacobian_into(y, masses, eps2, jac);
+
+    let (phi, dphi) = (&y[dim..], &mut dy[dim..]);
+    let w = 3 * n;
+    for i in 0..n {
+        for c in 0..3 {
+            // position rows: d/dt Φ_r = Φ_v
+            let (pr, pv) = (6 * i + c, 6 * i + 3 + c);
+            dphi[pr * dim..(pr + 1) * dim].copy_from_slice(&phi[pv * dim..(pv + 1) * dim]);
+
+            // velocity rows: d/dt Φ_v = Σ_j ∂a_i/∂r_j Φ_rj
+            let row = &mut dphi[pv * dim..(pv + 1) * dim];
+            row.fill(0.0);
+            for j in 0..n {
+                for d in 0..3 {
+                    let a = jac[(3 * i + c) * w + 3 * j + d];
+                    if a != 0.0 {
+                        let src = &phi[(6 * j + d) * dim..(6 * j + d + 1) * dim];
+                        erk::saxpy_into(row, a, src);
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn deriv(y: &[f64], masses: &[f64], eps2: f64) -> Vec<f64> {
+    let n = masses.len();
+    let mut jac = vec![0.0; 9 * n * n];
+    let mut dy = vec![0.0; y.len()];
+    deriv_into(y, masses, eps2, &mut jac, &mut dy);
+    dy
+}
+
+/// Monodromy matrix of a (candidate) periodic orbit and its Floquet multipliers.
+#[derive(Clone, Debug)]
+pub struct Monodromy {
+    /// Dimension of the phase space, 6N.
+    pub dim: usize,
+    /// Φ(T) = ∂y(T)/∂y(0), dim×dim row-major, in the packed order [r0, v0, r1, v1, ...].
+    pub matrix: Vec<f64>,
+    /// Eigenvalues of `matrix` as (re, im), by decreasing modulus.
+    pub multipliers: Vec<(f64, f64)>,
+    /// Largest |multiplier|.
+    pub max_modulus: f64,
+    /// Linearly stable: every multiplier on the unit circle (within `STABILITY_TOL`).
+    pub stable: bool,
+}
+
+impl Monodromy {
+    /// Eigen-decompose Φ(T) and classify it.
+    pub fn from_matrix(matrix: Vec<f64>, dim: usize) -> Result<Self, String> {
+        let multipliers = linalg::eigenvalues(&matrix, dim)?;
+        let max_modulus = multipliers
+            .iter()
+            .map(|&(re, im)| re.hypot(im))
+            .fold(0.0, f64::max);
+        Ok(Monodromy {
+            dim,
+            matrix,
+            multipliers,
+            max_modulus,
+            stable: max_modulus <= 1.0 + STABILITY_TOL,
+        })
+    }
+}
+
+/// Integrate the variational equations over one `period` and return the monodromy
+/// matrix with its multipliers. Only DOP853 and Feagin14 carry the variational system;
+/// `settings` replaces the method's defaults.
+pub fn monodromy(
+    bodies: &[Body],
+    period: f64,
+    method: Method,
+    settings: Option<&AdaptiveSettings>,
+) -> Result<Monodromy, String> {
+    let mut end = bodies.to_vec();
+    let s = |default: AdaptiveSettings| settings.copied().unwrap_or(default);
+    let (matrix, t) = match method {
+        Method::Dop853 => dop853::evolve_variational(&mut end, period, &s(dop853::SETTINGS)),
+        Method::Feagin14 => feagin14::evolve_variational(&mut end, period, &s(feagin14::SETTINGS)),
+        _ => {
+            return Err(format!(
+                "Variational equations are only available for dop853 and feagin14, not {method}"
+            ));
+        }
+    };
+    if (t - period).abs() > 1e-12 * period.abs().max(1.0) {
+        return Err(format!(
+            "Integration stopped at t = {t} before the period {period}"
+        ));
+    }
+    Monodromy::from_matrix(matrix, 6 * bodies.len())
+}
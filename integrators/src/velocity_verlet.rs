@@ -2,22 +2,52 @@
 
 use crate::{
     types::Body,
-    utils::{accelerations, add, smul},
+    utils::{accelerations_into, add, smul},
 };
 
+/// Scratch buffers for the Verlet steps, allocated once per run.
+struct Workspace {
+    m: Vec<f64>,
+    r: Vec<[f64; 3]>,
+    a: Vec<[f64; 3]>,
+    // `a` holds the accelerations at the current positions
+    a_valid: bool,
+}
+
+impl Workspace {
+    fn new(bodies: &[Body]) -> Self {
+        let n = bodies.len();
+        Workspace {
+            m: bodies.iter().map(|b| b.m).collect(),
+            r: vec![[0.0; 3]; n],
+            a: vec![[0.0; 3]; n],
+            a_valid: false,
+        }
+    }
+
+    fn update_accelerations(&mut self, bodies: &[Body], eps2: f64) {
+        for (r, b) in self.r.iter_mut().zip(bodies.iter()) {
+            *r = b.r;
+        }
+        accelerations_into(&self.r, &self.m, eps2, &mut self.a);
+        self.a_valid = true;
+    }
+}
+
 /// One velocity–Verlet step (KDK):
 /// v(t+dt/2) = v(t) + (dt/2) a(r(t))
 /// r(t+dt)   = r(t) + dt * v(t+dt/2)
 /// v(t+dt)   = v(t+dt/2) + (dt/2) a(r(t+dt))
-fn step_velocity_verlet(bodies: &mut [Body], dt: f64, eps2: f64) {
-    let m: Vec<f64> = bodies.iter().map(|b| b.m).collect();
-
+///
+/// a(r(t+dt)) is kept in the workspace and reused as a(r(t)) by the next step.
+fn step_velocity_verlet(bodies: &mut [Body], dt: f64, eps2: f64, ws: &mut Workspace) {
     // a(t)
-    let r0: Vec<[f64; 3]> = bodies.iter().map(|b| b.r).collect();
-    let a0 = accelerations(&r0, &m, eps2);
+    if !ws.a_valid {
+        ws.update_accelerations(bodies, eps2);
+    }
 
     // Kick (half)
-    for (body, a) in bodies.iter_mut().zip(a0.iter()) {
+    for (body, a) in bodies.iter_mut().zip(ws.a.iter()) {
         body.v = add(body.v, smul(0.5 * dt, *a));
     }
 
@@ -27,25 +57,24 @@ fn step_velocity_verlet(bodies: &mut [Body], dt: f64, eps2: f64) {
     }
 
     // a(t+dt)
-    let r1: Vec<[f64; 3]> = bodies.iter().map(|b| b.r).collect();
-    let a1 = accelerations(&r1, &m, eps2);
+    ws.update_accelerations(bodies, eps2);
 
     // Kick (half)
-    for (body, a) in bodies.iter_mut().zip(a1.iter()) {
+    for (body, a) in bodies.iter_mut().zip(ws.a.iter()) {
         body.v = add(body.v, smul(0.5 * dt, *a));
     }
 }
 
 // put near your verlet code
-fn step_sym4(bodies: &mut [Body], h: f64, eps2: f64) {
+fn step_sym4(bodies: &mut [Body], h: f64, eps2: f64, ws: &mut Workspace) {
     // Yoshida 4th-order coefficients
     const CBR2: f64 = 1.259_921_049_894_873_2; // 2^(1/3)
     const DEN: f64 = 2.0 - CBR2;
     const W1: f64 = 1.0 / DEN; // ≈ 1.3512071919596578
     const W2: f64 = -CBR2 / DEN; // ≈ -1.7024143839193153
-    step_velocity_verlet(bodies, W1 * h, eps2);
-    step_velocity_verlet(bodies, W2 * h, eps2);
-    step_velocity_verlet(bodies, W1 * h, eps2);
+    step_velocity_verlet(bodies, W1 * h, eps2, ws);
+    step_velocity_verlet(bodies, W2 * h, eps2, ws);
+    step_velocity_verlet(bodies, W1 * h, eps2, ws);
 }
 
 /// Integrate from t=0 to t=t_end with fixed step dt.
@@ -54,22 +83,23 @@ fn step_sym4(bodies: &mut [Body], h: f64, eps2: f64) {
 pub fn evolve(bodies: &mut [Body], t_end: f64) -> (Vec<f64>, f64) {
     let dt = 1e-5;
     let eps2 = 0.0; // 1e-8;
+    let mut ws = Workspace::new(bodies);
     let mut t = 0.0;
-    let mut result = Vec::new();
     // Do whole steps
     let steps = (t_end / dt).floor() as usize;
+    let mut result = Vec::with_capacity(steps * 10);
     for _ in 0..steps {
-        result.append(&mut bodies[0].r.to_vec());
-        result.append(&mut bodies[1].r.to_vec());
-        result.append(&mut bodies[2].r.to_vec());
+        result.extend_from_slice(&bodies[0].r);
+        result.extend_from_slice(&bodies[1].r);
+        result.extend_from_slice(&bodies[2].r);
         result.push(t);
-        step_sym4(bodies, dt, eps2);
+        step_sym4(bodies, dt, eps2, &mut ws);
         t += dt;
     }
     // Optional final partial step
     let rem = t_end - t;
     if rem > 0.0 {
-        step_sym4(bodies, rem, eps2);
+        step_sym4(bodies, rem, eps2, &mut ws);
         t = t_end;
     }
     (result, t)
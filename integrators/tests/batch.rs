@@ -0,0 +1,68 @@
+mod common;
+
+use common::{FIG8_PERIOD, fig8, unequal};
+use three_body::{
+    BS32_SETTINGS, Body, CASH_KARP_SETTINGS, DOP853_SETTINGS, DOPRI5_SETTINGS, evolve_bs32_batch,
+    evolve_bs32_with, evolve_cash_karp_batch, evolve_cash_karp_with, evolve_dop853_batch,
+    evolve_dop853_with, evolve_dopri5_batch, evolve_dopri5_with,
+};
+
+type Single = fn(&mut [Body], f64, &three_body::AdaptiveSettings) -> (Vec<f64>, f64);
+type Batch = fn(&mut [Vec<Body>], &[f64], &three_body::AdaptiveSettings) -> Vec<(Vec<f64>, f64)>;
+
+#[test]
+fn lockstep_matches_single_runs() {
+    let methods: [(&str, Single, Batch, three_body::AdaptiveSettings); 4] = [
+        (
+            "dop853",
+            evolve_dop853_with,
+            evolve_dop853_batch,
+            DOP853_SETTINGS,
+        ),
+        (
+            "dopri5",
+            evolve_dopri5_with,
+            evolve_dopri5_batch,
+            DOPRI5_SETTINGS,
+        ),
+        ("bs32", evolve_bs32_with, evolve_bs32_batch, BS32_SETTINGS),
+        (
+            "cash_karp",
+            evolve_cash_karp_with,
+            evolve_cash_karp_batch,
+            CASH_KARP_SETTINGS,
+        ),
+    ];
+    // Different systems and end times, so the lanes take different steps and finish apart
+    let starts = [fig8(), unequal(), fig8()];
+    let t_end = [FIG8_PERIOD, 1.5, 0.25 * FIG8_PERIOD];
+    for (name, single, batch, settings) in methods {
+        let mut systems = starts.to_vec();
+        let results = batch(&mut systems, &t_end, &settings);
+        assert_eq!(results.len(), starts.len());
+        for (s, (series, t)) in results.iter().enumerate() {
+            let mut alone = starts[s].clone();
+            let (series_alone, t_alone) = single(&mut alone, t_end[s], &settings);
+            assert_eq!(*t, t_alone, "{name}, system {s}");
+            assert_eq!(
+                series.len(),
+                series_alone.len(),
+                "{name}, system {s}: step count"
+            );
+            for (x, y) in series.iter().zip(&series_alone) {
+                assert!(
+                    (x - y).abs() <= 1e-12 * y.abs().max(1.0),
+                    "{name}, system {s}"
+                );
+            }
+            for (a, b) in systems[s].iter().zip(&alone) {
+                assert!((0..3).all(|k| (a.r[k] - b.r[k]).abs() < 1e-12));
+            }
+        }
+    }
+}
+
+#[test]
+fn empty_batch() {
+    assert!(evolve_dopri5_batch(&mut [], &[], &DOPRI5_SETTINGS).is_empty());
+}
@@ -0,0 +1,106 @@
+mod common;
+
+use std::str::FromStr;
+
+use common::distance;
+use three_body::{BigDecimal, Method, Stability, ic, parse_free_fall, parse_li_liao_3d};
+
+const DATA_CSV: &str = include_str!("../../docs/examples/data.csv");
+const SD_80: &str = include_str!("../../docs/examples/sd_80.csv");
+
+#[test]
+fn li_liao_row_matches_its_initial_conditions() {
+    let header = DATA_CSV.lines().next().unwrap();
+    let row = DATA_CSV.lines().nth(1).unwrap();
+    let entries = parse_li_liao_3d(&format!("{header}\n{row}\n")).unwrap();
+    assert_eq!(entries.len(), 1);
+    let entry = &entries[0];
+    assert_eq!(entry.name, "O_{1}(0.1)");
+    assert_eq!(entry.masses, [1.0, 1.0, 0.1]);
+    assert_eq!(entry.stability, Some(Stability::Unstable));
+    assert_eq!(entry.scaled_period, None);
+    assert_eq!(
+        entry.period,
+        BigDecimal::from_str("8.59173325042564").unwrap()
+    );
+    assert_eq!(
+        entry.state[14],
+        BigDecimal::from_str("0.578304539968228").unwrap()
+    );
+
+    // The same bodies as the f64 builder, and an orbit that closes
+    let bodies = ic::li_liao_3d(
+        5.78304539968228E-01,
+        2.99949028598560E-02,
+        3.53757085402933E-02,
+        1.66254073966839E-02,
+        0.1,
+    );
+    assert!(distance(&entry.bodies, &bodies) < 1e-15);
+    let mut end = entry.bodies.clone();
+    Method::Dop853.evolve(&mut end, 8.59173325042564, None);
+    assert!(distance(&end, &entry.bodies) < 1e-8);
+}
+
+#[test]
+fn free_fall_row_keeps_every_digit() {
+    let row = SD_80.lines().next().unwrap();
+    let entries = parse_free_fall(row, "sd_80").unwrap();
+    assert_eq!(entries.len(), 1);
+    let entry = &entries[0];
+    assert_eq!(entry.name, "sd_80/1");
+    assert_eq!(entry.stability, None);
+
+    // All 80 digits of each field survive
+    let fields: Vec<BigDecimal> = row
+        .split(',')
+        .map(|f| BigDecimal::from_str(f).unwrap())
+        .collect();
+    assert_eq!(entry.state[6], fields[0]);
+    assert_eq!(entry.state[7], fields[1]);
+    assert_eq!(entry.period, fields[2]);
+    assert_eq!(entry.scaled_period.as_ref(), Some(&fields[3]));
+    assert_eq!(fields[0].digits(), 80);
+
+    let bodies = ic::free_fall(0.0026853656879186095, 0.4205139234134632, [1.0; 3]);
+    assert!(distance(&entry.bodies, &bodies) < 1e-15);
+}
+
+#[test]
+fn whole_files_parse() {
+    let entries = parse_li_liao_3d(DATA_CSV).unwrap();
+    assert_eq!(
+        entries.len(),
+        DATA_CSV
+            .lines()
+            .skip(1)
+            .filter(|l| !l.trim().is_empty())
+            .count()
+    );
+    assert!(
+        entries
+            .iter()
+            .all(|e| e.masses[2] > 0.0 && e.state.len() == 18)
+    );
+    let entries = parse_free_fall(SD_80, "sd_80").unwrap();
+    assert_eq!(entries.len(), SD_80.lines().count());
+    assert_eq!(
+        entries.last().unwrap().name,
+        format!("sd_80/{}", entries.len())
+    );
+}
+
+#[test]
+fn malformed_rows_name_their_line() {
+    let header = "O_{index}(m_3)\tz_0\tv_x\tv_y\tv_z\tT\tstability\n";
+    let err = parse_li_liao_3d(&format!("{header}O_{{1}}(0.1)\t1\t2\t3\t4\t5\n")).unwrap_err();
+    assert!(err.starts_with("line 2: expected 7"), "{err}");
+    let err = parse_li_liao_3d(&format!("{header}O_{{1}}(0.1)\t1\t2\t3\t4\t5\tX\n")).unwrap_err();
+    assert!(err.contains("S or U"), "{err}");
+    let err = parse_li_liao_3d(&format!("{header}O_{{1}}(0)\t1\t2\t3\t4\t5\tS\n")).unwrap_err();
+    assert!(err.contains("positive"), "{err}");
+    let err = parse_free_fall("0.1,0.2,3\n", "sd_80").unwrap_err();
+    assert!(err.starts_with("line 1: expected 4"), "{err}");
+    let err = parse_free_fall("0.1,zero,3,4\n", "sd_80").unwrap_err();
+    assert!(err.contains("bad number"), "{err}");
+}
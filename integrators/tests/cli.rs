@@ -0,0 +1,557 @@
+// End-to-end runs of `orbit-plot` (needs `--features cli`).
+#![cfg(feature = "cli")]
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// Run `orbit-plot` in the crate directory; panics with its stderr if it fails.
+fn orbit_plot(args: &[&str]) -> String {
+    let out = Command::new(env!("CARGO_BIN_EXE_orbit-plot"))
+        .args(args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "orbit-plot {args:?} failed:\n{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    String::from_utf8(out.stdout).unwrap()
+}
+
+/// Run `orbit-plot` expecting it to fail; returns its stderr.
+fn orbit_plot_fails(args: &[&str]) -> String {
+    let out = Command::new(env!("CARGO_BIN_EXE_orbit-plot"))
+        .args(args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .unwrap();
+    assert!(!out.status.success(), "orbit-plot {args:?} succeeded");
+    String::from_utf8(out.stderr).unwrap()
+}
+
+/// Fresh scratch directory for one test.
+fn scratch(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("orbit-plot-{}-{test}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn path(p: &Path) -> &str {
+    p.to_str().unwrap()
+}
+
+#[test]
+fn batch_writes_one_row_per_orbit() {
+    let dir = scratch("batch");
+    let csv = dir.join("summary.csv");
+    orbit_plot(&["batch", "batch.toml", "-j", "2", "-o", path(&csv)]);
+    let text = fs::read_to_string(&csv).unwrap();
+    let rows: Vec<&str> = text.lines().collect();
+    assert!(rows[0].starts_with("index,name,status"));
+    let orbits = fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("batch.toml"))
+        .unwrap()
+        .matches("[[orbit]]")
+        .count();
+    assert_eq!(rows.len(), orbits + 1);
+    for (i, row) in rows[1..].iter().enumerate() {
+        assert!(row.starts_with(&format!("{i},")), "{row}");
+    }
+    assert!(rows[1].starts_with("0,figure-eight,ok,"));
+}
+
+/// Figure-eight plot config in `dir`, with `extra` lines ahead of the bodies.
+fn fig8_config(dir: &Path, extra: &str) -> PathBuf {
+    let file = dir.join("fig8.toml");
+    let toml = format!(
+        "method = \"dop853\"\nperiod = 6.32591398\noutput = \"{}\"\n{extra}\n\
+         [[body]]\nmass = 1.0\nr = [-0.97000436, 0.24308753, 0.0]\nv = [0.466203685, 0.43236573, 0.0]\n\
+         [[body]]\nmass = 1.0\nr = [0.97000436, -0.24308753, 0.0]\nv = [0.466203685, 0.43236573, 0.0]\n\
+         [[body]]\nmass = 1.0\nr = [0.0, 0.0, 0.0]\nv = [-0.93240737, -0.86473146, 0.0]\n",
+        path(&dir.join("fig8.png"))
+    );
+    fs::write(&file, toml).unwrap();
+    file
+}
+
+#[test]
+fn stability_of_the_figure_eight() {
+    let dir = scratch("stability");
+    let out = orbit_plot(&["stability", path(&fig8_config(&dir, ""))]);
+    assert_eq!(
+        out.lines().filter(|l| l.starts_with("  ")).count(),
+        18,
+        "{out}"
+    );
+    assert!(out.contains("S (linearly stable)"), "{out}");
+}
+
+#[test]
+fn refine_prints_a_closed_config() {
+    let dir = scratch("refine");
+    let out = orbit_plot(&["refine", path(&fig8_config(&dir, ""))]);
+    assert!(out.contains(" Newton steps, converged)"), "{out}");
+    assert!(out.contains("period = 6.32591"), "{out}");
+    assert_eq!(out.matches("[[body]]").count(), 3);
+}
+
+#[test]
+fn continued_family_runs_as_a_batch() {
+    let dir = scratch("continue");
+    let config = fig8_config(&dir, "");
+    let out = orbit_plot(&["continue", path(&config), "-p", "energy", "--points", "3"]);
+    assert_eq!(out.lines().count(), 4, "{out}");
+    assert!(out.starts_with("index,parameter,period,"));
+
+    let family = dir.join("family.toml");
+    let csv = dir.join("family.csv");
+    orbit_plot(&[
+        "continue",
+        path(&config),
+        "-p",
+        "energy",
+        "--points",
+        "3",
+        "-o",
+        path(&family),
+    ]);
+    orbit_plot(&["batch", path(&family), "-o", path(&csv)]);
+    let rows = fs::read_to_string(&csv).unwrap();
+    assert_eq!(rows.lines().count(), 4, "{rows}");
+    assert!(rows.lines().skip(1).all(|r| r.contains(",ok,")), "{rows}");
+}
+
+#[test]
+fn search_candidates_refine_to_periodic_orbits() {
+    let dir = scratch("search");
+    let out = orbit_plot(&[
+        "search",
+        "--vx",
+        "0.33:0.36:16",
+        "--vy",
+        "0.52:0.545:16",
+        "--t-max",
+        "8",
+        "-n",
+        "2",
+        "--configs",
+        path(&dir),
+    ]);
+    let rows: Vec<&str> = out.lines().collect();
+    assert_eq!(rows.len(), 3, "{out}");
+    assert!(rows[1].starts_with("0,0.342,0.535,7.269"), "{out}");
+
+    let refined = orbit_plot(&["refine", path(&dir.join("candidate-00.toml"))]);
+    assert!(refined.contains(", converged)"), "{refined}");
+}
+
+#[test]
+fn free_fall_finds_the_first_sd_80_orbit() {
+    let out = orbit_plot(&[
+        "free-fall",
+        "--x",
+        "0.0026:0.0028:3",
+        "--y",
+        "0.4204:0.4206:3",
+    ]);
+    let rows: Vec<Vec<f64>> = out
+        .lines()
+        .map(|l| l.split(',').map(|v| v.parse().unwrap()).collect())
+        .collect();
+    assert_eq!(rows.len(), 1, "{out}");
+    // docs/examples/sd_80.csv, first row
+    let want = [2.6853656879e-3, 0.42051392341, 1.7803915330, 14.571737414];
+    for (got, want) in rows[0].iter().zip(want) {
+        assert!((got - want).abs() < 1e-7 * want.max(1.0), "{out}");
+    }
+}
+
+#[test]
+fn topology_groups_configs_by_class() {
+    let dir = scratch("topology");
+    let a = fig8_config(&dir, "");
+    let b = dir.join("copy.toml");
+    fs::copy(&a, &b).unwrap();
+    let out = orbit_plot(&["topology", path(&a), path(&b)]);
+    assert_eq!(out.matches("6 syzygies").count(), 2, "{out}");
+    assert!(out.contains("class abAB"), "{out}");
+    assert!(out.contains("Same class:\n  abAB: "), "{out}");
+}
+
+#[test]
+fn shape_sphere_projection_plots_a_png() {
+    let dir = scratch("shape-sphere");
+    let config = fig8_config(&dir, "projection = \"shape_sphere\"");
+    orbit_plot(&["-c", path(&config)]);
+    let png = fs::read(dir.join("fig8.png")).unwrap();
+    assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+}
+
+#[test]
+fn period_of_the_figure_eight() {
+    let dir = scratch("period");
+    let out = orbit_plot(&["period", path(&fig8_config(&dir, "")), "-n", "1"]);
+    let rows: Vec<&str> = out.lines().collect();
+    assert_eq!(rows.len(), 2, "{out}");
+    assert!(rows[1].trim_start().starts_with("6.32591"), "{out}");
+}
+
+#[test]
+fn symmetry_lists_the_group_of_the_figure_eight() {
+    let dir = scratch("symmetry");
+    let config = fig8_config(&dir, "");
+    let out = orbit_plot(&["symmetry", path(&config)]);
+    let mut lines = out.lines();
+    assert_eq!(
+        lines.next().unwrap(),
+        format!(
+            "{}: order 12: choreography, reflection, time reversal",
+            path(&config)
+        )
+    );
+    assert_eq!(lines.filter(|l| l.contains("σ = (")).count(), 11, "{out}");
+}
+
+#[test]
+fn diagnostics_compare_methods() {
+    let dir = scratch("diagnostics");
+    let csv = dir.join("drift.csv");
+    let out = orbit_plot(&[
+        "diagnostics",
+        path(&fig8_config(&dir, "")),
+        "--methods",
+        "dop853,verlet",
+        "--samples",
+        "10",
+        "-o",
+        path(&csv),
+    ]);
+    let rows: Vec<&str> = out.lines().collect();
+    assert!(
+        rows[1].starts_with("dop853 ") && rows[2].starts_with("verlet "),
+        "{out}"
+    );
+    let series = fs::read_to_string(&csv).unwrap();
+    assert!(series.starts_with("method,t,energy,"));
+    assert_eq!(series.lines().count(), 1 + 2 * 11);
+}
+
+#[test]
+fn normalized_config_has_the_scaled_period() {
+    let dir = scratch("normalize");
+    let out = orbit_plot(&[
+        "normalize",
+        path(&fig8_config(&dir, "")),
+        "--energy",
+        "-0.5",
+    ]);
+    assert!(out.contains("# energy = -0.5\n"), "{out}");
+    let config = dir.join("normalized.toml");
+    fs::write(&config, &out).unwrap();
+    // 6.32591398 × (1.28714199 / 0.5)^(3/2)
+    let periods = orbit_plot(&["period", path(&config), "-n", "1", "--t-max", "30"]);
+    assert!(
+        periods.lines().nth(1).unwrap().contains(" 26.12810"),
+        "{periods}"
+    );
+}
+
+#[test]
+fn coordinates_as_csv() {
+    let dir = scratch("coordinates");
+    let config = fig8_config(&dir, "");
+    let out = orbit_plot(&["coordinates", path(&config), "--samples", "4"]);
+    let rows: Vec<&str> = out.lines().collect();
+    assert!(rows[0].starts_with("t,radius,alpha,"), "{out}");
+    assert_eq!(rows.len(), 6);
+    // R² = I = 2 at the start of the figure-eight
+    let radius: f64 = rows[1].split(',').nth(1).unwrap().parse().unwrap();
+    assert!((radius - 2f64.sqrt()).abs() < 1e-7);
+
+    let out = orbit_plot(&[
+        "coordinates",
+        path(&config),
+        "--system",
+        "jacobi",
+        "--samples",
+        "4",
+    ]);
+    assert!(out.starts_with("t,rho_x,"));
+    assert_eq!(out.lines().count(), 6);
+}
+
+#[test]
+fn frames_from_the_config() {
+    let dir = scratch("frames");
+    for extra in [
+        "frame = \"pair\"\npair = [3, 1]",
+        "frame = \"rotating\"\ntheta = 1.0",
+        "frame = \"principal_axes\"",
+    ] {
+        let _ = fs::remove_file(dir.join("fig8.png"));
+        orbit_plot(&["-c", path(&fig8_config(&dir, extra))]);
+        assert!(dir.join("fig8.png").exists(), "{extra}");
+    }
+    let err = orbit_plot_fails(&["-c", path(&fig8_config(&dir, "frame = \"rotating\""))]);
+    assert!(err.contains("needs theta or omega"), "{err}");
+    let err = orbit_plot_fails(&[
+        "-c",
+        path(&fig8_config(&dir, "frame = \"pair\"\npair = [0, 1]")),
+    ]);
+    assert!(err.contains("pair lists bodies from 1"), "{err}");
+}
+
+#[test]
+fn catalog_import_convert_validate_and_query() {
+    let dir = scratch("catalog");
+    let toml = dir.join("equal_mass.toml");
+    let json = dir.join("equal_mass.json");
+    let again = dir.join("again.toml");
+    orbit_plot(&[
+        "catalog",
+        "import",
+        "--format",
+        "equal_mass",
+        "../docs/examples/equal_mass.js",
+        "-o",
+        path(&toml),
+    ]);
+    let text = fs::read_to_string(&toml).unwrap();
+    assert!(text.contains("name = \"I.Ai.c.1\"\nfamily = \"equal_mass\""));
+    assert!(text.contains("v = [-0.6942337762, -1.0654498908, 0.0]"));
+
+    // TOML -> JSON -> TOML is lossless
+    orbit_plot(&["catalog", "convert", path(&toml), "-o", path(&json)]);
+    orbit_plot(&["catalog", "convert", path(&json), "-o", path(&again)]);
+    assert_eq!(fs::read_to_string(&again).unwrap(), text);
+
+    let orbits = text.matches("[[orbit]]").count();
+    let out = orbit_plot(&["catalog", "validate", path(&toml)]);
+    assert_eq!(out, format!("{orbits} orbits, 0 with problems\n"));
+    // The same names twice over
+    let err = orbit_plot_fails(&["catalog", "validate", path(&toml), path(&json)]);
+    assert!(err.contains("validation failed"), "{err}");
+
+    let out = orbit_plot(&[
+        "catalog",
+        "query",
+        path(&toml),
+        "--family",
+        "equal_mass",
+        "--period",
+        "6:6.5",
+    ]);
+    let names: Vec<&str> = out
+        .lines()
+        .skip(1)
+        .filter_map(|l| l.split_whitespace().nth(1))
+        .collect();
+    assert_eq!(names, ["I.Ai.c.1", "I.Ai.c.2", "orbits"]);
+
+    // Free-fall rows keep their 80 digits
+    let sd = dir.join("sd_80.toml");
+    orbit_plot(&[
+        "catalog",
+        "import",
+        "--format",
+        "puzynin",
+        "../docs/examples/sd_80.csv",
+        "-o",
+        path(&sd),
+    ]);
+    let text = fs::read_to_string(&sd).unwrap();
+    assert!(text.contains(
+        "period = \"1.7803915330283836111234811629291376034257424759817740575116356605248530022321484\""
+    ));
+    let out = orbit_plot(&["catalog", "validate", path(&sd)]);
+    assert!(out.ends_with(" 0 with problems\n"));
+}
+
+#[test]
+fn catalog_verify_reports_closure() {
+    let dir = scratch("verify");
+    let all = dir.join("equal_mass.toml");
+    let two = dir.join("two.toml");
+    orbit_plot(&[
+        "catalog",
+        "import",
+        "--format",
+        "equal_mass",
+        "../docs/examples/equal_mass.js",
+        "-o",
+        path(&all),
+    ]);
+    orbit_plot(&[
+        "catalog",
+        "query",
+        path(&all),
+        "--period",
+        "6:6.5",
+        "-o",
+        path(&two),
+    ]);
+    let csv = orbit_plot(&["catalog", "verify", path(&two)]);
+    let rows: Vec<Vec<&str>> = csv.lines().map(|l| l.split(',').collect()).collect();
+    assert_eq!(rows[0][8], "status");
+    assert_eq!(rows.len(), 3);
+    for row in &rows[1..] {
+        assert_eq!(row[8], "pass");
+        assert!(row[6].parse::<f64>().unwrap() < 1e-8);
+        assert!(row[7].parse::<f64>().unwrap() < 1e-9);
+    }
+    let md = dir.join("verify.md");
+    orbit_plot(&["catalog", "verify", path(&two), "-o", path(&md)]);
+    let text = fs::read_to_string(&md).unwrap();
+    assert!(text.starts_with("| family | name | period |"));
+    assert!(text.contains("| equal_mass | I.Ai.c.1 | 6.3259139829 | 0 |"));
+
+    // BHH satellites close up to their rotation, to the five digits of their table
+    let bhh = dir.join("bhh.toml");
+    orbit_plot(&[
+        "catalog",
+        "import",
+        "--format",
+        "bhh_satellites",
+        "../docs/examples/bhh_satellites.js",
+        "-o",
+        path(&bhh),
+    ]);
+    let err = orbit_plot_fails(&["catalog", "verify", path(&bhh)]);
+    assert!(err.contains("orbits failed verification"), "{err}");
+    let csv = orbit_plot(&["catalog", "verify", path(&bhh), "--tol", "1e-2"]);
+    assert!(csv.lines().skip(1).all(|l| l.contains(",pass,")));
+}
+
+#[test]
+fn export_writes_every_format() {
+    let dir = scratch("export");
+    let config = fig8_config(&dir, "");
+    let csv = dir.join("fig8.csv");
+    orbit_plot(&["export", path(&config), "-o", path(&csv), "--samples", "4"]);
+    let text = fs::read_to_string(&csv).unwrap();
+    let rows: Vec<&str> = text.lines().collect();
+    assert_eq!(rows[0], "t,x1,y1,z1,x2,y2,z2,x3,y3,z3");
+    assert_eq!(rows.len(), 6);
+    assert!(rows[5].starts_with("6.32591398,-0.970004"));
+
+    let json = dir.join("fig8.json");
+    orbit_plot(&[
+        "export",
+        path(&config),
+        "-o",
+        path(&json),
+        "--samples",
+        "2",
+        "--velocities",
+        "--diagnostics",
+    ]);
+    let text = fs::read_to_string(&json).unwrap();
+    assert!(text.starts_with("{\n  \"columns\": [\"t\", \"x1\", \"y1\", \"z1\", \"vx1\""));
+    assert!(text.contains("\"energy\""));
+
+    let npy = dir.join("fig8.npy");
+    orbit_plot(&["export", path(&config), "-o", path(&npy), "--samples", "0"]);
+    let bytes = fs::read(&npy).unwrap();
+    assert_eq!(&bytes[..8], b"\x93NUMPY\x01\x00");
+    let npz = dir.join("fig8.npz");
+    orbit_plot(&["export", path(&config), "-o", path(&npz)]);
+    assert_eq!(&fs::read(&npz).unwrap()[..4], b"PK\x03\x04");
+
+    let err = orbit_plot_fails(&["export", path(&config), "-o", "fig8.txt"]);
+    assert!(err.contains("unknown format"), "{err}");
+    let err = orbit_plot_fails(&[
+        "export",
+        path(&config),
+        "-o",
+        path(&csv),
+        "--samples",
+        "0",
+        "--velocities",
+    ]);
+    assert!(err.contains("need --samples > 0"), "{err}");
+}
+
+#[test]
+fn animate_writes_a_looping_gif() {
+    let dir = scratch("animate");
+    let config = fig8_config(&dir, "width = 240\nheight = 180");
+    let gif = dir.join("fig8.gif");
+    let out = orbit_plot(&[
+        "animate",
+        path(&config),
+        "-o",
+        path(&gif),
+        "--fps",
+        "5",
+        "--duration",
+        "2",
+    ]);
+    assert!(out.contains("(10 frames)"), "{out}");
+    let bytes = fs::read(&gif).unwrap();
+    assert_eq!(&bytes[..6], b"GIF89a");
+    assert_eq!(bytes[6..10], [240, 0, 180, 0]);
+    assert!(bytes.windows(11).any(|w| w == b"NETSCAPE2.0"));
+    // One graphic control extension per frame, each with a delay of 20/100 s
+    let delays: Vec<u16> = bytes
+        .windows(8)
+        .filter(|w| w[..3] == [0x21, 0xf9, 0x04] && w[7] == 0)
+        .map(|w| u16::from_le_bytes([w[4], w[5]]))
+        .collect();
+    assert_eq!(delays, [20; 10]);
+
+    let err = orbit_plot_fails(&["animate", path(&config), "--fps", "0"]);
+    assert!(err.contains("must be positive"), "{err}");
+    let config = fig8_config(&dir, "projection = \"xz\"");
+    let err = orbit_plot_fails(&["animate", path(&config), "-o", path(&gif)]);
+    assert!(err.contains("xy projection"), "{err}");
+}
+
+#[test]
+fn plots_as_svg_and_pdf_with_styles() {
+    let dir = scratch("formats");
+    let out = dir.join("fig8.png");
+    let styled = "format = \"svg\"\nline_width = 2.5\ncolors = [\"#112233\"]\n\
+                  labels = [\"First\"]\ntitle = \"Figure eight\"";
+    orbit_plot(&["-c", path(&fig8_config(&dir, styled))]);
+    let svg = fs::read_to_string(&out).unwrap();
+    assert!(svg.starts_with("<svg "));
+    assert!(svg.contains("\nFigure eight\n</text>"));
+    assert!(svg.contains("\nFirst\n</text>") && svg.contains("\nBody 2\n</text>"));
+    assert!(svg.contains("stroke=\"#112233\""));
+    assert!(svg.contains("stroke-width=\"3\""));
+
+    orbit_plot(&[
+        "-c",
+        path(&fig8_config(&dir, "format = \"svg\"\nbare = true")),
+    ]);
+    let svg = fs::read_to_string(&out).unwrap();
+    assert!(!svg.contains("Three-Body Orbit") && !svg.contains("Body 1"));
+
+    orbit_plot(&[
+        "-c",
+        path(&fig8_config(&dir, "format = \"pdf\"\nline_width = 2")),
+    ]);
+    let pdf = fs::read(&out).unwrap();
+    assert!(pdf.starts_with(b"%PDF-1.4\n") && pdf.ends_with(b"%%EOF\n"));
+    assert!(pdf.windows(9).any(|w| w == b"\n2 w 1 J "));
+
+    // The shape sphere goes through the same backends
+    for (format, magic) in [("svg", &b"<svg "[..]), ("pdf", b"%PDF-1.4")] {
+        let extra = format!("projection = \"shape_sphere\"\nformat = \"{format}\"");
+        orbit_plot(&["-c", path(&fig8_config(&dir, &extra))]);
+        assert!(fs::read(&out).unwrap().starts_with(magic));
+    }
+
+    for (extra, message) in [
+        ("format = \"jpg\"", "unknown image format"),
+        ("colors = [\"red\"]", "#rrggbb"),
+        ("line_width = 0.0", "line_width must be positive"),
+    ] {
+        let err = orbit_plot_fails(&["-c", path(&fig8_config(&dir, extra))]);
+        assert!(err.contains(message), "{err}");
+    }
+}
@@ -0,0 +1,58 @@
+// Shared fixtures of the integration tests.
+#![allow(dead_code)]
+
+use three_body::Body;
+
+/// Chenciner–Montgomery figure-eight (Simó's initial conditions).
+pub const FIG8_PERIOD: f64 = 6.32591398;
+
+pub fn fig8() -> Vec<Body> {
+    vec![
+        Body {
+            m: 1.0,
+            r: [-0.97000436, 0.24308753, 0.0],
+            v: [0.466203685, 0.43236573, 0.0],
+        },
+        Body {
+            m: 1.0,
+            r: [0.97000436, -0.24308753, 0.0],
+            v: [0.466203685, 0.43236573, 0.0],
+        },
+        Body {
+            m: 1.0,
+            r: [0.0, 0.0, 0.0],
+            v: [-0.93240737, -0.86473146, 0.0],
+        },
+    ]
+}
+
+/// Phase-space distance |y(a) - y(b)|.
+pub fn distance(a: &[Body], b: &[Body]) -> f64 {
+    a.iter()
+        .zip(b)
+        .flat_map(|(a, b)| (0..3).flat_map(move |k| [a.r[k] - b.r[k], a.v[k] - b.v[k]]))
+        .map(|d| d * d)
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// A non-planar system of unequal masses, collision-free for the first few time units.
+pub fn unequal() -> Vec<Body> {
+    vec![
+        Body {
+            m: 1.0,
+            r: [-1.0, 0.0, 0.1],
+            v: [0.1, -0.3, 0.05],
+        },
+        Body {
+            m: 0.8,
+            r: [1.0, 0.2, 0.0],
+            v: [-0.2, 0.4, 0.0],
+        },
+        Body {
+            m: 0.5,
+            r: [0.0, 0.9, -0.2],
+            v: [0.3, 0.1, -0.1],
+        },
+    ]
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use common::{FIG8_PERIOD, fig8};
+use three_body::{ContinuationOptions, Parameter, continue_family};
+
+#[test]
+fn energy_family_of_the_figure_eight_is_its_rescaling() {
+    let options = ContinuationOptions {
+        parameter: Parameter::Energy,
+        max_points: 4,
+        ..ContinuationOptions::default()
+    };
+    let family = continue_family(&fig8(), FIG8_PERIOD, &options).unwrap();
+    assert_eq!(family.points.len(), 4, "{}", family.stop_reason);
+
+    // Kepler scaling: T |E|^(3/2) is the same along the family
+    let first = &family.points[0];
+    let scaled = |p: &three_body::FamilyPoint| p.period * p.energy.abs().powf(1.5);
+    for (i, p) in family.points.iter().enumerate() {
+        assert!(
+            (p.parameter - p.energy).abs() < 1e-10,
+            "{} {}",
+            p.parameter,
+            p.energy
+        );
+        assert!(p.residual < 1e-9, "point {i}: residual {}", p.residual);
+        assert!(p.stable && p.event.is_none(), "point {i}");
+        assert!((scaled(p) - scaled(first)).abs() < 1e-8, "point {i}");
+        assert!(p.angular_momentum.abs() < 1e-10);
+    }
+    assert!(family.points[3].energy > first.energy);
+}
+
+#[test]
+fn mass_family_keeps_the_energy() {
+    let options = ContinuationOptions {
+        parameter: Parameter::Mass(2),
+        max_points: 3,
+        ..ContinuationOptions::default()
+    };
+    let family = continue_family(&fig8(), FIG8_PERIOD, &options).unwrap();
+    assert_eq!(family.points.len(), 3, "{}", family.stop_reason);
+    let e0 = family.points[0].energy;
+    for p in &family.points {
+        assert_eq!(p.parameter, p.bodies[2].m);
+        assert!((p.energy - e0).abs() < 1e-9);
+        assert!(p.residual < 1e-9);
+    }
+    assert!(family.points[1].parameter > 1.0);
+}
+
+#[test]
+fn bad_options_are_rejected() {
+    let options = ContinuationOptions {
+        parameter: Parameter::Mass(3),
+        ..ContinuationOptions::default()
+    };
+    assert!(continue_family(&fig8(), FIG8_PERIOD, &options).is_err());
+    let options = ContinuationOptions {
+        step: 0.0,
+        ..ContinuationOptions::default()
+    };
+    assert!(continue_family(&fig8(), FIG8_PERIOD, &options).is_err());
+}
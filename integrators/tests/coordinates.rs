@@ -0,0 +1,89 @@
+mod common;
+
+use common::{fig8, unequal};
+use three_body::{
+    Body, Hyperspherical, Jacobi, Method, jacobi_vectors, kinetic_energy, moment_of_inertia,
+    reduced_masses,
+};
+
+fn same_bodies(a: &[Body], b: &[Body], tol: f64) -> bool {
+    a.iter().zip(b).all(|(a, b)| {
+        a.m == b.m
+            && a.r.iter().zip(b.r).all(|(x, y)| (x - y).abs() < tol)
+            && a.v.iter().zip(b.v).all(|(x, y)| (x - y).abs() < tol)
+    })
+}
+
+/// The figure-eight a while after its start, where the hyperspherical angles are regular
+/// (it starts with body 3 at the centre of mass of 1 and 2, α = 0).
+fn planar() -> Vec<Body> {
+    let mut bodies = fig8();
+    Method::Dop853.evolve(&mut bodies, 1.0, None);
+    bodies
+}
+
+fn sq(v: [f64; 3]) -> f64 {
+    v.iter().map(|x| x * x).sum()
+}
+
+#[test]
+fn round_trips() {
+    for bodies in [unequal(), planar()] {
+        let j = Jacobi::from_bodies(&bodies).unwrap();
+        assert!(same_bodies(&j.to_bodies(), &bodies, 1e-14));
+        let h = Hyperspherical::from_bodies(&bodies).unwrap();
+        assert!(same_bodies(&h.to_bodies(), &bodies, 1e-12));
+    }
+}
+
+#[test]
+fn kinetic_energy_separates() {
+    let bodies = unequal();
+    let j = Jacobi::from_bodies(&bodies).unwrap();
+    let total: f64 = j.masses.iter().sum();
+    let [mu1, mu2] = reduced_masses(&j.masses);
+    let jacobi =
+        sq(j.momentum) / (2.0 * total) + sq(j.p_rho) / (2.0 * mu1) + sq(j.p_lambda) / (2.0 * mu2);
+    assert!((jacobi - kinetic_energy(&bodies)).abs() < 1e-14);
+
+    let h = Hyperspherical::from_jacobi(&j);
+    let r2 = h.radius * h.radius;
+    let (sa, ca) = h.alpha.sin_cos();
+    let hyper = sq(h.momentum) / (2.0 * total)
+        + 0.5
+            * (h.p_radius * h.p_radius
+                + h.p_alpha * h.p_alpha / r2
+                + (h.p_theta[0].powi(2) + (h.p_phi[0] / h.theta[0].sin()).powi(2))
+                    / (r2 * ca * ca)
+                + (h.p_theta[1].powi(2) + (h.p_phi[1] / h.theta[1].sin()).powi(2))
+                    / (r2 * sa * sa));
+    assert!((hyper - kinetic_energy(&bodies)).abs() < 1e-13);
+    // R² is the moment of inertia about the centre of mass
+    assert!((r2 - moment_of_inertia(&bodies)).abs() < 1e-14);
+}
+
+#[test]
+fn mass_weighted_vectors_are_the_shape_sphere_ones() {
+    let bodies = unequal();
+    let j = Jacobi::from_bodies(&bodies).unwrap();
+    let [mu1, mu2] = reduced_masses(&j.masses);
+    let positions = [bodies[0].r, bodies[1].r, bodies[2].r];
+    let [x, y] = jacobi_vectors(&positions, &j.masses);
+    for k in 0..3 {
+        assert!((x[k] - mu1.sqrt() * j.rho[k]).abs() < 1e-15);
+        assert!((y[k] - mu2.sqrt() * j.lambda[k]).abs() < 1e-15);
+    }
+}
+
+#[test]
+fn planar_orbits_lie_on_the_equator() {
+    let h = Hyperspherical::from_bodies(&planar()).unwrap();
+    let half_pi = std::f64::consts::FRAC_PI_2;
+    assert!(h.theta.iter().all(|t| (t - half_pi).abs() < 1e-15));
+    assert!(h.p_theta.iter().all(|p| p.abs() < 1e-15));
+    assert!(h.alpha > 0.0 && h.alpha < half_pi);
+    // The figure-eight starts at α = 0
+    assert!(Hyperspherical::from_bodies(&fig8()).unwrap().alpha.abs() < 1e-8);
+
+    assert!(Jacobi::from_bodies(&fig8()[..2]).is_err());
+}
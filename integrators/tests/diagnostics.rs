@@ -0,0 +1,72 @@
+mod common;
+
+use common::{FIG8_PERIOD, fig8, unequal};
+use three_body::{
+    Invariants, Method, centre_of_mass, diagnose, moment_of_inertia, total_angular_momentum,
+    total_linear_momentum, virial_ratio,
+};
+
+fn close(a: [f64; 3], b: [f64; 3], tol: f64) -> bool {
+    a.iter().zip(b).all(|(x, y)| (x - y).abs() < tol)
+}
+
+#[test]
+fn invariants_of_unequal_masses() {
+    let bodies = unequal();
+    // Σ m r × v; the unweighted Σ r × v would be (-0.04, 0, 0.47)
+    assert!(close(
+        total_angular_momentum(&bodies),
+        [-0.005, 0.03, 0.517],
+        1e-15
+    ));
+    assert!(close(
+        total_linear_momentum(&bodies),
+        [0.09, 0.07, 0.0],
+        1e-15
+    ));
+
+    let (r, v) = centre_of_mass(&bodies);
+    assert!(close(r, [-0.2 / 2.3, 0.61 / 2.3, 0.0], 1e-15));
+    assert!(close(v, [0.09 / 2.3, 0.07 / 2.3, 0.0], 1e-15));
+    // Σ m |r|² - M |R|²
+    let inertia = 1.01 + 0.8 * 1.04 + 0.5 * 0.85 - 2.3 * (r[0] * r[0] + r[1] * r[1]);
+    assert!((moment_of_inertia(&bodies) - inertia).abs() < 1e-14);
+}
+
+#[test]
+fn figure_eight_invariants_hold_along_the_orbit() {
+    let d = diagnose(&fig8(), FIG8_PERIOD, Method::Dop853, None, 20).unwrap();
+    assert_eq!(d.series.len(), 21);
+    assert!((d.t_end - FIG8_PERIOD).abs() < 1e-12);
+    let drift = d.drift;
+    assert!(drift.energy < 1e-9, "{drift:?}");
+    assert!(drift.momentum < 1e-12 && drift.centre < 1e-12, "{drift:?}");
+    assert!(
+        drift.angular_momentum < 1e-9 && drift.sundman < 1e-9,
+        "{drift:?}"
+    );
+    // L = 0, so c²E = 0; the virial ratio averages to 1 over the period
+    assert!(d.series[0].sundman.abs() < 1e-15);
+    let mean: f64 = d.series[1..].iter().map(|s| s.virial).sum::<f64>() / 20.0;
+    assert!((mean - 1.0).abs() < 1e-2, "{mean}");
+    assert_eq!(d.series[0].virial, virial_ratio(&fig8()));
+}
+
+#[test]
+fn drifts_tell_integrators_apart() {
+    let bodies = unequal();
+    let dop853 = diagnose(&bodies, 1.5, Method::Dop853, None, 10)
+        .unwrap()
+        .drift;
+    let bs32 = diagnose(&bodies, 1.5, Method::Bs32, None, 10)
+        .unwrap()
+        .drift;
+    assert!(dop853.energy < bs32.energy, "{dop853:?} {bs32:?}");
+    // Uniform motion of the centre of mass: R - V t stays put
+    let first = Invariants::of(&bodies, 0.0);
+    assert!(close(first.centre, centre_of_mass(&bodies).0, 1e-15));
+    assert!(dop853.centre < 1e-12);
+
+    assert!(diagnose(&bodies, 1.5, Method::Dop853, None, 0).is_err());
+    assert!(diagnose(&[], 1.5, Method::Dop853, None, 10).is_err());
+}
@@ -0,0 +1,139 @@
+mod common;
+
+use common::fig8;
+use three_body::{Method, Table, total_energy};
+
+fn small() -> Table {
+    Table {
+        columns: vec!["t".to_string(), "x1".to_string()],
+        rows: vec![vec![0.0, 1.5], vec![0.5, -2.0]],
+    }
+}
+
+fn u16_at(bytes: &[u8], i: usize) -> usize {
+    u16::from_le_bytes([bytes[i], bytes[i + 1]]) as usize
+}
+
+fn u32_at(bytes: &[u8], i: usize) -> usize {
+    u32::from_le_bytes(bytes[i..i + 4].try_into().unwrap()) as usize
+}
+
+#[test]
+fn trajectory_columns_and_rows() {
+    let states = Method::Dop853.sample(&fig8(), 1.0, None, 4);
+    let table = Table::trajectory(&states, true, true);
+    assert_eq!(table.columns.len(), 1 + 3 * 6 + 13);
+    assert_eq!(
+        &table.columns[..8],
+        ["t", "x1", "y1", "z1", "vx1", "vy1", "vz1", "x2"]
+    );
+    assert_eq!(table.columns[19], "energy");
+    assert_eq!(table.columns.last().unwrap(), "sundman");
+    assert_eq!(table.rows.len(), 5);
+    assert_eq!(
+        table.rows[0][..7],
+        [
+            0.0,
+            -0.97000436,
+            0.24308753,
+            0.0,
+            0.466203685,
+            0.43236573,
+            0.0
+        ]
+    );
+    assert_eq!(table.rows[4][0], 1.0);
+    for row in &table.rows {
+        assert!((row[19] - total_energy(&fig8())).abs() < 1e-10);
+    }
+
+    let table = Table::trajectory(&states, false, false);
+    assert_eq!(
+        table.columns,
+        ["t", "x1", "y1", "z1", "x2", "y2", "z2", "x3", "y3", "z3"]
+    );
+}
+
+#[test]
+fn positions_of_an_evolve_history() {
+    let mut bodies = fig8();
+    let (series, _) = Method::Rk4.evolve(&mut bodies, 0.1, None);
+    let table = Table::positions(&series, 3);
+    assert_eq!(table.rows.len(), series.len() / 10);
+    assert_eq!(table.rows[1][0], series[19]);
+    assert_eq!(table.rows[1][1..], series[10..19]);
+}
+
+#[test]
+fn csv_and_json() {
+    assert_eq!(small().to_csv(), "t,x1\n0.0,1.5\n0.5,-2.0\n");
+    let mut table = small();
+    table.rows[1][1] = f64::NAN;
+    assert_eq!(
+        table.to_json(),
+        "{\n  \"columns\": [\"t\", \"x1\"],\n  \"data\": [\n    [0.0, 1.5],\n    [0.5, null]\n  ]\n}\n"
+    );
+}
+
+#[test]
+fn npy_header_layout() {
+    let npy = small().to_npy();
+    assert_eq!(&npy[..8], b"\x93NUMPY\x01\x00");
+    let len = u16_at(&npy, 8);
+    assert_eq!((10 + len) % 64, 0);
+    let header = std::str::from_utf8(&npy[10..10 + len]).unwrap();
+    assert!(header.ends_with(" \n"));
+    assert_eq!(
+        header.trim_end(),
+        "{'descr': [('t', '<f8'), ('x1', '<f8')], 'fortran_order': False, 'shape': (2,), }"
+    );
+    // Rows of little-endian float64s
+    let data: Vec<f64> = npy[10 + len..]
+        .chunks_exact(8)
+        .map(|b| f64::from_le_bytes(b.try_into().unwrap()))
+        .collect();
+    assert_eq!(data, [0.0, 1.5, 0.5, -2.0]);
+}
+
+#[test]
+fn npz_archive_layout() {
+    let table = Table {
+        columns: vec!["t".to_string()],
+        rows: vec![vec![1.0], vec![2.0]],
+    };
+    let npz = table.to_npz();
+    // Local file header: stored, CRC-32 and sizes of the 144-byte `t.npy`
+    assert_eq!(u32_at(&npz, 0), 0x0403_4b50);
+    assert_eq!(u16_at(&npz, 8), 0);
+    assert_eq!(u32_at(&npz, 14), 0xbfaa_5b64);
+    assert_eq!(u32_at(&npz, 18), 144);
+    assert_eq!(u32_at(&npz, 22), 144);
+    assert_eq!(u16_at(&npz, 26), 5);
+    assert_eq!(&npz[30..35], b"t.npy");
+    let npy = &npz[35..35 + 144];
+    assert_eq!(&npy[..10], b"\x93NUMPY\x01\x00\x76\x00");
+    assert_eq!(
+        std::str::from_utf8(&npy[10..128]).unwrap().trim_end(),
+        "{'descr': '<f8', 'fortran_order': False, 'shape': (2,), }"
+    );
+    assert_eq!(npy[128..136], 1.0f64.to_le_bytes());
+
+    // Central directory entry, then the end record pointing at it
+    let central = 35 + 144;
+    assert_eq!(u32_at(&npz, central), 0x0201_4b50);
+    assert_eq!(u32_at(&npz, central + 42), 0);
+    let end = npz.len() - 22;
+    assert_eq!(u32_at(&npz, end), 0x0605_4b50);
+    assert_eq!(u16_at(&npz, end + 10), 1);
+    assert_eq!(u32_at(&npz, end + 12), end - central);
+    assert_eq!(u32_at(&npz, end + 16), central);
+
+    assert_eq!(
+        small()
+            .to_npz()
+            .windows(6)
+            .filter(|w| w.ends_with(b".npy"))
+            .count(),
+        4
+    );
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use common::unequal;
+use three_body::{Body, Method};
+
+fn momentum(bodies: &[Body]) -> [f64; 3] {
+    let mut p = [0.0; 3];
+    for b in bodies {
+        for (p, v) in p.iter_mut().zip(b.v) {
+            *p += b.m * v;
+        }
+    }
+    p
+}
+
+#[test]
+fn pairwise_forces_conserve_momentum() {
+    // Every pair is applied to both bodies with opposite signs, so only rounding remains
+    let p0 = momentum(&unequal());
+    for method in [Method::Rk4, Method::Verlet, Method::Dopri5, Method::Dop853] {
+        let mut bodies = unequal();
+        let (_, t) = method.evolve(&mut bodies, 2.0, None);
+        assert_eq!(t, 2.0, "{}", method.name());
+        let p = momentum(&bodies);
+        for k in 0..3 {
+            assert!(
+                (p[k] - p0[k]).abs() < 1e-12,
+                "{}: {p:?} vs {p0:?}",
+                method.name()
+            );
+        }
+    }
+}
+
+#[test]
+fn repeated_runs_are_identical() {
+    // Workspaces are per run: nothing carries over from one evolve to the next
+    for method in [Method::Rk4, Method::Verlet, Method::Dopri5, Method::Bs32] {
+        let runs: Vec<_> = (0..2)
+            .map(|_| {
+                let mut bodies = unequal();
+                method.evolve(&mut bodies, 0.5, None)
+            })
+            .collect();
+        assert_eq!(runs[0], runs[1], "{}", method.name());
+    }
+}
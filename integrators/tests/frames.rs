@@ -0,0 +1,78 @@
+mod common;
+
+use common::{FIG8_PERIOD, fig8, unequal};
+use three_body::{Body, Frame, Method, to_frame};
+
+/// Equal masses on an equilateral triangle of side 1, turning rigidly at Ω = √3.
+fn lagrange() -> Vec<Body> {
+    let omega = 3f64.sqrt();
+    let radius = 1.0 / 3f64.sqrt();
+    (0..3)
+        .map(|k| {
+            let (s, c) = (2.0 * std::f64::consts::PI * k as f64 / 3.0).sin_cos();
+            Body {
+                m: 1.0,
+                r: [radius * c, radius * s, 0.0],
+                v: [-omega * radius * s, omega * radius * c, 0.0],
+            }
+        })
+        .collect()
+}
+
+fn samples(frame: &[f64]) -> Vec<&[f64]> {
+    frame.chunks_exact(10).collect()
+}
+
+#[test]
+fn lagrange_orbit_stands_still_in_the_rotating_frame() {
+    let mut bodies = lagrange();
+    let (series, _) = Method::Dop853.evolve(&mut bodies, 2.0, None);
+    let frame = to_frame(&series, &[1.0; 3], Frame::Rotating { rate: 3f64.sqrt() }).unwrap();
+    let start = &frame[..9];
+    for s in samples(&frame) {
+        assert!(s[..9].iter().zip(start).all(|(a, b)| (a - b).abs() < 1e-8));
+    }
+    assert_eq!(
+        to_frame(&series, &[1.0; 3], Frame::Inertial).unwrap(),
+        series
+    );
+}
+
+#[test]
+fn pair_frame_puts_the_pair_on_the_x_axis() {
+    let masses = [1.0, 0.8, 0.5];
+    let mut bodies = unequal();
+    let (series, _) = Method::Dop853.evolve(&mut bodies, 1.5, None);
+    let frame = to_frame(&series, &masses, Frame::Pair(2, 0)).unwrap();
+    for s in samples(&frame) {
+        // Body 3 to body 1 along +x, centre of mass at the origin
+        assert!((s[7] - s[1]).abs() < 1e-12 && s[0] > s[6]);
+        for k in 0..2 {
+            let centre: f64 = (0..3).map(|i| masses[i] * s[3 * i + k]).sum();
+            assert!(centre.abs() < 1e-12);
+        }
+    }
+    assert!(to_frame(&series, &masses, Frame::Pair(1, 1)).is_err());
+    assert!(to_frame(&series, &masses, Frame::Pair(0, 3)).is_err());
+}
+
+#[test]
+fn principal_axes_frame_diagonalizes_the_inertia_tensor() {
+    let mut bodies = fig8();
+    let (series, _) = Method::Dop853.evolve(&mut bodies, FIG8_PERIOD, None);
+    let frame = to_frame(&series, &[1.0; 3], Frame::PrincipalAxes).unwrap();
+    // The axis keeps its sign from step to step: no half-turn jumps of the positions
+    let mut jump: f64 = 0.0;
+    for w in samples(&frame).windows(2) {
+        let d: f64 = (0..9).map(|k| (w[1][k] - w[0][k]).powi(2)).sum();
+        jump = jump.max(d.sqrt());
+    }
+    assert!(jump < 0.2, "{jump}");
+    for s in samples(&frame) {
+        let (sxx, syy, sxy) = (0..3).fold((0.0, 0.0, 0.0), |(xx, yy, xy), i| {
+            let (x, y) = (s[3 * i], s[3 * i + 1]);
+            (xx + x * x, yy + y * y, xy + x * y)
+        });
+        assert!(sxy.abs() < 1e-12 && sxx >= syy);
+    }
+}
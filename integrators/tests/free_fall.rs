@@ -0,0 +1,44 @@
+use three_body::{FreeFallOptions, find_brake, in_domain, solve_brake_orbit};
+
+// First row of docs/examples/sd_80.csv: x, y, T, T |E|^(3/2). Most of the published
+// orbits pass too close to collisions to be reproduced in f64; this one does not.
+const SD_ROW: [f64; 4] = [
+    0.002_685_365_687_918_609_5,
+    0.420_513_923_413_463_2,
+    1.780_391_533_028_383_6,
+    14.571_737_413_553_345,
+];
+
+#[test]
+fn published_brake_orbit_is_a_fixed_point() {
+    let [x, y, period, scaled] = SD_ROW;
+    let options = FreeFallOptions::default();
+    let orbit = solve_brake_orbit(x, y, period / 2.0, &options).unwrap();
+    assert!(orbit.residual <= options.tol);
+    assert!((orbit.x - x).abs() < 1e-7 && (orbit.y - y).abs() < 1e-7);
+    assert!((orbit.period() - period).abs() < 1e-7, "{}", orbit.period());
+    assert!((orbit.scaled_period() - scaled).abs() < 1e-6);
+}
+
+#[test]
+fn brake_point_is_found_and_polished_from_nearby() {
+    let [x, y, period, _] = SD_ROW;
+    let options = FreeFallOptions::default();
+    let (x0, y0) = (x + 1e-5, y - 1e-5);
+    let (tau, kinetic) = find_brake(x0, y0, &options).unwrap();
+    assert!(kinetic <= options.max_kinetic);
+    assert!((tau - period / 2.0).abs() < 1e-2, "τ = {tau}");
+    let orbit = solve_brake_orbit(x0, y0, tau, &options).unwrap();
+    assert!((orbit.x - x).abs() < 1e-6 && (orbit.y - y).abs() < 1e-6);
+    assert!((orbit.period() - period).abs() < 1e-6);
+}
+
+#[test]
+fn agekyan_anosova_domain() {
+    assert!(in_domain(0.0, 0.0));
+    assert!(in_domain(0.5, 0.0));
+    assert!(in_domain(SD_ROW[0], SD_ROW[1]));
+    assert!(!in_domain(-0.1, 0.3));
+    assert!(!in_domain(0.3, -0.1));
+    assert!(!in_domain(0.4, 0.5));
+}
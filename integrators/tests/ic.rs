@@ -0,0 +1,110 @@
+mod common;
+
+use common::{FIG8_PERIOD, distance, fig8};
+use three_body::{Body, Method, centre_of_mass, ic, total_energy, total_linear_momentum};
+
+fn closure(bodies: &[Body], period: f64) -> f64 {
+    let mut end = bodies.to_vec();
+    Method::Dop853.evolve(&mut end, period, None);
+    distance(&end, bodies)
+}
+
+fn at_rest_in_the_centre_of_mass_frame(bodies: &[Body]) {
+    let (r, v) = centre_of_mass(bodies);
+    for k in 0..3 {
+        assert!(total_linear_momentum(bodies)[k].abs() < 1e-15);
+        assert!(r[k].abs() < 1e-15 && v[k].abs() < 1e-15);
+    }
+}
+
+#[test]
+fn suvakov_figure_eight() {
+    // I.A^i.c.1 of the equal-mass list: the figure-eight seen from its Euler configuration
+    let bodies = ic::suvakov(0.3471168881, 0.5327249454, 1.0);
+    assert_eq!(bodies[1].r, [0.0; 3]);
+    assert_eq!(bodies[1].v, [-0.6942337762, -1.0654498908, 0.0]);
+    at_rest_in_the_centre_of_mass_frame(&bodies);
+    assert!((total_energy(&bodies) - total_energy(&fig8())).abs() < 1e-8);
+    assert!(closure(&bodies, FIG8_PERIOD) < 1e-7);
+
+    // Unequal m3 keeps the momentum balanced
+    let bodies = ic::suvakov(0.2, 0.3, 0.5);
+    assert_eq!(bodies[1].v, [-0.8, -1.2, 0.0]);
+    at_rest_in_the_centre_of_mass_frame(&bodies);
+}
+
+#[test]
+fn li_liao_3d_orbit_closes() {
+    // O_1(0.1) of data.csv
+    let bodies = ic::li_liao_3d(
+        5.78304539968228E-01,
+        2.99949028598560E-02,
+        3.53757085402933E-02,
+        1.66254073966839E-02,
+        0.1,
+    );
+    assert_eq!(bodies[2].m, 0.1);
+    assert_eq!(bodies[0].v[2], -bodies[1].v[2]);
+    for k in 0..3 {
+        assert!(total_linear_momentum(&bodies)[k].abs() < 1e-15);
+    }
+    // Not centred: the third body starts off the plane of the others
+    let (r, _) = centre_of_mass(&bodies);
+    assert!((r[2] - 0.1 * 5.78304539968228E-01 / 2.1).abs() < 1e-15);
+    assert!(closure(&bodies, 8.59173325042564) < 1e-8);
+}
+
+#[test]
+fn broucke_orbit_closes() {
+    // Solution 1 of broucke_boggs.js, listed with half its period
+    let bodies = ic::broucke([1.0 / 3.0; 3], 1.003649, 1.263550, -1.232358, 0.794760);
+    at_rest_in_the_centre_of_mass_frame(&bodies);
+    assert!(bodies.iter().all(|b| b.r[1] == 0.0 && b.v[0] == 0.0));
+    assert!((bodies[0].r[0] - bodies[1].r[0] - (1.003649 + 1.232358)).abs() < 1e-15);
+    assert!(closure(&bodies, 2.0 * 6.061160) < 1e-4);
+}
+
+#[test]
+fn bhh_satellites_balance_momentum() {
+    // First row of bhh_satellites.js (periodic in a rotating frame, so no closure here)
+    let masses = [1.0124, 1.0, 0.9968];
+    let bodies = ic::bhh(masses, -1.32962, -0.88963, -0.28501);
+    at_rest_in_the_centre_of_mass_frame(&bodies);
+    assert_eq!(bodies[0].v[1], -0.88963);
+    assert_eq!(bodies[2].v[1], -0.28501);
+    assert!((bodies[2].r[0] - bodies[1].r[0] - 1.0).abs() < 1e-15);
+    assert!((bodies[1].r[0] - bodies[0].r[0] - 1.32962).abs() < 1e-15);
+}
+
+#[test]
+fn choreography_closes_after_three_shifts() {
+    // First row of choreographies.js: c5 is a third of the period
+    let [c1, c2, c3, c4, c5] = [
+        0.15460250151939,
+        -0.0987561640960160,
+        -1.1843704912618038,
+        0.2521819944674475,
+        0.55870630791976,
+    ];
+    let bodies = ic::choreography(c1, c2, c3, c4);
+    at_rest_in_the_centre_of_mass_frame(&bodies);
+    assert!(bodies.iter().all(|b| b.m == 1.0 / 3.0));
+    assert!(closure(&bodies, 3.0 * c5) < 1e-5);
+    // After one third the bodies have moved on to each other's places
+    assert!(closure(&bodies, c5) > 0.1);
+}
+
+#[test]
+fn free_fall_starts_at_rest() {
+    let bodies = ic::free_fall(0.2, 0.3, [1.0, 0.5, 2.0]);
+    let positions: Vec<_> = bodies.iter().map(|b| b.r).collect();
+    assert_eq!(
+        positions,
+        [[-0.5, 0.0, 0.0], [0.2, 0.3, 0.0], [0.5, 0.0, 0.0]]
+    );
+    assert_eq!(
+        bodies.iter().map(|b| b.m).collect::<Vec<_>>(),
+        [1.0, 0.5, 2.0]
+    );
+    assert!(bodies.iter().all(|b| b.v == [0.0; 3]));
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use common::{FIG8_PERIOD, distance, fig8};
+use three_body::{Method, total_energy};
+
+#[test]
+fn every_method_closes_the_figure_eight() {
+    for method in Method::ALL {
+        let mut bodies = fig8();
+        let (series, t) = method.evolve(&mut bodies, FIG8_PERIOD, None);
+        assert!(
+            (t - FIG8_PERIOD).abs() < 1e-12,
+            "{}: stopped at {t}",
+            method.name()
+        );
+        assert_eq!(series.len() % 10, 0);
+        let closure = distance(&bodies, &fig8());
+        // Bogacki–Shampine is third order and runs at looser default tolerances
+        let tol = if method == Method::Bs32 { 1e-4 } else { 1e-6 };
+        assert!(closure < tol, "{}: closure {closure:e}", method.name());
+        let drift = (total_energy(&bodies) / total_energy(&fig8()) - 1.0).abs();
+        assert!(drift < 1e-5, "{}: energy drift {drift:e}", method.name());
+    }
+}
+
+#[test]
+fn names_round_trip() {
+    for method in Method::ALL {
+        assert_eq!(method.name().parse::<Method>(), Ok(method));
+    }
+    assert!("rk45".parse::<Method>().is_err());
+}
+
+#[test]
+fn every_controller_closes_the_figure_eight() {
+    for name in ["i", "pi", "pid"] {
+        let mut settings = Method::Dopri5.default_settings().unwrap();
+        settings.controller = name.parse().unwrap();
+        let mut bodies = fig8();
+        let (_, t) = Method::Dopri5.evolve(&mut bodies, FIG8_PERIOD, Some(&settings));
+        assert_eq!(t, FIG8_PERIOD);
+        let closure = distance(&bodies, &fig8());
+        assert!(closure < 1e-6, "{name}: closure {closure:e}");
+    }
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use common::{FIG8_PERIOD, distance, fig8, unequal};
+use three_body::{
+    Method, ScaleTarget, centre_of_mass, moment_of_inertia, remove_net_momentum, rescale_to,
+    sundman_invariant, to_barycentric, total_energy, total_linear_momentum,
+};
+
+#[test]
+fn rescaled_figure_eight_closes_at_the_scaled_period() {
+    let mut bodies = fig8();
+    let scaling = rescale_to(&mut bodies, ScaleTarget::Energy(-0.5)).unwrap();
+    assert!((total_energy(&bodies) + 0.5).abs() < 1e-14);
+    assert!((scaling.energy() * total_energy(&fig8()) + 0.5).abs() < 1e-14);
+
+    // T ∝ |E|^(-3/2)
+    let period = scaling.period(FIG8_PERIOD);
+    let expected = FIG8_PERIOD * (total_energy(&fig8()) / -0.5).powf(1.5);
+    assert!((period - expected).abs() < 1e-12 * expected);
+    let mut end = bodies.clone();
+    Method::Dop853.evolve(&mut end, period, None);
+    assert!(distance(&end, &bodies) < 1e-6);
+}
+
+#[test]
+fn period_and_inertia_targets() {
+    let mut bodies = fig8();
+    let scaling = rescale_to(
+        &mut bodies,
+        ScaleTarget::Period {
+            current: FIG8_PERIOD,
+            target: 1.0,
+        },
+    )
+    .unwrap();
+    assert!((scaling.period(FIG8_PERIOD) - 1.0).abs() < 1e-14);
+    // E T^(2/3) and c²E do not change
+    let invariant = |b: &[three_body::Body], t: f64| total_energy(b) * t.powf(2.0 / 3.0);
+    assert!((invariant(&bodies, 1.0) - invariant(&fig8(), FIG8_PERIOD)).abs() < 1e-13);
+    assert!((sundman_invariant(&bodies) - sundman_invariant(&fig8())).abs() < 1e-13);
+
+    rescale_to(&mut bodies, ScaleTarget::Inertia(3.0)).unwrap();
+    assert!((moment_of_inertia(&bodies) - 3.0).abs() < 1e-13);
+}
+
+#[test]
+fn barycentric_frame() {
+    let mut bodies = unequal();
+    remove_net_momentum(&mut bodies);
+    assert!(
+        total_linear_momentum(&bodies)
+            .iter()
+            .all(|p| p.abs() < 1e-15)
+    );
+    assert_eq!(bodies[0].r, unequal()[0].r);
+
+    to_barycentric(&mut bodies);
+    let (r, v) = centre_of_mass(&bodies);
+    assert!(r.iter().chain(&v).all(|x| x.abs() < 1e-15));
+}
+
+#[test]
+fn unreachable_targets_are_rejected() {
+    let mut bodies = fig8();
+    assert!(rescale_to(&mut bodies, ScaleTarget::Energy(0.5)).is_err());
+    assert!(rescale_to(&mut bodies, ScaleTarget::Inertia(-1.0)).is_err());
+    let backwards = ScaleTarget::Period {
+        current: FIG8_PERIOD,
+        target: -1.0,
+    };
+    assert!(rescale_to(&mut bodies, backwards).is_err());
+    assert_eq!(bodies[0].r, fig8()[0].r);
+}
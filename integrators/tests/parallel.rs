@@ -0,0 +1,102 @@
+mod common;
+
+use std::{thread, time::Duration};
+
+use common::{fig8, unequal};
+use three_body::{BatchOptions, Body, Job, Method, par_map, run_batch, worker_count};
+
+#[test]
+fn par_map_keeps_input_order() {
+    let items: Vec<u64> = (0..32).collect();
+    for threads in [1, 4] {
+        let mut calls = Vec::new();
+        let results = par_map(
+            &items,
+            threads,
+            |&i| {
+                // Later items finish first
+                thread::sleep(Duration::from_millis(32 - i));
+                Ok(i * i)
+            },
+            |done, total| calls.push((done, total)),
+        );
+        let squares: Vec<u64> = results.into_iter().map(Result::unwrap).collect();
+        assert_eq!(squares, items.iter().map(|i| i * i).collect::<Vec<_>>());
+        assert_eq!(calls, (1..=32).map(|d| (d, 32)).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn errors_and_panics_stay_with_their_job() {
+    let items = [1, 0, 2, -1];
+    let results = par_map(
+        &items,
+        2,
+        |&i| match i {
+            0 => Err("zero".to_string()),
+            i if i < 0 => panic!("negative"),
+            i => Ok(10 / i),
+        },
+        |_, _| {},
+    );
+    assert_eq!(results[0], Ok(10));
+    assert_eq!(results[1], Err("zero".to_string()));
+    assert_eq!(results[2], Ok(5));
+    assert!(results[3].as_ref().unwrap_err().contains("negative"));
+}
+
+#[test]
+fn worker_counts() {
+    assert_eq!(worker_count(8, 3), 3);
+    assert_eq!(worker_count(2, 10), 2);
+    assert_eq!(worker_count(4, 0), 1);
+    assert!(worker_count(0, 100) >= 1);
+}
+
+#[test]
+fn batch_results_do_not_depend_on_the_thread_count() {
+    let mut bad = unequal();
+    bad[1].r[0] = f64::NAN;
+    let jobs = [
+        Job {
+            bodies: fig8(),
+            t_end: 1.0,
+        },
+        Job {
+            bodies: bad,
+            t_end: 1.0,
+        },
+        Job {
+            bodies: unequal(),
+            t_end: 2.0,
+        },
+        Job {
+            bodies: fig8(),
+            t_end: f64::INFINITY,
+        },
+    ];
+    let run = |threads| {
+        let options = BatchOptions {
+            method: Method::Dopri5,
+            settings: None,
+            threads,
+        };
+        run_batch(&jobs, &options, |_, _| {})
+    };
+    let (one, four) = (run(1), run(4));
+    for (a, b) in one.iter().zip(&four) {
+        match (a, b) {
+            (Ok(a), Ok(b)) => {
+                assert_eq!(a.series, b.series);
+                assert_eq!(a.t, b.t);
+            }
+            (Err(a), Err(b)) => assert_eq!(a, b),
+            _ => panic!("thread count changed the outcome"),
+        }
+    }
+    assert!(one[0].is_ok() && one[2].is_ok());
+    assert!(one[1].as_ref().unwrap_err().contains("not finite"));
+    assert!(one[3].as_ref().unwrap_err().contains("t_end"));
+    let end: &[Body] = &one[2].as_ref().unwrap().bodies;
+    assert_eq!(end.len(), 3);
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use common::{FIG8_PERIOD, fig8};
+use three_body::{Method, PeriodOptions, estimate_periods};
+
+#[test]
+fn figure_eight_period_not_a_multiple() {
+    let estimates = estimate_periods(&fig8(), &PeriodOptions::default()).unwrap();
+    let first = estimates[0];
+    assert!((first.period - FIG8_PERIOD).abs() < 1e-6, "{estimates:?}");
+    assert!(first.distance < 1e-8 && first.theta == 0.0);
+    // The multiples follow in order, then the half-period returns with the bodies swapped
+    let periods: Vec<f64> = estimates.iter().map(|e| e.period).collect();
+    for (k, p) in periods.iter().take(7).enumerate() {
+        assert!(
+            (p / FIG8_PERIOD - (k + 1) as f64).abs() < 1e-6,
+            "{periods:?}"
+        );
+    }
+    assert!(estimates[7].distance > 1.0);
+
+    let one = PeriodOptions {
+        count: 1,
+        ..PeriodOptions::default()
+    };
+    let estimates = estimate_periods(&fig8(), &one).unwrap();
+    assert_eq!(estimates.len(), 1);
+    assert!((estimates[0].period - FIG8_PERIOD).abs() < 1e-6);
+}
+
+#[test]
+fn periodic_orbits_return_without_rotation() {
+    let options = PeriodOptions {
+        rotation: true,
+        t_max: 8.0,
+        ..PeriodOptions::default()
+    };
+    let e = estimate_periods(&fig8(), &options).unwrap()[0];
+    assert!((e.period - FIG8_PERIOD).abs() < 1e-6 && e.theta.abs() < 1e-8);
+}
+
+#[test]
+fn needs_an_embedded_pair_and_a_return() {
+    let rk4 = PeriodOptions {
+        method: Method::Rk4,
+        ..PeriodOptions::default()
+    };
+    assert!(estimate_periods(&fig8(), &rk4).is_err());
+    let short = PeriodOptions {
+        t_max: 1.0,
+        ..PeriodOptions::default()
+    };
+    assert!(estimate_periods(&fig8(), &short).is_err());
+}
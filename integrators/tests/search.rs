@@ -0,0 +1,79 @@
+use three_body::{Axis, PeriodOptions, SearchOptions, estimate_periods, ic, scan_grid};
+
+fn suvakov(vx: f64, vy: f64) -> Vec<three_body::Body> {
+    ic::suvakov(vx, vy, 1.0)
+}
+
+#[test]
+fn close_returns_are_located_not_interpolated() {
+    // Near the figure-eight, where the Hermite interpolant of d² used to dip below zero
+    let vx = Axis {
+        min: 0.33,
+        max: 0.36,
+        steps: 16,
+    };
+    let vy = Axis {
+        min: 0.52,
+        max: 0.545,
+        steps: 16,
+    };
+    let options = SearchOptions {
+        t_max: 8.0,
+        ..SearchOptions::default()
+    };
+    let scan = scan_grid(suvakov, &vx, &vy, &options, |_, _| {}).unwrap();
+    assert!(
+        scan.points
+            .iter()
+            .all(|p| p.as_ref().unwrap().distance > 0.0)
+    );
+
+    let best = scan.candidates(3);
+    assert_eq!(best[0].params, [0.342, 0.535]);
+    assert!(
+        (best[0].distance - 7.269e-4).abs() < 1e-7,
+        "{}",
+        best[0].distance
+    );
+    assert!((best[0].time - 6.3021).abs() < 1e-4, "{}", best[0].time);
+
+    // Same minima as the period estimate, which always integrates to them
+    let periods = PeriodOptions {
+        t_min: options.t_min,
+        t_max: options.t_max,
+        ..PeriodOptions::default()
+    };
+    for c in &best {
+        let p = estimate_periods(&c.bodies, &periods).unwrap()[0];
+        assert!((c.distance - p.distance).abs() < 1e-9 * p.distance.max(1.0));
+        assert!((c.time - p.period).abs() < 1e-6);
+    }
+    assert!(best.windows(2).all(|w| w[0].distance <= w[1].distance));
+}
+
+#[test]
+fn axes_include_both_ends() {
+    let a = Axis {
+        min: -1.0,
+        max: 1.0,
+        steps: 5,
+    };
+    let values: Vec<f64> = (0..5).map(|i| a.value(i)).collect();
+    assert_eq!(values, [-1.0, -0.5, 0.0, 0.5, 1.0]);
+    let single = Axis { steps: 1, ..a };
+    assert_eq!(single.value(0), -1.0);
+}
+
+#[test]
+fn search_needs_an_embedded_pair() {
+    let a = Axis {
+        min: 0.3,
+        max: 0.3,
+        steps: 1,
+    };
+    let options = SearchOptions {
+        method: three_body::Method::Rk4,
+        ..SearchOptions::default()
+    };
+    assert!(scan_grid(suvakov, &a, &a, &options, |_, _| {}).is_err());
+}
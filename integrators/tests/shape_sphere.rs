@@ -0,0 +1,101 @@
+mod common;
+
+use common::{FIG8_PERIOD, fig8};
+use three_body::{
+    Method, collision_points, jacobi_vectors, lagrange_points, shape_point, shape_sphere,
+};
+
+fn norm(n: [f64; 3]) -> f64 {
+    n.iter().map(|x| x * x).sum::<f64>().sqrt()
+}
+
+#[test]
+fn special_points_of_equal_masses() {
+    let masses = [1.0; 3];
+    let [ccw, cw] = lagrange_points(&masses);
+    for (n, pole) in [(ccw, [0.0, 0.0, 1.0]), (cw, [0.0, 0.0, -1.0])] {
+        assert!(
+            n.iter().zip(pole).all(|(a, b)| (a - b).abs() < 1e-12),
+            "{n:?}"
+        );
+    }
+    // Binary collisions on the equator, 120° apart
+    let c = collision_points(&masses);
+    for i in 0..3 {
+        assert!((norm(c[i]) - 1.0).abs() < 1e-12 && c[i][2] == 0.0);
+        let j = (i + 1) % 3;
+        let cos = c[i][0] * c[j][0] + c[i][1] * c[j][1];
+        assert!((cos + 0.5).abs() < 1e-12, "{c:?}");
+    }
+}
+
+#[test]
+fn special_points_of_unequal_masses() {
+    let masses = [1.0, 0.8, 0.5];
+    let [ccw, cw] = lagrange_points(&masses);
+    assert!((norm(ccw) - 1.0).abs() < 1e-12 && (norm(cw) - 1.0).abs() < 1e-12);
+    assert!(ccw[2] > 0.0 && cw[2] < 0.0);
+    assert!((ccw[0] - cw[0]).abs() < 1e-12 && (ccw[2] + cw[2]).abs() < 1e-12);
+    for c in collision_points(&masses) {
+        assert!((norm(c) - 1.0).abs() < 1e-12 && c[2].abs() < 1e-15);
+    }
+}
+
+#[test]
+fn shape_forgets_position_orientation_and_size() {
+    let masses = [1.0, 0.8, 0.5];
+    let r = [[-1.0, 0.2, 0.0], [0.7, -0.3, 0.0], [0.1, 0.9, 0.0]];
+    let n = shape_point(&r, &masses).unwrap();
+    let (s, c) = 1.1f64.sin_cos();
+    let moved = r.map(|p| {
+        [
+            2.5 * (c * p[0] - s * p[1]) + 3.0,
+            2.5 * (s * p[0] + c * p[1]) - 1.0,
+            0.0,
+        ]
+    });
+    let m = shape_point(&moved, &masses).unwrap();
+    assert!(
+        n.iter().zip(m).all(|(a, b)| (a - b).abs() < 1e-12),
+        "{n:?} {m:?}"
+    );
+    assert!(shape_point(&[[0.5, 0.5, 0.0]; 3], &masses).is_none());
+
+    // |ρ|² + |λ|² is the moment of inertia about the centre of mass
+    let [rho, lambda] = jacobi_vectors(&r, &masses);
+    let total: f64 = masses.iter().sum();
+    let c: Vec<f64> = (0..2)
+        .map(|k| (0..3).map(|i| masses[i] * r[i][k]).sum::<f64>() / total)
+        .collect();
+    let inertia: f64 = (0..3)
+        .map(|i| masses[i] * ((r[i][0] - c[0]).powi(2) + (r[i][1] - c[1]).powi(2)))
+        .sum();
+    let jacobi: f64 = rho.iter().chain(&lambda).map(|x| x * x).sum();
+    assert!((jacobi - inertia).abs() < 1e-12);
+}
+
+#[test]
+fn figure_eight_winds_around_the_equator() {
+    let mut bodies = fig8();
+    let (series, _) = Method::Dop853.evolve(&mut bodies, FIG8_PERIOD, None);
+    let curve = shape_sphere(&series, &[1.0; 3]);
+    assert_eq!(curve.len() / 4, series.len() / 10);
+    let points: Vec<&[f64]> = curve.chunks_exact(4).collect();
+    assert!(
+        points
+            .iter()
+            .all(|p| (norm([p[0], p[1], p[2]]) - 1.0).abs() < 1e-12)
+    );
+    // Starts and ends collinear (Euler configuration), with five more syzygies between
+    assert!(points[0][2].abs() < 1e-8 && points[points.len() - 1][2].abs() < 1e-6);
+    let crossings = points[1..points.len() - 1]
+        .windows(2)
+        .filter(|w| w[0][2].signum() != w[1][2].signum())
+        .count();
+    assert_eq!(crossings, 5);
+    // Equal excursions into both hemispheres (up to the step sampling)
+    let (lo, hi) = points
+        .iter()
+        .fold((0.0f64, 0.0f64), |(lo, hi), p| (lo.min(p[2]), hi.max(p[2])));
+    assert!(hi > 0.5 && (lo + hi).abs() < 1e-3, "{lo} {hi}");
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use bigdecimal::ToPrimitive;
+use common::{FIG8_PERIOD, distance, fig8};
+use three_body::{Method, ShootingOptions, refine_orbit, total_energy};
+
+#[test]
+fn newton_recovers_the_figure_eight() {
+    // Off the orbit by 1e-4, keeping the centre of mass and the momentum at zero
+    let mut guess = fig8();
+    guess[0].r[0] += 1e-4;
+    guess[1].r[0] -= 1e-4;
+    guess[0].v[1] += 1e-4;
+    guess[1].v[1] -= 1e-4;
+    let refined = refine_orbit(&guess, FIG8_PERIOD + 1e-3, &ShootingOptions::default()).unwrap();
+
+    assert!(refined.converged, "{:?}", refined.history);
+    assert!(refined.residual_before > 1e-5);
+    assert!(refined.residual_after < 1e-10, "{}", refined.residual_after);
+    assert!(refined.iterations <= 10);
+    // The energy is kept, and along the family T ∝ |E|^(-3/2)
+    assert!((total_energy(&refined.bodies) - total_energy(&guess)).abs() < 1e-10);
+    let period = refined.period.to_f64().unwrap();
+    let scaled = FIG8_PERIOD * (total_energy(&fig8()) / total_energy(&guess)).powf(1.5);
+    assert!(
+        (period - scaled).abs() < 1e-6,
+        "T = {period}, expected {scaled}"
+    );
+    assert!(distance(&refined.bodies, &fig8()) < 1e-2);
+
+    let mut end = refined.bodies.clone();
+    Method::Dop853.evolve(&mut end, period, None);
+    assert!(distance(&end, &refined.bodies) < 1e-8);
+}
+
+#[test]
+fn state_and_masses_must_agree() {
+    let bodies = fig8();
+    let state = vec![Default::default(); 12];
+    let err = three_body::refine_orbit_bd(
+        &state,
+        &bodies.iter().map(|b| b.m).collect::<Vec<_>>(),
+        &Default::default(),
+        &ShootingOptions::default(),
+    )
+    .unwrap_err();
+    assert!(err.contains("6 values per body"), "{err}");
+}
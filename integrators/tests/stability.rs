@@ -0,0 +1,54 @@
+mod common;
+
+use common::{FIG8_PERIOD, fig8};
+use three_body::{Method, eigenvalues, monodromy};
+
+#[test]
+fn eigenvalues_of_known_matrices() {
+    // Upper triangular: the diagonal, by decreasing modulus
+    let a = [2.0, 1.0, 5.0, 0.0, -3.0, 4.0, 0.0, 0.0, 0.5];
+    let ev = eigenvalues(&a, 3).unwrap();
+    for (&(re, im), want) in ev.iter().zip([-3.0, 2.0, 0.5]) {
+        assert!((re - want).abs() < 1e-12 && im.abs() < 1e-12, "{ev:?}");
+    }
+
+    // A rotation by θ: e^{±iθ}
+    let (s, c) = 0.7f64.sin_cos();
+    let ev = eigenvalues(&[c, -s, s, c], 2).unwrap();
+    for &(re, im) in &ev {
+        assert!(
+            (re - c).abs() < 1e-12 && (im.abs() - s).abs() < 1e-12,
+            "{ev:?}"
+        );
+    }
+    assert!(ev[0].1 * ev[1].1 < 0.0);
+
+    assert!(eigenvalues(&[1.0, 2.0, 3.0], 2).is_err());
+    assert!(eigenvalues(&[1.0, f64::NAN, 0.0, 1.0], 2).is_err());
+}
+
+#[test]
+fn figure_eight_is_linearly_stable() {
+    for method in [Method::Dop853, Method::Feagin14] {
+        let m = monodromy(&fig8(), FIG8_PERIOD, method, None).unwrap();
+        assert_eq!(m.dim, 18);
+        assert_eq!(m.multipliers.len(), 18);
+        assert!(m.stable, "{method}: max |λ| = {}", m.max_modulus);
+        // Every multiplier on the unit circle, the trivial ones at 1
+        for &(re, im) in &m.multipliers {
+            assert!((re.hypot(im) - 1.0).abs() < 1e-3, "{method}: {re} {im}i");
+        }
+        let ones = m
+            .multipliers
+            .iter()
+            .filter(|&&(re, im)| (re - 1.0).hypot(im) < 1e-2)
+            .count();
+        assert!(ones >= 8, "{method}: {ones} multipliers at 1");
+    }
+}
+
+#[test]
+fn only_dop853_and_feagin14_carry_the_variational_equations() {
+    let err = monodromy(&fig8(), FIG8_PERIOD, Method::Rk4, None).unwrap_err();
+    assert!(err.contains("rk4"), "{err}");
+}
@@ -0,0 +1,46 @@
+mod common;
+
+use common::{FIG8_PERIOD, fig8, unequal};
+use three_body::{SymmetryOptions, find_symmetries};
+
+#[test]
+fn figure_eight_has_the_full_group() {
+    let s = find_symmetries(&fig8(), FIG8_PERIOD, &SymmetryOptions::default()).unwrap();
+    assert_eq!(s.order(), 12, "{:#?}", s.ops);
+    assert!(s.is_choreography() && s.has_reflection() && s.has_time_reversal());
+    assert_eq!(
+        s.label(),
+        "order 12: choreography, reflection, time reversal"
+    );
+    assert!(s.ops.iter().all(|op| op.residual < 1e-6));
+
+    // Choreography: each body follows the next a third of a period later
+    let mut shifts: Vec<f64> = s
+        .ops
+        .iter()
+        .filter(|op| op.is_cyclic() && !op.time_reversal && op.is_translation(1e-6))
+        .map(|op| op.shift)
+        .collect();
+    shifts.sort_by(f64::total_cmp);
+    assert_eq!(shifts.len(), 2);
+    assert!((shifts[0] - 1.0 / 3.0).abs() < 1e-6 && (shifts[1] - 2.0 / 3.0).abs() < 1e-6);
+}
+
+#[test]
+fn unequal_masses_have_no_symmetry() {
+    let s = find_symmetries(&unequal(), 1.5, &SymmetryOptions::default()).unwrap();
+    assert_eq!(s.order(), 1, "{:#?}", s.ops);
+    assert_eq!(s.label(), "order 1");
+}
+
+#[test]
+fn bad_input_is_rejected() {
+    let options = SymmetryOptions::default();
+    assert!(find_symmetries(&[], 1.0, &options).is_err());
+    assert!(find_symmetries(&fig8(), -1.0, &options).is_err());
+    let coarse = SymmetryOptions {
+        samples: 1,
+        ..options
+    };
+    assert!(find_symmetries(&fig8(), FIG8_PERIOD, &coarse).is_err());
+}
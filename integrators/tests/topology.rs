@@ -0,0 +1,52 @@
+mod common;
+
+use common::{FIG8_PERIOD, fig8};
+use three_body::{
+    Method, canonical_word, classify, cyclic_reduce, inverse_word, reduce_word, syzygy_word,
+};
+
+#[test]
+fn free_group_words() {
+    assert_eq!(reduce_word("aAbB"), "");
+    assert_eq!(reduce_word("abBA"), "");
+    assert_eq!(reduce_word("abBa"), "aa");
+    assert_eq!(reduce_word("abAB"), "abAB");
+    assert_eq!(cyclic_reduce("baaB"), "aa");
+    assert_eq!(cyclic_reduce("BabAb"), "b");
+    assert_eq!(cyclic_reduce("aabAa"), "aab");
+    assert_eq!(inverse_word("abAB"), "baBA");
+    assert_eq!(inverse_word(""), "");
+    assert_eq!(reduce_word(&format!("abaB{}", inverse_word("abaB"))), "");
+}
+
+#[test]
+fn classes_ignore_rotation_inversion_and_conjugation() {
+    let class = canonical_word("abAB");
+    assert_eq!(class, "abAB");
+    for w in ["bABa", "ABab", "baBA", "BabAbB", "aabABA"] {
+        assert_eq!(canonical_word(w), class, "{w}");
+    }
+    assert_ne!(canonical_word("aabb"), class);
+    assert_eq!(canonical_word("aA"), "");
+}
+
+#[test]
+fn syzygy_sequences_read_as_words() {
+    // Figure-eight: 1, 2, 3 in the middle in turn, twice per period; 12 31 23 = a BA b
+    assert_eq!(syzygy_word(&[0, 1, 2, 0, 1, 2]), "aBAb");
+    assert_eq!(canonical_word("aBAb"), "abAB");
+    // Stutters cancel, odd sequences are read twice
+    assert_eq!(syzygy_word(&[0, 1, 1, 2]), syzygy_word(&[0, 2]));
+    assert_eq!(syzygy_word(&[0, 1, 2]), syzygy_word(&[0, 1, 2, 0, 1, 2]));
+}
+
+#[test]
+fn figure_eight_is_abab() {
+    let t = classify(&fig8(), FIG8_PERIOD, Method::Dop853, None).unwrap();
+    assert_eq!(t.syzygies.len(), 6);
+    assert_eq!(t.sequence().len(), 6);
+    assert_eq!(t.canonical, "abAB");
+    assert!(t.syzygies.windows(2).all(|w| w[0].t < w[1].t));
+
+    assert!(classify(&fig8()[..2], FIG8_PERIOD, Method::Dop853, None).is_err());
+}
@@ -1,6 +1,6 @@
 use wasm_bindgen::prelude::*;
 
-use three_body::{evolve_dop853, evolve_rk4, evolve_verlet, sum, Body};
+use three_body::{Body, Method, PeriodOptions, ic, sum};
 
 #[wasm_bindgen]
 pub fn evolve(data: &[f64], t: f64, method: &str) -> Result<Vec<f64>, String> {
@@ -25,16 +25,108 @@ pub fn evolve(data: &[f64], t: f64, method: &str) -> Result<Vec<f64>, String> {
         },
     ];
 
-    let r = match method {
-        "rk4" => evolve_rk4(&mut bodies, t),
-        "verlet" => evolve_verlet(&mut bodies, t),
-        "dop853" => evolve_dop853(&mut bodies, t),
-        "feagin14" => three_body::evolve_feagin14(&mut bodies, t),
-        _ => return Err(format!("Unknown method: {}", method)),
-    };
+    let method: Method = method.parse()?;
+    let r = method.evolve(&mut bodies, t, None);
     Ok(r.0)
 }
 
+/// Shape-sphere curve `[n1, n2, n3, t] * steps` of an `evolve` history, for the masses in
+/// `data` (same 21-element layout as `evolve`).
+#[wasm_bindgen]
+pub fn shape_sphere(series: &[f64], data: &[f64]) -> Result<Vec<f64>, String> {
+    if data.len() != 21 {
+        return Err("Data must contain exactly 21 elements".to_string());
+    }
+    Ok(three_body::shape_sphere(
+        series,
+        &[data[6], data[13], data[20]],
+    ))
+}
+
+/// Marked points of the shape sphere, `[x, y, z] * 5`: the Lagrange points L+ and L-, then
+/// the collisions r1 = r2, r2 = r3 and r3 = r1.
+#[wasm_bindgen]
+pub fn shape_sphere_points(masses: &[f64]) -> Result<Vec<f64>, String> {
+    let [m1, m2, m3] = masses[..] else {
+        return Err("Masses must contain exactly 3 elements".to_string());
+    };
+    let masses = [m1, m2, m3];
+    let lagrange = three_body::lagrange_points(&masses);
+    let collisions = three_body::collision_points(&masses);
+    Ok(lagrange
+        .iter()
+        .chain(&collisions)
+        .flatten()
+        .copied()
+        .collect())
+}
+
+/// Candidate periods of the initial conditions in `data` (same layout as `evolve`) up to
+/// `t_max`, best first, as `[period, theta, distance] * count`. With `rotation`, returns
+/// up to a rotation by theta about z are accepted (the examples' `theta_max`).
+#[wasm_bindgen]
+pub fn estimate_periods(
+    data: &[f64],
+    t_max: f64,
+    rotation: bool,
+    count: usize,
+) -> Result<Vec<f64>, String> {
+    if data.len() != 21 {
+        return Err("Data must contain exactly 21 elements".to_string());
+    }
+    let bodies: Vec<Body> = data
+        .chunks_exact(7)
+        .map(|b| Body {
+            r: [b[0], b[1], b[2]],
+            v: [b[3], b[4], b[5]],
+            m: b[6],
+        })
+        .collect();
+    let options = PeriodOptions {
+        t_max,
+        rotation,
+        count,
+        ..PeriodOptions::default()
+    };
+    let estimates = three_body::estimate_periods(&bodies, &options)?;
+    Ok(estimates
+        .iter()
+        .flat_map(|e| [e.period, e.theta, e.distance])
+        .collect())
+}
+
+/// Initial conditions of one of the catalog parametrizations (see `three_body::ic`), in the
+/// 21-element layout of `evolve`:
+///
+/// - `suvakov`: v1, v2, m3
+/// - `free_fall`: x, y, m1, m2, m3
+/// - `broucke`: m1, m2, m3, x1, v1, x2, v2
+/// - `bhh`: m1, m2, m3, x1, v1, v3
+/// - `li_liao_3d`: z0, vx, vy, vz, m3
+/// - `choreography`: c1, c2, c3, c4
+#[wasm_bindgen]
+pub fn initial_conditions(family: &str, params: &[f64]) -> Result<Vec<f64>, String> {
+    let bodies = match (family, params) {
+        ("suvakov", &[v1, v2, m3]) => ic::suvakov(v1, v2, m3),
+        ("free_fall", &[x, y, m1, m2, m3]) => ic::free_fall(x, y, [m1, m2, m3]),
+        ("broucke", &[m1, m2, m3, x1, v1, x2, v2]) => ic::broucke([m1, m2, m3], x1, v1, x2, v2),
+        ("bhh", &[m1, m2, m3, x1, v1, v3]) => ic::bhh([m1, m2, m3], x1, v1, v3),
+        ("li_liao_3d", &[z0, vx, vy, vz, m3]) => ic::li_liao_3d(z0, vx, vy, vz, m3),
+        ("choreography", &[c1, c2, c3, c4]) => ic::choreography(c1, c2, c3, c4),
+        ("suvakov" | "free_fall" | "broucke" | "bhh" | "li_liao_3d" | "choreography", _) => {
+            return Err(format!(
+                "Wrong number of parameters for {family}: {}",
+                params.len()
+            ));
+        }
+        _ => return Err(format!("Unknown parametrization: {family}")),
+    };
+    Ok(bodies
+        .iter()
+        .flat_map(|b| [b.r[0], b.r[1], b.r[2], b.v[0], b.v[1], b.v[2], b.m])
+        .collect())
+}
+
 #[wasm_bindgen]
 pub fn suma(a: &str, b: &str) -> String {
     sum(a, b)